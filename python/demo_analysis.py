@@ -1,8 +1,8 @@
 # Import the actual library.
 # Note I have to add it to the path python uses to import.
 
-# Do not change this name! Otherwise this won't work!
-if __name__ == "demoanalysis":
+# The executable loads this file as a module named after the file.
+if __name__ == "demo_analysis":
     import os, sys
     sys.path.append(os.getcwd() + "/python/tf2dal")
     # Pylance will say entities / events can't be resolved. Ignore.
@@ -59,16 +59,20 @@ class TickAnalysis:
         
     pass
 
-## MAIN FUNCTIONS -> DO NOT NAME CHANGE
+## MAIN FUNCTIONS -> registered in `register` below
 
 from numpy import float32
 
-# Called by the executable. DON'T CHANGE THIS FUNCTION'S NAME OR ARGUMENTS!
+# Called by the executable with the whole demo.
 def demo_analysis_main(data: demo.DemoData):
     print("We got there!")
     print(data.demo_filename)
+    return {
+        "metrics": {"kills": len(data.kills), "rounds": len(data.rounds)},
+        "annotations": [{"tick": int(kill.tick), "text": f"{kill.attacker_id} killed {kill.dead_id}"} for kill in data.kills],
+    }
 
-# Called by the executable. DON'T CHANGE THIS FUNCTION'S NAME OR ARGUMENTS!
+# Called by the executable with a single tick.
 def tick_analysis_main(tick: demo.TickData):
     print("Tick main")
     print(tick.tick)
@@ -83,8 +87,13 @@ def tick_analysis_main(tick: demo.TickData):
 # ) -> (List[(ent.Player, float32)], List[(ent.Player, float32)]) :
 #     pass
 
+# Called by the executable once, when this script is loaded.
+def register(registry):
+    registry.add_demo_analysis("demo_analysis_main", demo_analysis_main)
+    registry.add_tick_analysis("tick_analysis_main", tick_analysis_main)
+
 if __name__ == "__main__":
     round_data = dal.load_demo_rounds("../assets/demofile.dem")
     for i, data in enumerate(round_data):
         print(f"Round {i} has {len(data.rounds)} rounds:")
-        print(f"Start/End: {data.rounds[0].start_tick} - {data.rounds[0].end_tick} ({data.rounds[0].end_tick - data.rounds[0].start_tick} total)")
\ No newline at end of file
+        print(f"Start/End: {data.rounds[0].start_tick} - {data.rounds[0].end_tick} ({data.rounds[0].end_tick - data.rounds[0].start_tick} total)")
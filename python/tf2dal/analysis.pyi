@@ -1,49 +1,1912 @@
-from numpy import uint32, uint16, float32, uint8
-from typing import List, Dict
-from enum import Enum
+# Generated by stubgen from the Rust source. Don't edit this file, change
+# the pyclasses or their doc comments and run `cargo run -p stubgen` instead.
+
+"""Analysis that is done in Rust instead of Python.
+
+`TickData.analyze()` gives team averages, medic distances and groupings
+for a single tick, and `DemoData.analyze()` does the same for every tick
+of a demo or round, following each group from tick to tick.
+`DemoData.fights()` splits rounds into the fights that happened in them,
+and `DemoData.timeline()` follows the alive counts, trades and first pick
+of a round. `DemoData.critheals()` reports how crit heals were used, and
+`DemoData.medic_heals()` who each medic healed. `DemoData.heatmap()`
+counts where players were, got kills and died, and
+`DemoData.zone_occupancy()` who held each named zone of the map.
+`DemoData.respawns()` times respawns, waves and recoveries from wipes,
+and `DemoData.medic_deaths()` what was around medics when they died.
+`DemoData.ratings()` rates each player's impact against class baselines.
+A `Season` adds up players and teams over many demos."""
+
+from numpy import float32, int16, uint16, uint32, uint8
+from numpy.typing import NDArray
+from typing import Any, ClassVar, Dict, List, Tuple
+from .demo import DemoData
+from .entities import Player
+from .events import Kill
+from .game import Class, Round, Team, World
+from .identity import SteamId
+from .math import Vector
+
+def load_zones(path: str | None = None) -> Dict[str, List[Zone]]:
+    """The zones of every map in the `[zones]` section of the config at `path`,
+    or `demo_analysis.toml`, keyed by map name. A missing file has no zones."""
+    ...
+
+
+class GroupingType:
+    """What kind of group a player is in."""
+
+    None: ClassVar[GroupingType]
+    """Not valid."""
+
+    Isolated: ClassVar[GroupingType]
+    """(1) Only one player."""
+
+    IsolatedCombo: ClassVar[GroupingType]
+    """(2) Contains the medic and one other player."""
+
+    Combo: ClassVar[GroupingType]
+    """(3+) Contains the medic and at least two other players."""
+
+    Flank: ClassVar[GroupingType]
+    """(2+) Does not contain the medic, but has at least two players."""
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> GroupingType: ...
+    def __deepcopy__(self, memo: Any) -> GroupingType: ...
+
+
+class CritHealPlayer:
+    """Crit heals of a single player."""
+
+    @property
+    def user_id(self) -> int: ...
+
+    @property
+    def name(self) -> str: ...
+
+    @property
+    def team(self) -> Team: ...
+
+    @property
+    def alive_time(self) -> float:
+        """Seconds alive."""
+        ...
+
+    @property
+    def eligible_time(self) -> float:
+        """Seconds alive with any crit heals built up."""
+        ...
+
+    @property
+    def full_critheal_time(self) -> float:
+        """Seconds alive with full crit heals."""
+        ...
+
+    @property
+    def crit_healed_time(self) -> float:
+        """Seconds being healed by a medic with any crit heals built up."""
+        ...
+
+    @property
+    def wasted_time(self) -> float:
+        """Seconds with full crit heals and missing health while a medic
+        on their team was alive but not healing them."""
+        ...
+
+    @property
+    def wasted_healing(self) -> float:
+        """Estimated healing lost in `wasted_time`: what a medic could have
+        healed at the full crit rate, up to the health the player was missing."""
+        ...
+
+    @property
+    def healing_received(self) -> int:
+        """Healing received from medics."""
+        ...
+
+    @property
+    def crit_healing_received(self) -> int:
+        """Healing received from medics while at full crit heals."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> CritHealPlayer: ...
+    def __deepcopy__(self, memo: Any) -> CritHealPlayer: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class CritHealMedic:
+    """Who a single medic chose to heal."""
+
+    @property
+    def user_id(self) -> int: ...
+
+    @property
+    def name(self) -> str: ...
+
+    @property
+    def team(self) -> Team: ...
+
+    @property
+    def healing_time(self) -> float:
+        """Seconds spent healing a teammate."""
+        ...
+
+    @property
+    def choice_time(self) -> float:
+        """Seconds spent healing while another hurt teammate had more or less
+        crit heals than the patient."""
+        ...
+
+    @property
+    def low_critheal_time(self) -> float:
+        """Seconds of `choice_time` the patient had less crit heals than
+        another hurt teammate."""
+        ...
+
+    @property
+    def low_critheal_fraction(self) -> float:
+        """How often the medic healed the patient with less crit heals when
+        there was a choice. 0 if there never was."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> CritHealMedic: ...
+    def __deepcopy__(self, memo: Any) -> CritHealMedic: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class CritHealReport:
+    """The crit heals of everyone in a demo or round. Made by `DemoData.critheals`."""
+
+    @property
+    def players(self) -> List[CritHealPlayer]:
+        """Ordered by team, then user ID."""
+        ...
+
+    @property
+    def medics(self) -> List[CritHealMedic]:
+        """Ordered by team, then user ID."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> CritHealReport: ...
+    def __deepcopy__(self, memo: Any) -> CritHealReport: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
 
-from demo_analysis_lib import Team, Vector
-from demo_analysis_lib.entities import Player
 
 class TickPlayerData:
     """Analysis data for a single player during a single tick."""
+
     @property
-    def entity_id(self) -> uint32: ...
+    def entity_id(self) -> int: ...
+
     @property
-    def user_id(self) -> uint16: ...
+    def user_id(self) -> int: ...
+
     @property
-    def dist_from_medic(self) -> float32:
-        """Distance in XYZ from the medic on the team.
-        If the the player IS the medic the distance is ~0,
-        if the medic is dead, or the team has no medic, distance is -1."""
+    def group_id(self) -> int:
+        """The `id` of the player's group."""
+        ...
+
     @property
-    def dist_from_team_avg(self) -> float32:
+    def dist_from_team_avg(self) -> float:
         """Distance from the team average position XYZ.
         If the player is the only person on the team, distance is ~0."""
+        ...
+
     @property
-    def dist_from_group_avg(self) -> float32:
-        """Distance from the group average position XYZ.
-        If the player is the only person on the team, distance is ~0."""
+    def dist_from_group_avg(self) -> float:
+        """Distance from the average position XYZ of the player's group.
+        If the player is alone in their group, distance is ~0."""
+        ...
+
+    @property
+    def dist_from_medic(self) -> float:
+        """Distance in XYZ from the medic on the team.
+        If the player IS the medic the distance is ~0,
+        if the medic is dead, or the team has no medic, distance is -1."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> TickPlayerData: ...
+    def __deepcopy__(self, memo: Any) -> TickPlayerData: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
 
-class GroupingType(Enum):
-    NONE = 0 #Invalid group
-    ISOLATED = 1 #Only one player in group
-    ISOLATED_COMBO = 2 #Contains medic and only one other player
-    COMBO = 3 #Contains medic and at least 2 other players
-    FLANK = 4 #Contains at least 2 players but no medic
 
 class TickPlayerGrouping:
+    """Teammates that are close to each other."""
+
+    @property
+    def id(self) -> int:
+        """Stays the same from tick to tick while the group does.
+        Only `DemoData.analyze` follows groups across ticks."""
+        ...
+
+    @property
+    def previous_ids(self) -> List[int]:
+        """IDs of the groups on the previous tick that had any of these players.
+        More than one means groups merged; an ID that isn't `id` means this
+        group split off from it."""
+        ...
+
     @property
-    def group_type(self) -> GroupingType : ...
+    def group_type(self) -> GroupingType: ...
+
     @property
     def players(self) -> List[Player]: ...
+
     @property
     def avg_pos(self) -> Vector: ...
 
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> TickPlayerGrouping: ...
+    def __deepcopy__(self, memo: Any) -> TickPlayerGrouping: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
 class TickTeamAnalysis:
+    """One team during a single tick. Only living players are included."""
+
     @property
     def team(self) -> Team: ...
+
+    @property
+    def avg_position(self) -> Vector:
+        """The average position of the team's living players."""
+        ...
+
     @property
     def medic(self) -> Player | None:
         """If the team has no medic or their medic is dead,
-        returns None."""
\ No newline at end of file
+        this is None."""
+        ...
+
+    @property
+    def playerdata(self) -> Dict[int, TickPlayerData]:
+        """Keyed by user ID."""
+        ...
+
+    @property
+    def groupings(self) -> List[TickPlayerGrouping]: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> TickTeamAnalysis: ...
+    def __deepcopy__(self, memo: Any) -> TickTeamAnalysis: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class TickAnalysis:
+    """The analysis of a single tick. Made by `TickData.analyze`,
+    or for many ticks at once by `DemoData.analyze`."""
+
+    @property
+    def tick(self) -> int: ...
+
+    @property
+    def redteam(self) -> TickTeamAnalysis | None:
+        """None if the team has no living players."""
+        ...
+
+    @property
+    def bluteam(self) -> TickTeamAnalysis | None:
+        """None if the team has no living players."""
+        ...
+
+    def team(self, team: Team) -> TickTeamAnalysis | None:
+        """`redteam` or `bluteam`. None for any other team."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> TickAnalysis: ...
+    def __deepcopy__(self, memo: Any) -> TickAnalysis: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class FightConfig:
+    """Settings for `DemoData.fights`."""
+
+    @property
+    def gap(self) -> float:
+        """Seconds without any damage, kills or ubers before a fight is over."""
+        ...
+    @gap.setter
+    def gap(self, value: float) -> None: ...
+
+    @property
+    def radius(self) -> float:
+        """Combat further than this from where a fight is happening
+        is a separate fight."""
+        ...
+    @radius.setter
+    def radius(self, value: float) -> None: ...
+
+    @property
+    def min_damage(self) -> int:
+        """Fights with less damage than this and no kills are left out."""
+        ...
+    @min_damage.setter
+    def min_damage(self, value: int) -> None: ...
+
+    def __init__(
+        self,
+        gap: float = 4.0,
+        radius: float = 1500.0,
+        min_damage: int = 300,
+    ) -> None: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> FightConfig: ...
+    def __deepcopy__(self, memo: Any) -> FightConfig: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class Fight:
+    """A single engagement between the teams. Kills, damage and ubers are
+    counted for the team that did them."""
+
+    @property
+    def start_tick(self) -> int:
+        """Tick of the first damage, kill or uber."""
+        ...
+
+    @property
+    def end_tick(self) -> int:
+        """Tick of the last damage, kill or uber."""
+        ...
+
+    @property
+    def participants(self) -> List[int]:
+        """User IDs of everyone that did or took damage, died, or ubered, ascending."""
+        ...
+
+    @property
+    def location(self) -> Vector:
+        """The average position of the players that were hurt or killed."""
+        ...
+
+    @property
+    def red_kills(self) -> int: ...
+
+    @property
+    def blue_kills(self) -> int: ...
+
+    @property
+    def red_damage(self) -> int: ...
+
+    @property
+    def blue_damage(self) -> int: ...
+
+    @property
+    def red_ubers(self) -> int: ...
+
+    @property
+    def blue_ubers(self) -> int: ...
+
+    @property
+    def winner(self) -> Team:
+        """The team with more kills, or `Team.Other` if they are even."""
+        ...
+
+    @property
+    def first_pick(self) -> Kill | None:
+        """The first kill of the fight."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Fight: ...
+    def __deepcopy__(self, memo: Any) -> Fight: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class ClusterMethod:
+    """How teammates are clustered into groups."""
+
+    Connected: ClassVar[ClusterMethod]
+    """Players closer than the threshold are in the same group, and so is
+    anyone within the threshold of any of them."""
+
+    Dbscan: ClassVar[ClusterMethod]
+    """DBSCAN, with the threshold as epsilon. Players that aren't part of
+    a cluster are put in a group of their own."""
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> ClusterMethod: ...
+    def __deepcopy__(self, memo: Any) -> ClusterMethod: ...
+
+
+class GroupingConfig:
+    """Settings for `TickData.analyze` and `DemoData.analyze`."""
+
+    @property
+    def method(self) -> ClusterMethod: ...
+    @method.setter
+    def method(self, value: ClusterMethod) -> None: ...
+
+    @property
+    def threshold(self) -> float:
+        """Players within this distance of each other are neighbours."""
+        ...
+    @threshold.setter
+    def threshold(self, value: float) -> None: ...
+
+    @property
+    def min_players(self) -> int:
+        """DBSCAN only. How many neighbours, counting the player itself,
+        a player needs to start or grow a group."""
+        ...
+    @min_players.setter
+    def min_players(self, value: int) -> None: ...
+
+    @property
+    def height_weight(self) -> float:
+        """Height differences are multiplied by this before measuring distance.
+        Above 1 splits players on different levels, 0 ignores height."""
+        ...
+    @height_weight.setter
+    def height_weight(self, value: float) -> None: ...
+
+    def __init__(
+        self,
+        method: ClusterMethod = ClusterMethod.Connected,
+        threshold: float = 600.0,
+        min_players: int = 2,
+        height_weight: float = 1.0,
+    ) -> None: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> GroupingConfig: ...
+    def __deepcopy__(self, memo: Any) -> GroupingConfig: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class HealStyle:
+    """How a medic spread their heals."""
+
+    Mixed: ClassVar[HealStyle]
+    """Neither of the others."""
+
+    Pocket: ClassVar[HealStyle]
+    """Most of the healing went to a single player."""
+
+    Juggling: ClassVar[HealStyle]
+    """Switched patients often, keeping several of them overhealed."""
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> HealStyle: ...
+    def __deepcopy__(self, memo: Any) -> HealStyle: ...
+
+
+class HealSegment:
+    """A medic healing the same patient without stopping."""
+
+    @property
+    def patient_id(self) -> int: ...
+
+    @property
+    def patient_name(self) -> str: ...
+
+    @property
+    def patient_class(self) -> Class: ...
+
+    @property
+    def start_tick(self) -> int: ...
+
+    @property
+    def end_tick(self) -> int:
+        """The last tick the patient was still being healed."""
+        ...
+
+    @property
+    def duration(self) -> float:
+        """Seconds."""
+        ...
+
+    @property
+    def start_health(self) -> int: ...
+
+    @property
+    def end_health(self) -> int: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> HealSegment: ...
+    def __deepcopy__(self, memo: Any) -> HealSegment: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class MedicReport:
+    """The heal targets of a single medic, and what they say about the medic."""
+
+    @property
+    def user_id(self) -> int: ...
+
+    @property
+    def name(self) -> str: ...
+
+    @property
+    def team(self) -> Team: ...
+
+    @property
+    def segments(self) -> List[HealSegment]:
+        """In tick order."""
+        ...
+
+    @property
+    def healing_time(self) -> float:
+        """Seconds spent healing."""
+        ...
+
+    @property
+    def class_time(self) -> Dict[Class, float]:
+        """Seconds spent healing each class."""
+        ...
+
+    @property
+    def time_to_heal(self) -> List[float]:
+        """Seconds from a teammate being damaged until this medic started
+        healing them, for each time they did."""
+        ...
+
+    @property
+    def unanswered(self) -> int:
+        """Times a teammate was damaged while this medic was alive, and wasn't
+        healed before dying, getting back to full health, or 10 seconds passing."""
+        ...
+
+    @property
+    def pocket_id(self) -> int | None:
+        """The patient healed the longest."""
+        ...
+
+    @property
+    def pocket_share(self) -> float:
+        """The share of `healing_time` spent on `pocket_id`."""
+        ...
+
+    @property
+    def switches_per_minute(self) -> float:
+        """Patient switches per minute of healing."""
+        ...
+
+    @property
+    def overheal_share(self) -> float:
+        """The share of `healing_time` the patient was overhealed."""
+        ...
+
+    @property
+    def style(self) -> HealStyle: ...
+
+    @property
+    def avg_time_to_heal(self) -> float | None:
+        """The average of `time_to_heal`, or None if it is empty."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> MedicReport: ...
+    def __deepcopy__(self, memo: Any) -> MedicReport: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class HeatmapKind:
+    """What a heatmap counts."""
+
+    Occupancy: ClassVar[HeatmapKind]
+    """Seconds spent in each cell."""
+
+    Kills: ClassVar[HeatmapKind]
+    """Kills of enemies, where the attacker was standing."""
+
+    Deaths: ClassVar[HeatmapKind]
+    """Deaths, where the player died."""
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> HeatmapKind: ...
+    def __deepcopy__(self, memo: Any) -> HeatmapKind: ...
+
+
+class HeatmapFilter:
+    """Which players and ticks go into a heatmap, for `DemoData.heatmap`.
+    Anything left as None isn't filtered on. For kills the filter applies
+    to the attacker, for deaths to the player that died."""
+
+    @property
+    def user_id(self) -> int | None: ...
+    @user_id.setter
+    def user_id(self, value: int | None) -> None: ...
+
+    @property
+    def player_class(self) -> Class | None: ...
+    @player_class.setter
+    def player_class(self, value: Class | None) -> None: ...
+
+    @property
+    def team(self) -> Team | None: ...
+    @team.setter
+    def team(self, value: Team | None) -> None: ...
+
+    @property
+    def round(self) -> Round | None: ...
+    @round.setter
+    def round(self, value: Round | None) -> None: ...
+
+    @property
+    def phase(self) -> Tuple[float, float] | None:
+        """(start, end) seconds since the start of the round, e.g. (0, 30)
+        for the opening of each round. Ticks outside of a round are left out."""
+        ...
+    @phase.setter
+    def phase(self, value: Tuple[float, float] | None) -> None: ...
+
+    @property
+    def alive_only(self) -> bool:
+        """Occupancy only. Leaves out dead players, whose positions stay
+        where they died."""
+        ...
+    @alive_only.setter
+    def alive_only(self, value: bool) -> None: ...
+
+    def __init__(
+        self,
+        user_id: int | None = None,
+        player_class: Class | None = None,
+        team: Team | None = None,
+        round: Round | None = None,
+        phase: Tuple[float, float] | None = None,
+        alive_only: bool = True,
+    ) -> None: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> HeatmapFilter: ...
+    def __deepcopy__(self, memo: Any) -> HeatmapFilter: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class Heatmap:
+    """A grid over the map, counting something per cell. Made by `DemoData.heatmap`."""
+
+    @property
+    def kind(self) -> HeatmapKind: ...
+
+    @property
+    def bounds(self) -> World:
+        """The area covered. Cell (0, 0) starts at `bound_min`."""
+        ...
+
+    @property
+    def cell_size(self) -> float:
+        """Width and height of each cell, in world units."""
+        ...
+
+    @property
+    def width(self) -> int:
+        """Cells along X."""
+        ...
+
+    @property
+    def height(self) -> int:
+        """Cells along Y."""
+        ...
+
+    @property
+    def values(self) -> NDArray[float32]:
+        """(height, width) array, indexed by [y, x] with y increasing upwards,
+        so it displays right side up with `imshow(origin="lower")`."""
+        ...
+
+    @property
+    def max(self) -> float:
+        """The highest value of any cell."""
+        ...
+
+    def save(self, path: str) -> None:
+        """Writes the heatmap to `path` as an SVG if it ends in `.svg`,
+        otherwise as a PNG. Empty cells are transparent."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Heatmap: ...
+    def __deepcopy__(self, memo: Any) -> Heatmap: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class MedicDeathConfig:
+    """Settings for `DemoData.medic_deaths`."""
+
+    @property
+    def range(self) -> float:
+        """Living enemies at most this far from the medic were in range."""
+        ...
+    @range.setter
+    def range(self, value: float) -> None: ...
+
+    @property
+    def uber_window(self) -> float:
+        """Seconds before the death an uber counts as deployed."""
+        ...
+    @uber_window.setter
+    def uber_window(self, value: float) -> None: ...
+
+    @property
+    def drop_charge(self) -> float:
+        """Dying with at least this much charge, from 0 to 1, is a drop."""
+        ...
+    @drop_charge.setter
+    def drop_charge(self, value: float) -> None: ...
+
+    def __init__(
+        self,
+        range: float = 1000.0,
+        uber_window: float = 8.0,
+        drop_charge: float = 1.0,
+    ) -> None: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> MedicDeathConfig: ...
+    def __deepcopy__(self, memo: Any) -> MedicDeathConfig: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class MedicDeath:
+    """A medic's death, and what was around them."""
+
+    @property
+    def kill(self) -> Kill: ...
+
+    @property
+    def name(self) -> str: ...
+
+    @property
+    def team(self) -> Team: ...
+
+    @property
+    def state_tick(self) -> int | None:
+        """The tick positions and charge were taken from. None without tick states."""
+        ...
+
+    @property
+    def position(self) -> Vector | None: ...
+
+    @property
+    def charge(self) -> float | None:
+        """From 0 to 1."""
+        ...
+
+    @property
+    def dropped(self) -> bool:
+        """If `charge` was at least `MedicDeathConfig.drop_charge`."""
+        ...
+
+    @property
+    def nearest_teammate(self) -> int | None: ...
+
+    @property
+    def teammate_distance(self) -> float | None: ...
+
+    @property
+    def nearest_enemy(self) -> int | None: ...
+
+    @property
+    def enemy_distance(self) -> float | None: ...
+
+    @property
+    def enemies_in_range(self) -> List[int]:
+        """User IDs of the living enemies within `MedicDeathConfig.range`, nearest first."""
+        ...
+
+    @property
+    def time_since_damaged(self) -> float | None:
+        """Seconds since another player last hurt the medic. None if nobody
+        did since they spawned."""
+        ...
+
+    @property
+    def time_since_uber(self) -> float | None:
+        """Seconds since the medic last ubered. None if they didn't since they spawned."""
+        ...
+
+    @property
+    def uber_deployed(self) -> bool:
+        """If `time_since_uber` is within `MedicDeathConfig.uber_window`."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> MedicDeath: ...
+    def __deepcopy__(self, memo: Any) -> MedicDeath: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class MedicDeathTotals:
+    """The deaths of a single medic."""
+
+    @property
+    def user_id(self) -> int: ...
+
+    @property
+    def name(self) -> str: ...
+
+    @property
+    def team(self) -> Team: ...
+
+    @property
+    def deaths(self) -> int: ...
+
+    @property
+    def drops(self) -> int: ...
+
+    @property
+    def deaths_after_uber(self) -> int:
+        """Deaths within `MedicDeathConfig.uber_window` of ubering."""
+        ...
+
+    @property
+    def avg_charge(self) -> float:
+        """Over the deaths with a known charge."""
+        ...
+
+    @property
+    def avg_teammate_distance(self) -> float:
+        """Over the deaths with a living teammate."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> MedicDeathTotals: ...
+    def __deepcopy__(self, memo: Any) -> MedicDeathTotals: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class MedicDeathReport:
+    """Every medic death in a demo or round. Made by `DemoData.medic_deaths`."""
+
+    @property
+    def deaths(self) -> List[MedicDeath]:
+        """In tick order."""
+        ...
+
+    @property
+    def medics(self) -> List[MedicDeathTotals]:
+        """Ordered by team, then user ID."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> MedicDeathReport: ...
+    def __deepcopy__(self, memo: Any) -> MedicDeathReport: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class ClassBaseline:
+    """What an average player of a class does per minute."""
+
+    @property
+    def kills(self) -> float: ...
+    @kills.setter
+    def kills(self, value: float) -> None: ...
+
+    @property
+    def assists(self) -> float: ...
+    @assists.setter
+    def assists(self, value: float) -> None: ...
+
+    @property
+    def deaths(self) -> float: ...
+    @deaths.setter
+    def deaths(self, value: float) -> None: ...
+
+    @property
+    def damage(self) -> float: ...
+    @damage.setter
+    def damage(self, value: float) -> None: ...
+
+    @property
+    def healing(self) -> float: ...
+    @healing.setter
+    def healing(self, value: float) -> None: ...
+
+    @property
+    def ubercharges(self) -> float: ...
+    @ubercharges.setter
+    def ubercharges(self, value: float) -> None: ...
+
+    def __init__(
+        self,
+        kills: float = 0.0,
+        assists: float = 0.0,
+        deaths: float = 0.0,
+        damage: float = 0.0,
+        healing: float = 0.0,
+        ubercharges: float = 0.0,
+    ) -> None: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> ClassBaseline: ...
+    def __deepcopy__(self, memo: Any) -> ClassBaseline: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class RatingWeights:
+    """How much each score counts towards a class's rating. Should add up to 1."""
+
+    @property
+    def kills(self) -> float: ...
+    @kills.setter
+    def kills(self, value: float) -> None: ...
+
+    @property
+    def assists(self) -> float: ...
+    @assists.setter
+    def assists(self, value: float) -> None: ...
+
+    @property
+    def damage(self) -> float: ...
+    @damage.setter
+    def damage(self, value: float) -> None: ...
+
+    @property
+    def healing(self) -> float: ...
+    @healing.setter
+    def healing(self, value: float) -> None: ...
+
+    @property
+    def ubercharges(self) -> float: ...
+    @ubercharges.setter
+    def ubercharges(self, value: float) -> None: ...
+
+    @property
+    def survival(self) -> float: ...
+    @survival.setter
+    def survival(self, value: float) -> None: ...
+
+    def __init__(
+        self,
+        kills: float = 0.0,
+        assists: float = 0.0,
+        damage: float = 0.0,
+        healing: float = 0.0,
+        ubercharges: float = 0.0,
+        survival: float = 0.0,
+    ) -> None: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> RatingWeights: ...
+    def __deepcopy__(self, memo: Any) -> RatingWeights: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class RatingModel:
+    """The baselines and weights ratings are worked out with. Changes to the
+    dicts have to be assigned back, e.g. `model.baselines = baselines`."""
+
+    @property
+    def baselines(self) -> Dict[Class, ClassBaseline]:
+        """Classes missing from here use the baseline of `Class.Other`."""
+        ...
+    @baselines.setter
+    def baselines(self, value: Dict[Class, ClassBaseline]) -> None: ...
+
+    @property
+    def weights(self) -> Dict[Class, RatingWeights]:
+        """Classes missing from here use the weights of `Class.Other`."""
+        ...
+    @weights.setter
+    def weights(self, value: Dict[Class, RatingWeights]) -> None: ...
+
+    @property
+    def max_score(self) -> float:
+        """The highest any one score can be, so one lucky stat can't carry a rating."""
+        ...
+    @max_score.setter
+    def max_score(self, value: float) -> None: ...
+
+    @property
+    def min_time(self) -> float:
+        """Seconds on a class below which it isn't rated, as the rates mean little."""
+        ...
+    @min_time.setter
+    def min_time(self, value: float) -> None: ...
+
+    def __init__(self) -> None:
+        """The default baselines and weights."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> RatingModel: ...
+    def __deepcopy__(self, memo: Any) -> RatingModel: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class RatingComponent:
+    """One stat's part of a class rating."""
+
+    @property
+    def name(self) -> str:
+        """"kills", "assists", "damage", "healing", "ubercharges" or "survival"."""
+        ...
+
+    @property
+    def rate(self) -> float:
+        """Per minute. Deaths per minute for survival."""
+        ...
+
+    @property
+    def baseline(self) -> float:
+        """Per minute, from the model."""
+        ...
+
+    @property
+    def score(self) -> float:
+        """`rate` over `baseline` (the other way around for survival),
+        capped at `RatingModel.max_score`."""
+        ...
+
+    @property
+    def weight(self) -> float: ...
+
+    @property
+    def contribution(self) -> float:
+        """`score * weight`. These add up to the class rating."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> RatingComponent: ...
+    def __deepcopy__(self, memo: Any) -> RatingComponent: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class ClassRating:
+    """A player's rating on a single class."""
+
+    @property
+    def player_class(self) -> Class: ...
+
+    @property
+    def time_played(self) -> float:
+        """Seconds on the class, dead or alive."""
+        ...
+
+    @property
+    def rating(self) -> float: ...
+
+    @property
+    def components(self) -> List[RatingComponent]: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> ClassRating: ...
+    def __deepcopy__(self, memo: Any) -> ClassRating: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class PlayerRating:
+    """A player's rating over a round, demo or season."""
+
+    @property
+    def user_id(self) -> int | None:
+        """None for season ratings, which span many user IDs."""
+        ...
+
+    @property
+    def steam_id(self) -> SteamId | None: ...
+
+    @property
+    def name(self) -> str: ...
+
+    @property
+    def team(self) -> Team:
+        """The team they last spawned on. `Team.Other` for season ratings."""
+        ...
+
+    @property
+    def rating(self) -> float | None:
+        """The time-weighted average of `classes`, or None if they didn't
+        play any class for `RatingModel.min_time`."""
+        ...
+
+    @property
+    def time_played(self) -> float:
+        """Seconds, on every class."""
+        ...
+
+    @property
+    def classes(self) -> List[ClassRating]:
+        """Only the classes played for at least `RatingModel.min_time`, longest first."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> PlayerRating: ...
+    def __deepcopy__(self, memo: Any) -> PlayerRating: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class RatingReport:
+    """The ratings of everyone in a demo. Made by `DemoData.ratings`."""
+
+    @property
+    def players(self) -> List[PlayerRating]:
+        """Over the whole demo, best first."""
+        ...
+
+    @property
+    def rounds(self) -> List[List[PlayerRating]]:
+        """Over each round, in the order of `DemoData.rounds`, best first."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> RatingReport: ...
+    def __deepcopy__(self, memo: Any) -> RatingReport: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class Respawn:
+    """How long a player waited to respawn after dying."""
+
+    @property
+    def kill(self) -> Kill: ...
+
+    @property
+    def name(self) -> str: ...
+
+    @property
+    def team(self) -> Team:
+        """The team and class the player last spawned as."""
+        ...
+
+    @property
+    def player_class(self) -> Class: ...
+
+    @property
+    def spawn_tick(self) -> int | None:
+        """None if the round or demo ended before the player spawned again."""
+        ...
+
+    @property
+    def respawn_time(self) -> float | None:
+        """Seconds from the death to the spawn."""
+        ...
+
+    @property
+    def wave_fraction(self) -> float | None:
+        """`respawn_time` over the longest respawn of the team. Near 1 the player
+        just missed a wave, near 0 they made it in time for one."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Respawn: ...
+    def __deepcopy__(self, memo: Any) -> Respawn: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class RespawnWave:
+    """Players of a team spawning together."""
+
+    @property
+    def team(self) -> Team: ...
+
+    @property
+    def start_tick(self) -> int: ...
+
+    @property
+    def end_tick(self) -> int: ...
+
+    @property
+    def user_ids(self) -> List[int]:
+        """In the order they spawned."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> RespawnWave: ...
+    def __deepcopy__(self, memo: Any) -> RespawnWave: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class TeamRespawns:
+    """Respawn timing of a team."""
+
+    @property
+    def team(self) -> Team: ...
+
+    @property
+    def deaths(self) -> int: ...
+
+    @property
+    def avg_respawn_time(self) -> float:
+        """Seconds, over the deaths that were followed by a spawn."""
+        ...
+
+    @property
+    def longest_respawn(self) -> float: ...
+
+    @property
+    def bad_waves(self) -> int:
+        """Deaths that just missed a wave, with a `wave_fraction` of 0.75 or more."""
+        ...
+
+    @property
+    def good_waves(self) -> int:
+        """Deaths that made it in time for a wave, with a `wave_fraction` of 0.25 or less."""
+        ...
+
+    @property
+    def forced_bad_waves(self) -> int:
+        """Kills by this team that made the victim just miss a wave."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> TeamRespawns: ...
+    def __deepcopy__(self, memo: Any) -> TeamRespawns: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class Wipe:
+    """Every living player of a team dying."""
+
+    @property
+    def team(self) -> Team: ...
+
+    @property
+    def tick(self) -> int:
+        """The tick of the last death."""
+        ...
+
+    @property
+    def back_tick(self) -> int | None:
+        """The tick everyone on the team was alive again, if that happened
+        before the round ended."""
+        ...
+
+    @property
+    def time_to_full(self) -> float | None:
+        """Seconds from `tick` to `back_tick`."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Wipe: ...
+    def __deepcopy__(self, memo: Any) -> Wipe: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class RespawnReport:
+    """Spawns, respawns and wipes of a demo or round. Made by `DemoData.respawns`."""
+
+    @property
+    def respawns(self) -> List[Respawn]:
+        """One for each death of a player that had spawned, in tick order."""
+        ...
+
+    @property
+    def waves(self) -> List[RespawnWave]:
+        """In the order they started."""
+        ...
+
+    @property
+    def teams(self) -> List[TeamRespawns]:
+        """RED, then BLU."""
+        ...
+
+    @property
+    def wipes(self) -> List[Wipe]:
+        """In tick order."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> RespawnReport: ...
+    def __deepcopy__(self, memo: Any) -> RespawnReport: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class SeasonFilter:
+    """Which demos, and which of each player's time, go into a season summary.
+    Anything left as None isn't filtered on."""
+
+    @property
+    def map(self) -> str | None:
+        """Part of the map name, ignoring case, e.g. "process"."""
+        ...
+    @map.setter
+    def map(self, value: str | None) -> None: ...
+
+    @property
+    def opponent(self) -> str | None:
+        """The name of a team that played against `Season.our_team`,
+        or played at all if there is no `our_team`."""
+        ...
+    @opponent.setter
+    def opponent(self, value: str | None) -> None: ...
+
+    @property
+    def since(self) -> str | None:
+        """The earliest date, as YYYY-MM-DD. Demos without a date are left out."""
+        ...
+    @since.setter
+    def since(self, value: str | None) -> None: ...
+
+    @property
+    def until(self) -> str | None:
+        """The latest date, as YYYY-MM-DD. Demos without a date are left out."""
+        ...
+    @until.setter
+    def until(self, value: str | None) -> None: ...
+
+    @property
+    def player_class(self) -> Class | None:
+        """Only count players' stats while they were this class.
+        Team summaries aren't affected."""
+        ...
+    @player_class.setter
+    def player_class(self, value: Class | None) -> None: ...
+
+    def __init__(
+        self,
+        map: str | None = None,
+        opponent: str | None = None,
+        since: str | None = None,
+        until: str | None = None,
+        player_class: Class | None = None,
+    ) -> None: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> SeasonFilter: ...
+    def __deepcopy__(self, memo: Any) -> SeasonFilter: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class SeasonDemo:
+    """What a season knows about one of its demos."""
+
+    @property
+    def demo_filename(self) -> str: ...
+
+    @property
+    def map_name(self) -> str: ...
+
+    @property
+    def date(self) -> str | None:
+        """YYYY-MM-DD, from the first date in the file name, or else the
+        day the file was last modified. None if that was before 1970."""
+        ...
+
+    @property
+    def red(self) -> str:
+        """The roster that had the most players on RED, or "RED" if none did."""
+        ...
+
+    @property
+    def blue(self) -> str:
+        """The roster that had the most players on BLU, or "BLU" if none did."""
+        ...
+
+    @property
+    def our_side(self) -> Team | None:
+        """The side `Season.our_team` played on, if they played."""
+        ...
+
+    @property
+    def red_rounds(self) -> int: ...
+
+    @property
+    def blue_rounds(self) -> int: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> SeasonDemo: ...
+    def __deepcopy__(self, memo: Any) -> SeasonDemo: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class SeasonPlayer:
+    """A player's totals over a season."""
+
+    @property
+    def steam_id(self) -> SteamId: ...
+
+    @property
+    def name(self) -> str:
+        """The name in the latest demo they were in."""
+        ...
+
+    @property
+    def demos(self) -> int:
+        """Demos they spawned in."""
+        ...
+
+    @property
+    def kills(self) -> int: ...
+
+    @property
+    def assists(self) -> int: ...
+
+    @property
+    def deaths(self) -> int: ...
+
+    @property
+    def damage(self) -> int: ...
+
+    @property
+    def damage_taken(self) -> int: ...
+
+    @property
+    def healing(self) -> int:
+        """Healing done to other players."""
+        ...
+
+    @property
+    def ubercharges(self) -> int: ...
+
+    @property
+    def alive_time(self) -> float:
+        """Seconds alive."""
+        ...
+
+    @property
+    def class_time(self) -> Dict[Class, float]:
+        """Seconds alive as each class."""
+        ...
+
+    @property
+    def kd(self) -> float:
+        """Kills per death, or kills if they never died."""
+        ...
+
+    @property
+    def damage_per_minute(self) -> float:
+        """Damage per minute alive."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> SeasonPlayer: ...
+    def __deepcopy__(self, memo: Any) -> SeasonPlayer: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class SeasonTeam:
+    """A team's totals over a season. Teams without a roster are named after
+    their side, so all of the unknown REDs are added up together."""
+
+    @property
+    def name(self) -> str: ...
+
+    @property
+    def demos(self) -> int: ...
+
+    @property
+    def wins(self) -> int:
+        """Demos they won more rounds in than the other team."""
+        ...
+
+    @property
+    def losses(self) -> int: ...
+
+    @property
+    def rounds_won(self) -> int: ...
+
+    @property
+    def rounds_lost(self) -> int: ...
+
+    @property
+    def kills(self) -> int: ...
+
+    @property
+    def deaths(self) -> int: ...
+
+    @property
+    def damage(self) -> int: ...
+
+    @property
+    def ubercharges(self) -> int: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> SeasonTeam: ...
+    def __deepcopy__(self, memo: Any) -> SeasonTeam: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class SeasonSummary:
+    """Totals of the demos that passed a filter. Made by `Season.summary`."""
+
+    @property
+    def demos(self) -> List[SeasonDemo]:
+        """The demos counted, oldest first."""
+        ...
+
+    @property
+    def players(self) -> List[SeasonPlayer]:
+        """Ordered by SteamID."""
+        ...
+
+    @property
+    def teams(self) -> List[SeasonTeam]:
+        """Ordered by name."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> SeasonSummary: ...
+    def __deepcopy__(self, memo: Any) -> SeasonSummary: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class Season:
+    """Many demos and the rosters of the teams that played in them.
+    Made by `tf2dal.load_season`, or from demos that are already loaded."""
+
+    @property
+    def info(self) -> List[SeasonDemo]: ...
+
+    @property
+    def teams(self) -> Dict[str, List[SteamId]]:
+        """Team name to the SteamIDs of its players."""
+        ...
+
+    @property
+    def our_team(self) -> str | None: ...
+
+    @property
+    def demos(self) -> List[DemoData]:
+        """Every demo, in the same order as `info`. Copies them all."""
+        ...
+
+    def __init__(
+        self,
+        demos: List[DemoData],
+        teams: Dict[str, List[SteamId]] = ...,
+        our_team: str | None = None,
+    ) -> None: ...
+
+    def summary(self, filter: SeasonFilter | None = None) -> SeasonSummary:
+        """Player and team totals over the demos that pass `filter`."""
+        ...
+
+    def ratings(
+        self,
+        model: RatingModel | None = None,
+        filter: SeasonFilter | None = None,
+    ) -> List[PlayerRating]:
+        """Impact ratings by SteamID over the demos that pass `filter`, best
+        first. Uses `RatingModel()` unless `model` is given."""
+        ...
+
+    def __len__(self) -> int: ...
+    def __repr__(self) -> str: ...
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Season: ...
+    def __deepcopy__(self, memo: Any) -> Season: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+
+
+class ManAdvantage:
+    """A stretch of ticks where one team had more players alive than the other."""
+
+    @property
+    def team(self) -> Team:
+        """The team with more players alive."""
+        ...
+
+    @property
+    def start_tick(self) -> int: ...
+
+    @property
+    def end_tick(self) -> int:
+        """The last tick the team was still ahead."""
+        ...
+
+    @property
+    def max_advantage(self) -> int:
+        """The most players the team was ahead by."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> ManAdvantage: ...
+    def __deepcopy__(self, memo: Any) -> ManAdvantage: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class Trade:
+    """A kill answered by the death of the killer."""
+
+    @property
+    def kill(self) -> Kill: ...
+
+    @property
+    def response(self) -> Kill:
+        """The kill of `kill`'s attacker."""
+        ...
+
+    @property
+    def delay(self) -> float:
+        """Seconds between the kills."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Trade: ...
+    def __deepcopy__(self, memo: Any) -> Trade: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class RoundTimeline:
+    """What happened over a round, tick by tick. The arrays are in tick order
+    and only have the recorded ticks."""
+
+    @property
+    def round(self) -> Round: ...
+
+    @property
+    def advantages(self) -> List[ManAdvantage]:
+        """Every time a team was ahead, in order."""
+        ...
+
+    @property
+    def trades(self) -> List[Trade]: ...
+
+    @property
+    def first_pick(self) -> Kill | None:
+        """The first kill of the round."""
+        ...
+
+    @property
+    def ticks(self) -> NDArray[uint32]: ...
+
+    @property
+    def red_alive(self) -> NDArray[uint8]:
+        """Living players on RED at each tick."""
+        ...
+
+    @property
+    def blue_alive(self) -> NDArray[uint8]:
+        """Living players on BLU at each tick."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> RoundTimeline: ...
+    def __deepcopy__(self, memo: Any) -> RoundTimeline: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class Zone:
+    """A named polygon on the map, seen from above."""
+
+    @property
+    def name(self) -> str: ...
+    @name.setter
+    def name(self, value: str) -> None: ...
+
+    @property
+    def points(self) -> List[Tuple[float, float]]:
+        """The corners, as (x, y), in order around the edge."""
+        ...
+    @points.setter
+    def points(self, value: List[Tuple[float, float]]) -> None: ...
+
+    @property
+    def min_z(self) -> float | None:
+        """Only positions at or above this height are in the zone."""
+        ...
+    @min_z.setter
+    def min_z(self, value: float | None) -> None: ...
+
+    @property
+    def max_z(self) -> float | None:
+        """Only positions at or below this height are in the zone."""
+        ...
+    @max_z.setter
+    def max_z(self, value: float | None) -> None: ...
+
+    def __init__(
+        self,
+        name: str,
+        points: List[Tuple[float, float]],
+        min_z: float | None = None,
+        max_z: float | None = None,
+    ) -> None: ...
+
+    def contains(self, position: Vector) -> bool:
+        """If `position` is inside the polygon and within the height limits."""
+        ...
+
+    def center(self) -> Vector:
+        """The average of the corners, for placing labels."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Zone: ...
+    def __deepcopy__(self, memo: Any) -> Zone: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class ZoneTime:
+    """Seconds a player spent alive in a zone."""
+
+    @property
+    def user_id(self) -> int: ...
+
+    @property
+    def name(self) -> str: ...
+
+    @property
+    def team(self) -> Team: ...
+
+    @property
+    def zone(self) -> str: ...
+
+    @property
+    def seconds(self) -> float: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> ZoneTime: ...
+    def __deepcopy__(self, memo: Any) -> ZoneTime: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class ZoneControl:
+    """Seconds each team held a zone, by having more living players in it."""
+
+    @property
+    def zone(self) -> str: ...
+
+    @property
+    def red_time(self) -> float: ...
+
+    @property
+    def blue_time(self) -> float: ...
+
+    @property
+    def contested_time(self) -> float:
+        """Seconds with the same number of players from each team in the zone,
+        and at least one."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> ZoneControl: ...
+    def __deepcopy__(self, memo: Any) -> ZoneControl: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class KillZone:
+    """Where the players in a kill were."""
+
+    @property
+    def kill(self) -> Kill: ...
+
+    @property
+    def attacker_zone(self) -> str | None: ...
+
+    @property
+    def victim_zone(self) -> str | None: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> KillZone: ...
+    def __deepcopy__(self, memo: Any) -> KillZone: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class ZoneReport:
+    """Who was in which zone over a demo or round. Made by `DemoData.zone_occupancy`."""
+
+    @property
+    def zones(self) -> List[str]:
+        """The zone names, in the order given. Zone indices refer to these."""
+        ...
+
+    @property
+    def time_in_zone(self) -> List[ZoneTime]:
+        """Ordered by user ID, then zone."""
+        ...
+
+    @property
+    def control_time(self) -> List[ZoneControl]:
+        """In the same order as `zones`."""
+        ...
+
+    @property
+    def kills(self) -> List[KillZone]:
+        """In tick order."""
+        ...
+
+    @property
+    def ticks(self) -> NDArray[uint32]:
+        """The tick of each row of the arrays, ascending."""
+        ...
+
+    @property
+    def user_ids(self) -> NDArray[uint16]:
+        """The user ID of each column of `player_zones`, ascending."""
+        ...
+
+    @property
+    def player_zones(self) -> NDArray[int16]:
+        """(ticks, players) array of the zone index each living player was in,
+        or -1 if they were dead, missing, or in no zone."""
+        ...
+
+    @property
+    def control(self) -> NDArray[uint8]:
+        """(ticks, zones) array of the Team value holding each zone, which is
+        `Team.Other` if it is empty or contested."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> ZoneReport: ...
+    def __deepcopy__(self, memo: Any) -> ZoneReport: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
@@ -1,72 +1,108 @@
-"""
-Contains the structures for DemoData and TIckData.
+# Generated by stubgen from the Rust source. Don't edit this file, change
+# the pyclasses or their doc comments and run `cargo run -p stubgen` instead.
+
+"""Contains the structures for DemoData and TickData.
 
 DemoData contains one of the following:
 
-    1) Information on the entire demo file. If this is the case, 
-        `DemoData.rounds` will contain multiple rounds inside of it and may 
-        contain ticks that are not a part of an actual round of gameplay.
-    
-    OR
+1) Information on the entire demo file. If this is the case,
+   `DemoData.rounds` will contain multiple rounds inside of it and may
+   contain ticks that are not a part of an actual round of gameplay.
 
-    2) Information on a single round in the demo. In this case, `rounds` will
-        only contain one round. The map name, demo file name, duration, and 
-        player reach bounds will be the same as the whole demo. Note that the
-        duration is NOT the duration of the round in time in this case!
-"""
+OR
 
-print("| Importing dal.demo...")
+2) Information on a single round in the demo. In this case, `rounds` will
+   only contain one round. The map name, demo file name, duration, and
+   player reach bounds will be the same as the whole demo. Note that the
+   duration is NOT the duration of the round in time in this case!"""
 
-from typing import List, Dict
-from numpy  import float32
+from numpy import bool_, float32, uint16, uint32, uint8
+from numpy.typing import NDArray
+from pandas import DataFrame
+from typing import Any, Dict, List, Tuple
+from .analysis import CritHealReport, Fight, FightConfig, GroupingConfig, Heatmap, HeatmapFilter, HeatmapKind, MedicDeathConfig, MedicDeathReport, MedicReport, RatingModel, RatingReport, RespawnReport, RoundTimeline, TickAnalysis, Zone, ZoneReport
+from .entities import Dispenser, Medigun, Player, Sentry, Teleporter
+from .events import Capture, Damage, Heal, Kill, Spawn, Ubercharge
+from .game import Round, World
+from .identity import IdentityMap
 
-from .         import EntityID, UserID, DemoTick
-from .game     import Round, World
-from .entities import Player, Sentry, Dispenser, Teleporter, Medigun
-from .events   import Kill
 
 class TickData:
     """Contains information about the state of a tick."""
+
     @property
     def players(self) -> List[Player]:
         """List of all players that have ever been seen up until this tick."""
+        ...
 
     @property
-    def sentries(self) -> Dict[Sentry]: ...
+    def tick_delta(self) -> float:
+        """Amount of actual seconds this tick lasted."""
+        ...
 
     @property
-    def dispensers(self) -> Dict[Dispenser]: ...
+    def sentries(self) -> Dict[int, Sentry]: ...
 
     @property
-    def teleporters(self) -> Dict[Teleporter]: ...
+    def dispensers(self) -> Dict[int, Dispenser]: ...
 
     @property
-    def mediguns(self) -> List[Medigun]:
-        """List of all mediguns that have ever been seen up until this tick."""
+    def teleporters(self) -> Dict[int, Teleporter]: ...
 
     @property
-    def tick(self) -> DemoTick:
+    def tick(self) -> int:
         """Index of the tick."""
+        ...
 
     @property
-    def tick_delta(self) -> float32:
-        """Amount of actual seconds this tick lasted."""
+    def mediguns(self) -> List[Medigun]:
+        """List of all mediguns that have ever been seen up until this tick."""
+        ...
+
+    def __repr__(self) -> str: ...
+
+    @staticmethod
+    def empty_player() -> Player: ...
+
+    def analyze(self, config: GroupingConfig | None = None) -> TickAnalysis:
+        """Team averages, medic distances and groupings for this tick."""
+        ...
+
+    def get_player_by_entityid(self, entity_id: int) -> Player | None: ...
+    def get_player_by_userid(self, user_id: int) -> Player | None: ...
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> TickData: ...
+    def __deepcopy__(self, memo: Any) -> TickData: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
 
-    def get_player_by_entityid(self, entity_id: EntityID) -> Player | None: ...
-    def get_player_by_userid(self, user_id: UserID) -> Player | None: ...
 
 class DemoData:
     """Contains either information for the whole demo,
     or information on a piece of the demo that contains multiple
-    tick states (e.g. a round)."""
+    tick states (e.g. a round).
+
+    The player arrays are each shaped (ticks, players, ...). Rows are in the
+    order of `tick_index()` and columns are in the order of `player_index()`.
+    Players that don't exist in a tick are NaN for float arrays, 0 otherwise.
+    They are copied out of the tick states on every call, so keep the
+    result rather than asking again.
+
+    In the DataFrames, Class and Team columns are categoricals. Ticks between
+    rounds have a `round` of -1."""
+
     @property
-    def demo_filename(self) -> str: ...
+    def demo_filename(self) -> str:
+        """The path to the demofile that this data is for."""
+        ...
 
     @property
-    def map_name(self) -> str: ...
+    def map_name(self) -> str:
+        """The name of the map. Should end in .bsp."""
+        ...
 
     @property
-    def duration(self) -> float32: 
+    def duration(self) -> float:
         """The duration of the demo file in seconds.
         If this DemoData is a single round of a demo file, then this
         will not change! It will still be the duration of the entire demo,
@@ -74,17 +110,244 @@ class DemoData:
         ...
 
     @property
-    def rounds(self) -> List[Round]: ...
+    def rounds(self) -> List[Round]:
+        """Basic information for each round that occurred."""
+        ...
 
     @property
-    def kills(self) -> List[Kill]: ...
+    def kills(self) -> List[Kill]:
+        """Every single kill that happened in the game."""
+        ...
 
     @property
-    def player_reach_bounds(self) -> World: ...
+    def point_captures(self) -> List[Capture]: ...
 
     @property
-    def tick_states(self) -> Dict[DemoTick, TickData]: ...
+    def ubercharges(self) -> List[Ubercharge]:
+        """Every ubercharge deployed in the game."""
+        ...
+
+    @property
+    def damage(self) -> List[Damage]:
+        """Every time a player was hurt."""
+        ...
+
+    @property
+    def heals(self) -> List[Heal]:
+        """Every time a player was healed."""
+        ...
+
+    @property
+    def spawns(self) -> List[Spawn]:
+        """Every time a player spawned."""
+        ...
+
+    @property
+    def identities(self) -> IdentityMap:
+        """The SteamID and names behind each user ID, for following players
+        across demos."""
+        ...
+
+    @property
+    def player_reach_bounds(self) -> World:
+        """The minimum and maximum X, Y, and Z values players ever had positions."""
+        ...
+
+    @property
+    def tick_states(self) -> Dict[int, TickData]:
+        """The state of every recorded tick, by tick."""
+        ...
+
+    def __repr__(self) -> str: ...
 
     def round_data(self, round: Round) -> DemoData:
-        """Limit the data to a specific round.
-        This DOES copy. Don't use this function if `len(rounds) < 2` !"""
\ No newline at end of file
+        """View data that has been limited to a single round.
+        This DOES copy the data in the parse, so be wary.
+        Don't use this function if `len(rounds) < 2`!"""
+        ...
+
+    def save(self, path: str) -> None:
+        """Writes the parse to `path`, to be read back with `DemoData.load`
+        or passed to `load_season` in place of the demo."""
+        ...
+
+    @staticmethod
+    def load(path: str) -> DemoData:
+        """Reads a parse written by `DemoData.save`."""
+        ...
+
+    def analyze(
+        self,
+        round: Round | None = None,
+        config: GroupingConfig | None = None,
+    ) -> List[TickAnalysis]:
+        """`TickData.analyze` for every tick, or only the ticks of `round`,
+        in tick order. Groups are followed from tick to tick within a round."""
+        ...
+
+    def fights(
+        self,
+        round: Round | None = None,
+        config: FightConfig | None = None,
+    ) -> List[Fight]:
+        """The fights in `round`, or in every round, in the order they started.
+        Needs the tick states, so it is always empty with `store_ticks=False`."""
+        ...
+
+    def timeline(self, round: Round, trade_window: float = 5.0) -> RoundTimeline:
+        """Alive counts, man advantages, trades and the first pick of `round`.
+        A kill is traded if its attacker dies within `trade_window` seconds."""
+        ...
+
+    def critheals(self, round: Round | None = None) -> CritHealReport:
+        """How crit heals were built up, used and wasted, by player and by medic,
+        over the demo or only `round`."""
+        ...
+
+    def medic_heals(self, round: Round | None = None) -> List[MedicReport]:
+        """Each medic's heal targets over the demo or only `round`, with how
+        their healing was spread over classes and patients."""
+        ...
+
+    def heatmap(
+        self,
+        kind: HeatmapKind = HeatmapKind.Occupancy,
+        filter: HeatmapFilter | None = None,
+        cell_size: float = 64.0,
+    ) -> Heatmap:
+        """A `kind` heatmap over the player reach bounds, with square cells
+        `cell_size` units wide, of only what passes `filter`. Empty with
+        `store_ticks=False`."""
+        ...
+
+    def zone_occupancy(
+        self,
+        zones: List[Zone],
+        round: Round | None = None,
+    ) -> ZoneReport:
+        """Which of `zones` each player was in, how long each team held them,
+        and where each kill happened, over the demo or only `round`.
+        `tf2dal.analysis.load_zones()` reads zones from the config."""
+        ...
+
+    def respawns(self, round: Round | None = None) -> RespawnReport:
+        """How long each death waited to respawn, the respawn waves of each
+        team, and how long teams took to recover from wipes, over every
+        round or only `round`. Only needs the events."""
+        ...
+
+    def medic_deaths(
+        self,
+        round: Round | None = None,
+        config: MedicDeathConfig | None = None,
+    ) -> MedicDeathReport:
+        """The charge, nearest teammate and enemies, last damage and last uber of
+        every medic that died, over every round or only `round`. Positions and
+        charge need the tick states, and are None with `store_ticks=False`."""
+        ...
+
+    def ratings(self, model: RatingModel | None = None) -> RatingReport:
+        """Each player's impact rating over the demo and each round, with the
+        breakdown of every class they played. Uses `RatingModel()` unless
+        `model` is given. Only needs the events."""
+        ...
+
+    def tick_index(self) -> NDArray[uint32]:
+        """The tick of each row of the player arrays, ascending."""
+        ...
+
+    def player_index(self) -> NDArray[uint16]:
+        """The user ID of each column of the player arrays, ascending."""
+        ...
+
+    def player_positions(self) -> NDArray[float32]:
+        """(ticks, players, 3) array of XYZ positions."""
+        ...
+
+    def player_health(self) -> NDArray[uint16]:
+        """(ticks, players) array of health."""
+        ...
+
+    def player_time_since_hurt(self) -> NDArray[float32]:
+        """(ticks, players) array of `Player.time_since_last_hurt`."""
+        ...
+
+    def player_teams(self) -> NDArray[uint8]:
+        """(ticks, players) array of Team values."""
+        ...
+
+    def player_classes(self) -> NDArray[uint8]:
+        """(ticks, players) array of Class values."""
+        ...
+
+    def alive_mask(self) -> NDArray[bool_]:
+        """(ticks, players) array that is True where the player is alive."""
+        ...
+
+    def to_dataframe(self) -> DataFrame:
+        """One row per player per tick. Requires pandas."""
+        ...
+
+    def kills_to_dataframe(self) -> DataFrame:
+        """One row per kill, with the class and team of the victim and attacker."""
+        ...
+
+    def captures_to_dataframe(self) -> DataFrame:
+        """One row per point capture."""
+        ...
+
+    def ubercharges_to_dataframe(self) -> DataFrame:
+        """One row per ubercharge, with the team of the medic."""
+        ...
+
+    def damage_to_dataframe(self) -> DataFrame:
+        """One row per time a player was hurt, with the class and team of the
+        victim and attacker."""
+        ...
+
+    def heals_to_dataframe(self) -> DataFrame:
+        """One row per time a player was healed, with the class and team of the
+        patient and healer."""
+        ...
+
+    def spawns_to_dataframe(self) -> DataFrame:
+        """One row per spawn, with where the player spawned."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> DemoData: ...
+    def __deepcopy__(self, memo: Any) -> DemoData: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+
+
+class PlayerSummary:
+    """Post-game summary of a player."""
+
+    @property
+    def kills(self) -> int: ...
+
+    @property
+    def assists(self) -> int: ...
+
+    @property
+    def deaths(self) -> int: ...
+
+    @property
+    def buildings_destroyed(self) -> int: ...
+
+    @property
+    def captures(self) -> int: ...
+
+    @property
+    def defenses(self) -> int: ...
+
+    @property
+    def user_id(self) -> int: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> PlayerSummary: ...
+    def __deepcopy__(self, memo: Any) -> PlayerSummary: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
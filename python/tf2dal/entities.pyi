@@ -1,15 +1,14 @@
-"""
-Contains definitions for each entity.
-An entity is (pretty much) any game object, including players, weapons, hats, 
-objectives, and many more.
-"""
+# Generated by stubgen from the Rust source. Don't edit this file, change
+# the pyclasses or their doc comments and run `cargo run -p stubgen` instead.
 
-from numpy import uint16, float32, uint8
-from enum import Enum
+"""Contains definitions for each entity.
+An entity is (pretty much) any game object, including players, weapons, hats,
+objectives, and many more."""
+
+from typing import Any, ClassVar, List, Tuple
+from .game import Class, ClassList, Team
+from .math import Vector
 
-from .math      import Vector
-from .game      import Team, Class, ClassList
-from .          import EntityID, UserID, DemoTick
 
 class UserInfo:
     @property
@@ -19,116 +18,409 @@ class UserInfo:
     def name(self) -> str: ...
 
     @property
-    def user_id(self) -> UserID: ...
+    def user_id(self) -> int: ...
 
     @property
     def steam_id(self) -> str: ...
 
     @property
-    def entity_id(self) -> EntityID: ...
+    def entity_id(self) -> int: ...
 
     @property
-    def team(self) -> Team: 
-        """Same as Player.team"""
+    def team(self) -> Team:
+        """Same as `Player.team`."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> UserInfo: ...
+    def __deepcopy__(self, memo: Any) -> UserInfo: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class PlayerState:
+    """Life state of a player. Just use `Player.is_alive`."""
+
+    Alive: ClassVar[PlayerState]
+    Dying: ClassVar[PlayerState]
+    Death: ClassVar[PlayerState]
+    Respawnable: ClassVar[PlayerState]
+    def __init__(self, number: int) -> None: ...
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> PlayerState: ...
+    def __deepcopy__(self, memo: Any) -> PlayerState: ...
 
-class PlayerState(Enum):
-    """Life state of a player. Just use Player.is_alive."""
-    Alive = uint8(0)
-    Dying = uint8(1)
-    Death = uint8(2)
-    Respawnable = uint8(3)
 
 class Player:
     """All properties of players that are tracked."""
 
     @property
     def position(self) -> Vector: ...
+
     @property
-    def health(self) -> uint16: ...
+    def health(self) -> int: ...
+
     @property
-    def max_health(self) -> uint16: ...
+    def max_health(self) -> int: ...
+
     @property
-    def player_class(self) -> Class: 
+    def player_class(self) -> Class:
         """What class they're currently playing."""
+        ...
+
     @property
-    def team(self) -> Team: 
+    def team(self) -> Team:
         """The team the player is on. Can be any of:
-         
+
         - Other (0): Used for non-player entities.
         - Spectator (1)
         - Red (2)
         - Blue (3)"""
+        ...
 
     @property
-    def view_angle(self) -> float32:
+    def view_angle(self) -> float:
         """Part of the direction that the player is looking.
         This specific measure is the angle from the positive x
         axis, moving counter-clockwise, that the player is facing.
         Should range from 0 to 2pi, or maybe -pi to pi."""
+        ...
 
     @property
-    def pitch_angle(self) -> float32:
+    def pitch_angle(self) -> float:
         """The angle the player is looking up and down.
         I believe this is measured with 0 looking neither up or down,
         and ranging from -pi/2 to pi/2. Could also range from 0 to pi."""
+        ...
+
     @property
-    def state(self) -> PlayerState: 
-        """Just use is_alive unless you really need to know"""
+    def state(self) -> PlayerState:
+        """Just use is_alive unless you really need to know."""
+        ...
+
+    @property
+    def time_since_last_hurt(self) -> float: ...
 
     @property
     def info(self) -> UserInfo | None: ...
+
     @property
-    def charge(self) -> uint8: ...
+    def charge(self) -> int: ...
+
     @property
-    def simtime(self) -> uint16: ...
+    def simtime(self) -> int: ...
+
     @property
-    def ping(self) -> uint16: ...
+    def ping(self) -> int: ...
+
     @property
     def in_pvs(self) -> bool: ...
 
     @property
-    def time_since_last_hurt(self) -> float32: ...
-    # todo: properties
+    def scout_info(self) -> ScoutInfo | None: ...
+
+    @property
+    def soldier_info(self) -> SoldierInfo | None: ...
+
+    @property
+    def pyro_info(self) -> PyroInfo | None: ...
 
-    def distance_from(self, other: Player) -> float32: ...
-    def distance_from_xy(self, other: Player) -> float32:
+    @property
+    def demoman_info(self) -> DemomanInfo | None: ...
+
+    @property
+    def heavy_info(self) -> HeavyInfo | None: ...
+
+    @property
+    def engineer_info(self) -> EngineerInfo | None: ...
+
+    @property
+    def medic_info(self) -> MedicInfo | None: ...
+
+    @property
+    def sniper_info(self) -> SniperInfo | None: ...
+
+    @property
+    def spy_info(self) -> SpyInfo | None: ...
+
+    def closest_to(self, player_list: List[Player]) -> Player: ...
+    def distance_from(self, other: Player) -> float: ...
+
+    def distance_from_xy(self, other: Player) -> float:
         """Distance, but only taking into account X and Y axes."""
         ...
-    def height_diff(self, other: Player) -> float32:
+
+    def height_diff(self, other: Player) -> float:
         """Height difference between two players.
         Negative means self is below other."""
+        ...
 
     def is_alive(self) -> bool: ...
+    def critheal_percent(self) -> float: ...
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Player: ...
+    def __deepcopy__(self, memo: Any) -> Player: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
 
 class Medigun:
     @property
-    def owner(self) -> EntityID:
+    def entity_id(self) -> int: ...
+
+    @property
+    def owner(self) -> int:
         """The medigun owner's (wielder's) entity ID."""
         ...
 
     @property
-    def charge(self) -> float32:
+    def charge(self) -> float:
         """Ubercharge %."""
         ...
-    
+
     @property
-    def heal_target(self) -> EntityID:
+    def heal_target(self) -> int:
         """The EntityID of the person being healed."""
         ...
 
     @property
     def is_healing(self) -> bool:
-        """If the medic is actually healing"""
+        """If the medic is actually healing."""
         ...
 
     @property
     def is_holstered(self) -> bool:
-        """If the medigun is currently put away"""
+        """If the medigun is currently put away."""
         ...
 
-# todo
-class Sentry: ...
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Medigun: ...
+    def __deepcopy__(self, memo: Any) -> Medigun: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class ScoutInfo:
+    ...
+
+
+class SoldierInfo:
+    ...
+
+
+class PyroInfo:
+    ...
+
+
+class DemomanInfo:
+    ...
+
+
+class HeavyInfo:
+    ...
+
+
+class EngineerInfo:
+    ...
+
+
+class MedicInfo:
+    @property
+    def is_healing(self) -> bool: ...
+
+    @property
+    def heal_target(self) -> int: ...
+
+    @property
+    def last_heal_target(self) -> int: ...
+
+
+class SniperInfo:
+    ...
+
+
+class SpyInfo:
+    ...
+
+
+class Sentry:
+    @property
+    def entity(self) -> int: ...
+
+    @property
+    def builder(self) -> int: ...
+
+    @property
+    def position(self) -> Vector: ...
+
+    @property
+    def level(self) -> int: ...
+
+    @property
+    def max_health(self) -> int: ...
+
+    @property
+    def health(self) -> int: ...
+
+    @property
+    def building(self) -> bool: ...
+
+    @property
+    def sapped(self) -> bool: ...
+
+    @property
+    def team(self) -> Team: ...
+
+    @property
+    def angle(self) -> float: ...
+
+    @property
+    def player_controlled(self) -> bool: ...
+
+    @property
+    def auto_aim_target(self) -> int: ...
+
+    @property
+    def shells(self) -> int: ...
+
+    @property
+    def rockets(self) -> int: ...
+
+    @property
+    def is_mini(self) -> bool: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Sentry: ...
+    def __deepcopy__(self, memo: Any) -> Sentry: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class Dispenser:
+    @property
+    def entity(self) -> int: ...
+
+    @property
+    def builder(self) -> int: ...
+
+    @property
+    def position(self) -> Vector: ...
+
+    @property
+    def level(self) -> int: ...
+
+    @property
+    def max_health(self) -> int: ...
+
+    @property
+    def health(self) -> int: ...
+
+    @property
+    def building(self) -> bool: ...
+
+    @property
+    def sapped(self) -> bool: ...
+
+    @property
+    def team(self) -> Team: ...
+
+    @property
+    def angle(self) -> float: ...
+
+    @property
+    def healing(self) -> List[int]: ...
+
+    @property
+    def metal(self) -> int: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Dispenser: ...
+    def __deepcopy__(self, memo: Any) -> Dispenser: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class Teleporter:
+    @property
+    def entity(self) -> int: ...
+
+    @property
+    def builder(self) -> int: ...
+
+    @property
+    def position(self) -> Vector: ...
+
+    @property
+    def level(self) -> int: ...
+
+    @property
+    def max_health(self) -> int: ...
+
+    @property
+    def health(self) -> int: ...
+
+    @property
+    def building(self) -> bool: ...
+
+    @property
+    def sapped(self) -> bool: ...
+
+    @property
+    def team(self) -> Team: ...
+
+    @property
+    def angle(self) -> float: ...
+
+    @property
+    def is_entrance(self) -> bool: ...
+
+    @property
+    def other_end(self) -> int: ...
+
+    @property
+    def recharge_time(self) -> float: ...
+
+    @property
+    def recharge_duration(self) -> float: ...
+
+    @property
+    def times_used(self) -> int: ...
+
+    @property
+    def yaw_to_exit(self) -> float: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Teleporter: ...
+    def __deepcopy__(self, memo: Any) -> Teleporter: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class ProjectileType:
+    Unknown: ClassVar[ProjectileType]
+    Rocket: ClassVar[ProjectileType]
+    GrenadePipe: ClassVar[ProjectileType]
+    StickyBomb: ClassVar[ProjectileType]
+    CrossbowBolt: ClassVar[ProjectileType]
+    HuntsmanArrow: ClassVar[ProjectileType]
+    BallOrnament: ClassVar[ProjectileType]
+    Flare: ClassVar[ProjectileType]
+    BisonBolt: ClassVar[ProjectileType]
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> ProjectileType: ...
+    def __deepcopy__(self, memo: Any) -> ProjectileType: ...
 
-class Dispenser: ...
 
-class Teleporter: ...
\ No newline at end of file
+class Projectile:
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Projectile: ...
+    def __deepcopy__(self, memo: Any) -> Projectile: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
@@ -1,45 +1,76 @@
-"""
-Constructs that record important game events that occur during the demo.
-"""
+# Generated by stubgen from the Rust source. Don't edit this file, change
+# the pyclasses or their doc comments and run `cargo run -p stubgen` instead.
 
-from numpy import uint8
-from typing import List
+"""Constructs that record important game events that occur during the demo."""
+
+from pandas import DataFrame
+from typing import Any, List, Tuple
+from .game import Class, Team
+from .math import Vector
 
-from .game  import Team
-from .      import UserID, EntityID, DemoTick
 
 class Kill:
     """By God, he's dead!
-    
+
     In all seriousness, this is a record of a kill."""
 
     @property
-    def dead_id(self) -> UserID:
+    def weapon(self) -> str: ...
+
+    @property
+    def weapon_id(self) -> int: ...
+
+    @property
+    def dead_rocketjumping(self) -> bool:
+        """If the killed / dead player was rocket jumping when they died."""
+        ...
+
+    @property
+    def dead_id(self) -> int:
         """User ID of the killed / dead player."""
+        ...
+
     @property
-    def dead_entity(self) -> EntityID:
+    def dead_entity(self) -> int:
         """Entity ID of the killed / dead player."""
+        ...
+
     @property
-    def attacker_id(self) -> UserID:
+    def attacker_id(self) -> int:
         """User ID of the player was responsible for the final blow.
         May not be a valid ID if the player died to the environment."""
+        ...
+
     @property
-    def inflictor_id(self) -> EntityID:
+    def inflictor_id(self) -> int:
         """Entity ID of the thing responsible for the final blow. May not be a player."""
+        ...
+
     @property
-    def weapon(self) -> str: ...
-    @property
-    def assister(self) -> UserID | None:
-        """User ID of the assister. Could be none."""
-    @property
-    def dead_rocketjumping(self) -> bool:
-        """If the killed/dead player was rocket jumping when they died."""
+    def assister_id(self) -> int | None:
+        """User ID of the assister, if anyone assisted."""
+        ...
+
     @property
-    def tick(self) -> DemoTick: ...
+    def tick(self) -> int: ...
+
+    @staticmethod
+    def to_dataframe(kills: List[Kill]) -> DataFrame:
+        """One row per kill. Use `DemoData.kills_to_dataframe` to also get the
+        class and team of everyone involved."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Kill: ...
+    def __deepcopy__(self, memo: Any) -> Kill: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
 
 class Capture:
     @property
-    def cp_index(self) -> uint8:
+    def cp_index(self) -> int:
         """The index of the capture point. 0 is typically blue last or the
         midpoint in KOTH, with 4 being red last, but this is not guaranteed.
         The index of each point will be in order though."""
@@ -56,11 +87,181 @@ class Capture:
         ...
 
     @property
-    def cappers(self) -> List[UserID]:
-        """Who was standing on the point when it was captured."""
+    def cappers(self) -> List[int]:
+        """User IDs of who was standing on the point when it was captured."""
+        ...
+
+    @property
+    def tick(self) -> int:
+        """When the capture occurred."""
+        ...
+
+    @staticmethod
+    def to_dataframe(captures: List[Capture]) -> DataFrame: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Capture: ...
+    def __deepcopy__(self, memo: Any) -> Capture: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class Ubercharge:
+    @property
+    def medic_id(self) -> int:
+        """User ID of the medic that deployed."""
+        ...
+
+    @property
+    def ubered_id(self) -> int:
+        """User ID of the player the medic was healing when they deployed."""
+        ...
+
+    @property
+    def tick(self) -> int: ...
+
+    @staticmethod
+    def to_dataframe(ubercharges: List[Ubercharge]) -> DataFrame: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Ubercharge: ...
+    def __deepcopy__(self, memo: Any) -> Ubercharge: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class Damage:
+    """A player taking damage."""
+
+    @property
+    def victim_id(self) -> int:
+        """User ID of the player that was hurt."""
+        ...
+
+    @property
+    def attacker_id(self) -> int:
+        """User ID of the player that did the damage.
+        May not be a valid ID if the damage came from the environment."""
+        ...
+
+    @property
+    def amount(self) -> int: ...
+
+    @property
+    def health(self) -> int:
+        """Health the victim had left afterwards."""
+        ...
+
+    @property
+    def crit(self) -> bool: ...
+
+    @property
+    def mini_crit(self) -> bool: ...
+
+    @property
+    def weapon_id(self) -> int: ...
+
+    @property
+    def tick(self) -> int: ...
+
+    @staticmethod
+    def to_dataframe(damage: List[Damage]) -> DataFrame: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Damage: ...
+    def __deepcopy__(self, memo: Any) -> Damage: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class Heal:
+    """A player being healed by a teammate."""
+
+    @property
+    def patient_id(self) -> int:
+        """User ID of the player that was healed."""
+        ...
+
+    @property
+    def healer_id(self) -> int:
+        """User ID of the player that did the healing."""
         ...
 
     @property
-    def tick(self) -> DemoTick:
-        """When the capture occurred"""
-        ...
\ No newline at end of file
+    def amount(self) -> int: ...
+
+    @property
+    def tick(self) -> int: ...
+
+    @staticmethod
+    def to_dataframe(heals: List[Heal]) -> DataFrame: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Heal: ...
+    def __deepcopy__(self, memo: Any) -> Heal: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class Spawn:
+    """A player spawning, at the start of a round or after dying."""
+
+    @property
+    def user_id(self) -> int: ...
+
+    @property
+    def team(self) -> Team: ...
+
+    @property
+    def player_class(self) -> Class: ...
+
+    @property
+    def position(self) -> Vector:
+        """Where the player was at the end of the tick they spawned on."""
+        ...
+
+    @property
+    def tick(self) -> int: ...
+
+    @staticmethod
+    def to_dataframe(spawns: List[Spawn]) -> DataFrame: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Spawn: ...
+    def __deepcopy__(self, memo: Any) -> Spawn: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class TickEvents:
+    """Every event that happened during a single tick. Passed to `on_tick`."""
+
+    @property
+    def kills(self) -> List[Kill]: ...
+
+    @property
+    def captures(self) -> List[Capture]: ...
+
+    @property
+    def ubercharges(self) -> List[Ubercharge]: ...
+
+    @property
+    def damage(self) -> List[Damage]: ...
+
+    @property
+    def heals(self) -> List[Heal]: ...
+
+    @property
+    def spawns(self) -> List[Spawn]: ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> TickEvents: ...
+    def __deepcopy__(self, memo: Any) -> TickEvents: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
@@ -1,71 +1,212 @@
-"""
-Game-related classes that translate to generic information about the game.
-Typically not directly related to players.
-"""
+# Generated by stubgen from the Rust source. Don't edit this file, change
+# the pyclasses or their doc comments and run `cargo run -p stubgen` instead.
 
-from typing import List, Tuple
-from enum import Enum
-from numpy import uint8
+"""Game-related classes that translate to generic information about the game.
+Typically not directly related to players."""
+
+from typing import Any, ClassVar, List, Tuple
+from .math import Vector
 
-from .      import DemoTick
-from .math  import Vector
 
 class World:
     """Defines the boundaries of the world as given by the demofile.
     You can expect bound_min.x < bound_max.x, and so on."""
+
+    @property
+    def bound_min(self) -> Vector:
+        """The minimum."""
+        ...
+
+    @property
+    def bound_max(self) -> Vector:
+        """The maximum."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> World: ...
+    def __deepcopy__(self, memo: Any) -> World: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class Class:
+    """Representation of each class in the game as an enum.
+
+    Compares equal to and hashes the same as its value, so
+    `player.player_class == 5` works and classes can be mixed with ints in
+    dicts and sets. Names don't compare equal, as they hash differently; use
+    `Class("medic")` to look one up. Ordered by value."""
+
+    Other: ClassVar[Class]
+    Scout: ClassVar[Class]
+    Sniper: ClassVar[Class]
+    Soldier: ClassVar[Class]
+    Demoman: ClassVar[Class]
+    Medic: ClassVar[Class]
+    Heavy: ClassVar[Class]
+    Pyro: ClassVar[Class]
+    Spy: ClassVar[Class]
+    Engineer: ClassVar[Class]
+
     @property
-    def bound_min(self) -> Vector: ...
+    def name(self) -> str: ...
 
     @property
-    def bound_max(self) -> Vector: ...
-
-class Class(Enum):
-    Other = uint8(0)
-    Scout = uint8(1)
-    Sniper = uint8(2)
-    Soldier = uint8(3)
-    Demoman = uint8(4)
-    Medic = uint8(5)
-    Heavy = uint8(6)
-    Pyro = uint8(7)
-    Spy = uint8(8)
-    Engineer = uint8(9)
-
-class ClassList(List): 
-    """Counts how many times a player has switched to a class.
-    List items are tuples in the form of (class, count)."""
+    def value(self) -> int: ...
+
+    def __init__(self, value: Any) -> None:
+        """Takes either the value or the name of the class."""
+        ...
+
+    @staticmethod
+    def from_str(name: str) -> Class:
+        """Case insensitive. Also takes "demo", "heavyweapons" and "engi".
+        Raises ValueError for anything else."""
+        ...
+
+    @staticmethod
+    def all() -> List[Class]:
+        """Every class, in order of their values."""
+        ...
+
+    def __str__(self) -> str: ...
+
+    def __hash__(self) -> int:
+        """Same as the hash of the value, since they compare equal."""
+        ...
+
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __lt__(self, other: object) -> bool: ...
+    def __le__(self, other: object) -> bool: ...
+    def __gt__(self, other: object) -> bool: ...
+    def __ge__(self, other: object) -> bool: ...
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Class: ...
+    def __deepcopy__(self, memo: Any) -> Class: ...
+
+
+class ClassList:
+    """How many times a player spawned as each class. Behaves like a
+    `collections.Counter` of classes: unplayed classes have a count of 0 and
+    are not included in `len`, iteration, `keys`, `values` or `items`."""
+
     def __len__(self) -> int:
-        """Number of classes played by the player."""
+        """Number of classes played."""
+        ...
 
-    def __contains__(self, item: Class) -> bool:
+    def __contains__(self, key: Any) -> bool:
         """Check to see if a player has played this class."""
+        ...
+
+    def __getitem__(self, key: Any) -> int:
+        """Number of spawns as the class. 0 if it wasn't played."""
+        ...
+
+    def __iter__(self) -> ClassListIter:
+        """Iterates over the classes played."""
+        ...
+
+    def __repr__(self) -> str: ...
+
+    def get(self, key: Any, default: Any | None = None) -> Any:
+        """The count for `key`, or `default` if the class wasn't played."""
+        ...
+
+    def keys(self) -> List[Class]: ...
+    def values(self) -> List[int]: ...
+    def items(self) -> List[Tuple[Class, int]]: ...
+
+    def most_common(self) -> List[Tuple[Class, int]]:
+        """Classes played and their counts, most played first."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> ClassList: ...
+    def __deepcopy__(self, memo: Any) -> ClassList: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
 
-    def __iter__(self): #TODO: iter?
-        """Iterates over all classes that have been played."""
 
 class ClassListIter:
     def __iter__(self) -> ClassListIter: ...
-    def __next__(self) -> Tuple[Class, uint8] | None: ...
+    def __next__(self) -> Class: ...
+
+
+class Team:
+    """Compares equal to and hashes the same as its value, but not its name,
+    like Class. Use `Team("red")` to look one up by name."""
+
+    Other: ClassVar[Team]
+    Spectator: ClassVar[Team]
+    Red: ClassVar[Team]
+    Blue: ClassVar[Team]
+
+    @property
+    def name(self) -> str: ...
+
+    @property
+    def value(self) -> int: ...
 
-class Team(Enum):
-    Other = uint8(0)
-    Spectator = uint8(1)
-    Red = uint8(2)
-    Blue = uint8(3)
+    def __init__(self, value: Any) -> None:
+        """Takes either the value or the name of the team."""
+        ...
+
+    @staticmethod
+    def from_str(name: str) -> Team:
+        """Case insensitive. Also takes "blu". Raises ValueError for anything else."""
+        ...
+
+    @staticmethod
+    def all() -> List[Team]:
+        """Every team, in order of their values."""
+        ...
 
-    def is_player(self) -> bool: 
+    def is_player(self) -> bool:
         """If the team actually supports having alive players on it.
         The only teams to do so are Red and Blue."""
         ...
 
+    def __str__(self) -> str: ...
+
+    def __hash__(self) -> int:
+        """Same as the hash of the value, since they compare equal."""
+        ...
+
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __lt__(self, other: object) -> bool: ...
+    def __le__(self, other: object) -> bool: ...
+    def __gt__(self, other: object) -> bool: ...
+    def __ge__(self, other: object) -> bool: ...
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Team: ...
+    def __deepcopy__(self, memo: Any) -> Team: ...
+
+
 class Round:
     """Defines information on a round, such as the tick it started, ended,
     and who won."""
+
     @property
-    def start_tick(self) -> DemoTick: ...
+    def start_tick(self) -> int: ...
+
     @property
-    def end_tick(self) -> DemoTick: ...
+    def end_tick(self) -> int: ...
+
     @property
     def winner(self) -> Team: ...
 
-    def is_tie(self) -> bool: ...
\ No newline at end of file
+    def contains(self, tick: int) -> bool:
+        """If `tick` is in the round. A round that hasn't ended has an end tick
+        of 0, and contains every tick from its start."""
+        ...
+
+    def is_tie(self) -> bool: ...
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Round: ...
+    def __deepcopy__(self, memo: Any) -> Round: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
@@ -0,0 +1,138 @@
+# Generated by stubgen from the Rust source. Don't edit this file, change
+# the pyclasses or their doc comments and run `cargo run -p stubgen` instead.
+
+"""Who players are across demos.
+
+User IDs only last for a single connection to a single server, and names
+change whenever players feel like it. SteamIDs don't, so players are
+followed from demo to demo by theirs."""
+
+from typing import Any, Dict, List, Tuple
+from .demo import DemoData
+
+
+class SteamId:
+    """A Steam account, stored as its SteamID64.
+
+    Can be made from a SteamID64 as an int or string, a SteamID3 like
+    `[U:1:22202]`, or a SteamID2 like `STEAM_0:0:11101`. Bots have no SteamID."""
+
+    @property
+    def steam64(self) -> int: ...
+
+    @property
+    def account_id(self) -> int:
+        """The account ID, which is what SteamID3s and SteamID2s are made of."""
+        ...
+
+    @property
+    def steam3(self) -> str:
+        """`[U:1:<account ID>]`."""
+        ...
+
+    def __init__(self, value: Any) -> None: ...
+
+    def __str__(self) -> str:
+        """The SteamID3."""
+        ...
+
+    def __int__(self) -> int: ...
+    def __hash__(self) -> int: ...
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> SteamId: ...
+    def __deepcopy__(self, memo: Any) -> SteamId: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class PlayerIdentity:
+    """A player, and every name they played under."""
+
+    @property
+    def steam_id(self) -> SteamId: ...
+
+    @property
+    def name(self) -> str:
+        """The most recent name."""
+        ...
+
+    @property
+    def names(self) -> List[str]:
+        """Every name, in the order they were first used."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> PlayerIdentity: ...
+    def __deepcopy__(self, memo: Any) -> PlayerIdentity: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class IdentityMap:
+    """The players of a single demo, and which user IDs they had in it.
+    Players that reconnect get a new user ID, so one player can have several."""
+
+    @property
+    def players(self) -> List[PlayerIdentity]:
+        """Ordered by SteamID."""
+        ...
+
+    @property
+    def user_ids(self) -> Dict[int, SteamId]:
+        """User ID to SteamID."""
+        ...
+
+    def steam_id(self, user_id: int) -> SteamId | None:
+        """The SteamID of the player with `user_id`."""
+        ...
+
+    def get(self, user_id: int) -> PlayerIdentity | None:
+        """The player with `user_id`."""
+        ...
+
+    def user_ids_of(self, steam_id: SteamId) -> List[int]:
+        """Every user ID the player with `steam_id` had."""
+        ...
+
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> IdentityMap: ...
+    def __deepcopy__(self, memo: Any) -> IdentityMap: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
+
+
+class PlayerRegistry:
+    """Players over many demos, keyed by SteamID, with every name they used."""
+
+    @property
+    def players(self) -> List[PlayerIdentity]:
+        """Every player, ordered by SteamID."""
+        ...
+
+    def __init__(self, demos: List[DemoData] = ...) -> None: ...
+
+    def add(self, demo: DemoData) -> None:
+        """Adds the players of `demo`. Add demos oldest first, so each player's
+        `name` ends up being the most recent one."""
+        ...
+
+    def get(self, steam_id: SteamId) -> PlayerIdentity | None: ...
+
+    def identify(self, demo: DemoData, user_id: int) -> PlayerIdentity | None:
+        """Who had `user_id` in `demo`, if they were added."""
+        ...
+
+    def find(self, name: str) -> List[PlayerIdentity]:
+        """Players that ever used a name containing `name`, ignoring case."""
+        ...
+
+    def __len__(self) -> int: ...
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> PlayerRegistry: ...
+    def __deepcopy__(self, memo: Any) -> PlayerRegistry: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
@@ -1,37 +1,45 @@
-"""
-The 2D and 3D vectors used by the library, including extra functions
-necessary for __math__.
-"""
+# Generated by stubgen from the Rust source. Don't edit this file, change
+# the pyclasses or their doc comments and run `cargo run -p stubgen` instead.
+
+"""The 2D and 3D vectors used by the library, including extra functions
+necessary for __math__."""
+
+from typing import Any, Tuple
 
-from numpy import float32
 
 class Vector:
-    """3D vector / point"""
-    def __init__(x:float32, y:float32, z:float32) -> Vector: ...
-    
+    """3D vector / point. Compares equal if `self - other ~= 0`."""
+
     @property
-    def x(self) -> float32: ...
+    def x(self) -> float: ...
     @x.setter
-    def x(self, value: float32): ...
+    def x(self, value: float) -> None: ...
+
     @property
-    def y(self) -> float32: ...
+    def y(self) -> float: ...
     @y.setter
-    def y(self, value: float32): ...
+    def y(self, value: float) -> None: ...
+
     @property
-    def z(self) -> float32: ...
+    def z(self) -> float: ...
     @z.setter
-    def z(self, value: float32): ...
+    def z(self, value: float) -> None: ...
 
-    def dot(self, other: Vector) -> float32: ...
-    def dist_to(self, other: Vector) -> float32: ...
-    def angle_btwn(self, other: Vector) -> float32: ...
+    def __init__(self, x: float, y: float, z: float) -> None: ...
+    def dot(self, other: Vector) -> float: ...
+    def dist_to(self, other: Vector) -> float: ...
 
-    def abs2(self) -> float32:
-        """|v|^2"""
+    def cross(self, other: Vector) -> Vector:
+        """3D Cross product."""
         ...
 
-    def len(self) -> float32: ...
+    def angle_btwn(self, other: Vector) -> float: ...
 
+    def abs2(self) -> float:
+        """|v|^2"""
+        ...
+
+    def len(self) -> float: ...
     def xy(self) -> VectorXY: ...
 
     def normalized(self) -> Vector:
@@ -39,59 +47,66 @@ class Vector:
         same direction but length ~= 1"""
         ...
 
-    def cross(self, other: Vector) -> Vector: 
-        """3D cross product."""
-        ...
-
-    # Operators
-    
     def __add__(self, other: Vector) -> Vector: ...
     def __sub__(self, other: Vector) -> Vector: ...
     def __neg__(self) -> Vector: ...
-    def __eq__(self, other: Vector) -> bool:
-        """Equality. Basically, if self - other ~= 0."""
+    def __mul__(self, other: float) -> Vector: ...
+    def __rmul__(self, other: float) -> Vector: ...
+    def __truediv__(self, other: float) -> Vector: ...
+    def __rtruediv__(self, other: float) -> Vector: ...
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> Vector: ...
+    def __deepcopy__(self, memo: Any) -> Vector: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
 
-    # Scalar multiplication: k*x
-    def __mul__(self, other: float32) -> Vector: ...
-    def __rmul__(self, other: float32) -> Vector: ...
-    def __truediv__(self, other: float32) -> Vector: ...
-    def __rtruediv__(self, other: float32) -> Vector: ...
 
 class VectorXY:
-    """2D vector / point"""
-    def __init__(x:float32, y:float32) -> VectorXY: ...
+    """VectorXY
+    2D vector / point."""
+
     @property
-    def x(self) -> float32: ...
+    def x(self) -> float: ...
     @x.setter
-    def x(self, value: float32): ...
+    def x(self, value: float) -> None: ...
+
     @property
-    def y(self) -> float32: ...
+    def y(self) -> float: ...
     @y.setter
-    def y(self, value: float32): ...
+    def y(self, value: float) -> None: ...
 
-    def dot(self, other: VectorXY) -> float32: ...
-    def dist_to(self, other: VectorXY) -> float32: ...
-    def angle_btwn(self, other: VectorXY) -> float32: ...
-    def abs2(self) -> float32:
+    def __init__(self, x: float, y: float) -> None: ...
+    def dot(self, other: VectorXY) -> float: ...
+    def dist_to(self, other: VectorXY) -> float: ...
+
+    def abs2(self) -> float:
         """|v|^2"""
-    def len(self) -> float32: ...
-    def xyz(self) -> Vector: 
+        ...
+
+    def len(self) -> float:
+        """|v|"""
+        ...
+
+    def xyz(self) -> Vector:
         """Literally just appends 0 to the Z coordinate."""
+        ...
 
     def normalized(self) -> VectorXY:
         """Doesn't change self; simply returns a vector with the
         same direction but length ~= 1"""
+        ...
 
-    # Operators
-    
     def __add__(self, other: VectorXY) -> VectorXY: ...
     def __sub__(self, other: VectorXY) -> VectorXY: ...
     def __neg__(self) -> VectorXY: ...
-    def __eq__(self, other: VectorXY) -> bool:
-        """Equality. Basically, if self - other ~= 0."""
-
-    # Scalar multiplication: k*x
-    def __mul__(self, other: float32) -> VectorXY: ...
-    def __rmul__(self, other: float32) -> VectorXY: ...
-    def __truediv__(self, other: float32) -> VectorXY: ...
-    def __rtruediv__(self, other: float32) -> VectorXY: ...
+    def __mul__(self, other: float) -> VectorXY: ...
+    def __rmul__(self, other: float) -> VectorXY: ...
+    def __truediv__(self, other: float) -> VectorXY: ...
+    def __rtruediv__(self, other: float) -> VectorXY: ...
+    def __reduce__(self) -> Tuple[Any, Tuple[bytes]]: ...
+    def __copy__(self) -> VectorXY: ...
+    def __deepcopy__(self, memo: Any) -> VectorXY: ...
+    def __eq__(self, other: object) -> bool: ...
+    def __ne__(self, other: object) -> bool: ...
+    def __repr__(self) -> str: ...
@@ -0,0 +1,74 @@
+# Generated by stubgen from the Rust source. Don't edit this file, change
+# the pyclasses or their doc comments and run `cargo run -p stubgen` instead.
+
+"""Handles to demos being parsed in the background.
+
+The GIL is not held while a demo is parsing, so other Python threads (and
+Jupyter) keep running."""
+
+from .demo import DemoData
+
+
+class ParseHandle:
+    """A demo being parsed on another thread. Returned from `tf2dal.parse_async`."""
+
+    @property
+    def demo_filename(self) -> str: ...
+
+    @property
+    def max_tick(self) -> int:
+        """Number of ticks in the demo. 0 until the header has been read."""
+        ...
+
+    @property
+    def current_tick(self) -> int:
+        """The last tick the parser reported."""
+        ...
+
+    @property
+    def error(self) -> str | None:
+        """Why the parse failed, if it did."""
+        ...
+
+    @property
+    def cancelled(self) -> bool: ...
+
+    @property
+    def progress(self) -> float:
+        """The fraction of the demo that has been parsed, from 0 to 1."""
+        ...
+
+    @property
+    def done(self) -> bool:
+        """True once the parse has finished, failed, or been cancelled."""
+        ...
+
+    @property
+    def paused(self) -> bool: ...
+
+    def poll(self) -> None:
+        """Takes in any progress the parse has made. Never blocks."""
+        ...
+
+    def cancel(self) -> None:
+        """Stops the parse and waits for the parse thread to exit."""
+        ...
+
+    def pause(self) -> None:
+        """Pauses the parse until `resume` is called."""
+        ...
+
+    def resume(self) -> None: ...
+
+    def wait(self, timeout: float | None = None) -> bool:
+        """Blocks until the parse is done, or until `timeout` seconds have passed.
+        The GIL is released while waiting. Returns `done`."""
+        ...
+
+    def result(self, timeout: float | None = None) -> DemoData:
+        """Waits for the parse to finish and returns the data. The data is moved
+        out of the handle, so this can only be called once.
+
+        Raises RuntimeError if the parse failed, was cancelled, timed out, or
+        the result was already taken."""
+        ...
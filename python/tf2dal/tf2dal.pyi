@@ -1,31 +1,80 @@
-# demo_analysis_lib
-# Contains the interface for items in the library.
+# Generated by stubgen from the Rust source. Don't edit this file, change
+# the pyclasses or their doc comments and run `cargo run -p stubgen` instead.
 
-# Types contained in this file are considered basic; essentially, they don't depend
-# on each other (or barely depend on each other).
+"""Demo Analysis Library
 
-"""
-Demo Analysis Library
-"""
+Every class can be pickled (so works with multiprocessing, joblib, etc.),
+copied with `copy.copy`/`copy.deepcopy`, and printed. Data classes compare
+by value with `==`."""
 
-print("Importing Demo Analysis Library!")
+from typing import Any, Dict, List
+from .analysis import Season
+from .demo import DemoData
+from .identity import SteamId
+from .parse import ParseHandle
 
-from typing import List, TypeAlias
-from numpy import uint32, uint16
+def load_demo(
+    fname: str,
+    start_tick: int | None = None,
+    end_tick: int | None = None,
+    rounds: List[int] | None = None,
+    stop_early: bool = True,
+    on_tick: Any | None = None,
+    every: int | None = None,
+    events: List[str] | None = None,
+    store_ticks: bool = True,
+) -> DemoData:
+    """Loads a demo and returns the associated data.
 
-from .demo  import DemoData
+    `start_tick` and `end_tick` limit the recorded ticks to an inclusive range,
+    and `rounds` limits them to the rounds with those indices (0 is the first
+    round). Events outside of those are not recorded either.
+    With `stop_early`, parsing stops as soon as nothing else would be recorded.
 
-DemoTick: TypeAlias = uint32
-EntityID: TypeAlias = uint32
-UserID: TypeAlias = uint16
+    `on_tick(tick_data, tick_events)` is called while parsing with each
+    recorded tick and the events that happened on it. `every` only calls it
+    every N recorded ticks, and `events` ("kill", "capture", "ubercharge", "damage", "heal", "spawn")
+    only on ticks with one of those events; with both, either will do. An
+    exception raised by `on_tick` stops the parse and is re-raised.
+    With `store_ticks=False` the result has no `tick_states`, so whole demos
+    can be processed without keeping every tick in memory.
 
-## Demo Loading
-
-def load_demo(file_path: str) -> DemoData:
-    """Loads a demo and returns the associated data."""
+    The GIL is released while parsing, here and in the other load functions,
+    so other Python threads (and Jupyter) keep running. It is only taken
+    back to call `on_tick`."""
     ...
 
-def load_demo_rounds(file_path: str) -> List[DemoData]:
+def load_demo_rounds(fname: str) -> List[DemoData]:
     """Loads a demo and returns data split into rounds.
-    Each DemoData in the list should be a seperate round."""
+    Each DemoData in the list should be a separate round."""
+    ...
+
+def load_demos(fnames: List[str], threads: int = 4) -> List[DemoData | None]:
+    """Parses many demos at once, `threads` at a time. The returned list is in
+    the same order as `fnames`, with None for any demo that failed to parse."""
+    ...
+
+def load_season(
+    fnames: List[str],
+    threads: int = 4,
+    cache_dir: str | None = None,
+    teams: Dict[str, List[SteamId]] = ...,
+    our_team: str | None = None,
+) -> Season:
+    """Parses many demos, `threads` at a time and without tick states, into a
+    `Season`. Saved parses (`.tf2dal` files from `DemoData.save`) can be
+    given in place of demos. With `cache_dir`, each parse is saved there and
+    read back instead of parsing the demo again, until the demo changes.
+    Demos that fail to parse are left out."""
+    ...
+
+def parse_async(
+    fname: str,
+    start_tick: int | None = None,
+    end_tick: int | None = None,
+    rounds: List[int] | None = None,
+    stop_early: bool = True,
+) -> ParseHandle:
+    """Starts parsing a demo on another thread and returns immediately.
+    Takes the same tick and round options as `load_demo`."""
     ...
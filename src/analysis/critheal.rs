@@ -0,0 +1,228 @@
+//! How crit heals were built up, used and wasted.
+//!
+//! Crit heals build up from 10 to 15 seconds after a player was last hurt,
+//! and at full crit heals a medigun heals three times as fast.
+
+use std::collections::BTreeMap;
+
+use pyo3::prelude::*;
+use serde::{Serialize, Deserialize};
+
+use crate::analysisresult::{AnalysisResult, Table};
+use crate::types::demo::DemoData;
+use crate::types::entities::Player;
+use crate::types::game::{Class, Round, Team};
+
+/// Health per second a medigun heals at full crit heals.
+const CRIT_HEAL_RATE: f32 = 72.0;
+
+/// Crit heals of a single player.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CritHealPlayer {
+    pub user_id: u16,
+    pub name: String,
+    pub team: Team,
+    /// Seconds alive.
+    pub alive_time: f32,
+    /// Seconds alive with any crit heals built up.
+    pub eligible_time: f32,
+    /// Seconds alive with full crit heals.
+    pub full_critheal_time: f32,
+    /// Seconds being healed by a medic with any crit heals built up.
+    pub crit_healed_time: f32,
+    /// Seconds with full crit heals and missing health while a medic
+    /// on their team was alive but not healing them.
+    pub wasted_time: f32,
+    /// Estimated healing lost in `wasted_time`: what a medic could have
+    /// healed at the full crit rate, up to the health the player was missing.
+    pub wasted_healing: f32,
+    /// Healing received from medics.
+    pub healing_received: u32,
+    /// Healing received from medics while at full crit heals.
+    pub crit_healing_received: u32,
+}
+
+/// Who a single medic chose to heal.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CritHealMedic {
+    pub user_id: u16,
+    pub name: String,
+    pub team: Team,
+    /// Seconds spent healing a teammate.
+    pub healing_time: f32,
+    /// Seconds spent healing while another hurt teammate had more or less
+    /// crit heals than the patient.
+    pub choice_time: f32,
+    /// Seconds of `choice_time` the patient had less crit heals than
+    /// another hurt teammate.
+    pub low_critheal_time: f32,
+}
+
+#[pymethods]
+impl CritHealMedic {
+    /// How often the medic healed the patient with less crit heals when
+    /// there was a choice. 0 if there never was.
+    #[getter]
+    fn low_critheal_fraction(&self) -> f32 {
+        if self.choice_time > 0.0 {self.low_critheal_time / self.choice_time} else {0.0}
+    }
+}
+
+/// The crit heals of everyone in a demo or round. Made by `DemoData.critheals`.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CritHealReport {
+    /// Ordered by team, then user ID.
+    pub players: Vec<CritHealPlayer>,
+    /// Ordered by team, then user ID.
+    pub medics: Vec<CritHealMedic>,
+}
+
+fn name_of(player: &Player) -> String {
+    player.info.as_ref().map_or(String::new(), |info| info.name.clone())
+}
+
+/// The crit heal report of every tick of `demo`, or only the ticks of `round`.
+pub fn critheal_report(demo: &DemoData, round: Option<&Round>) -> CritHealReport {
+    let in_round = |tick: u32| round.map_or(true, |r| r.contains(tick));
+    let dt = 1.0 / demo.tick_rate();
+
+    let mut players = BTreeMap::<u16, CritHealPlayer>::new();
+    let mut medics = BTreeMap::<u16, CritHealMedic>::new();
+
+    for tick in demo.sorted_ticks().into_iter().filter(|t| in_round(*t)) {
+        let state = &demo.tick_states[&tick];
+        let targets = state.heal_targets();
+        let living = state.players.iter()
+            .filter(|p| p.is_alive() && p.info.is_some())
+            .collect::<Vec<&Player>>();
+
+        for player in &living {
+            let user_id = player.info.as_ref().unwrap().user_id;
+            let entry = players.entry(user_id).or_default();
+            entry.user_id = user_id;
+            entry.name = name_of(player);
+            entry.team = player.team;
+            entry.alive_time += dt;
+
+            let critheal = player.critheal_percent();
+            let healed = targets.iter().any(|(_, patient)| patient.entity == player.entity);
+            if critheal > 0.0 {
+                entry.eligible_time += dt;
+                if healed {
+                    entry.crit_healed_time += dt;
+                }
+            }
+            if critheal < 1.0 {
+                continue;
+            }
+            entry.full_critheal_time += dt;
+
+            let missing = player.max_health.saturating_sub(player.health) as f32;
+            let medic_alive = living.iter()
+                .any(|p| p.team == player.team && p.class == Class::Medic && p.entity != player.entity);
+            if !healed && missing > 0.0 && medic_alive {
+                entry.wasted_time += dt;
+                entry.wasted_healing += (CRIT_HEAL_RATE * dt).min(missing);
+            }
+        }
+
+        for (medic, patient) in &targets {
+            let user_id = medic.info.as_ref().unwrap().user_id;
+            let entry = medics.entry(user_id).or_default();
+            entry.user_id = user_id;
+            entry.name = name_of(medic);
+            entry.team = medic.team;
+            entry.healing_time += dt;
+
+            let others = living.iter()
+                .filter(|p| p.team == medic.team && p.entity != medic.entity && p.entity != patient.entity)
+                .filter(|p| p.health < p.max_health)
+                .map(|p| p.critheal_percent())
+                .collect::<Vec<f32>>();
+            let critheal = patient.critheal_percent();
+            if others.iter().any(|c| *c != critheal) {
+                entry.choice_time += dt;
+            }
+            if others.iter().any(|c| *c > critheal) {
+                entry.low_critheal_time += dt;
+            }
+        }
+    }
+
+    for heal in demo.heals.iter().filter(|h| in_round(h.tick)) {
+        let is_medic = demo.player_at(heal.tick, heal.healer_id).is_some_and(|p| p.class == Class::Medic);
+        let (Some(patient), true) = (demo.player_at(heal.tick, heal.patient_id), is_medic) else {
+            continue;
+        };
+        if let Some(entry) = players.get_mut(&heal.patient_id) {
+            entry.healing_received += heal.amount as u32;
+            if patient.critheal_percent() >= 1.0 {
+                entry.crit_healing_received += heal.amount as u32;
+            }
+        }
+    }
+
+    let mut players = players.into_values().collect::<Vec<_>>();
+    players.sort_by_key(|p| (p.team, p.user_id));
+    let mut medics = medics.into_values().collect::<Vec<_>>();
+    medics.sort_by_key(|m| (m.team, m.user_id));
+    CritHealReport { players, medics }
+}
+
+impl CritHealReport {
+    /// The report as tables, for the viewer and CSV export.
+    pub fn to_result(&self) -> AnalysisResult {
+        let players = Table {
+            name: "players".to_string(),
+            columns: ["name", "team", "alive", "eligible", "full crits", "crit healed",
+                      "wasted", "wasted healing", "healing", "crit healing"]
+                .map(String::from).to_vec(),
+            rows: self.players.iter().map(|p| vec![
+                p.name.clone(),
+                p.team.name().to_string(),
+                format!("{:.1}", p.alive_time),
+                format!("{:.1}", p.eligible_time),
+                format!("{:.1}", p.full_critheal_time),
+                format!("{:.1}", p.crit_healed_time),
+                format!("{:.1}", p.wasted_time),
+                format!("{:.0}", p.wasted_healing),
+                p.healing_received.to_string(),
+                p.crit_healing_received.to_string(),
+            ]).collect(),
+        };
+
+        let medics = Table {
+            name: "medics".to_string(),
+            columns: ["name", "team", "healing", "choice", "low crits", "low crits %"]
+                .map(String::from).to_vec(),
+            rows: self.medics.iter().map(|m| vec![
+                m.name.clone(),
+                m.team.name().to_string(),
+                format!("{:.1}", m.healing_time),
+                format!("{:.1}", m.choice_time),
+                format!("{:.1}", m.low_critheal_time),
+                format!("{:.0}", m.low_critheal_fraction() * 100.0),
+            ]).collect(),
+        };
+
+        let metrics = [Team::Red, Team::Blue].iter()
+            .map(|team| (
+                format!("{} wasted crit healing", team.name()),
+                self.players.iter().filter(|p| p.team == *team).map(|p| p.wasted_healing as f64).sum(),
+            ))
+            .collect();
+
+        AnalysisResult {
+            metrics,
+            tables: vec![players, medics],
+            ..Default::default()
+        }
+    }
+}
+
+py_pickle!(CritHealPlayer, CritHealMedic, CritHealReport);
+py_eq!(CritHealPlayer, CritHealMedic, CritHealReport);
+py_debug_repr!(CritHealPlayer, CritHealMedic, CritHealReport);
@@ -1,155 +1,135 @@
 //! Data types for passing to analysis
 
 use pyo3::prelude::*;
+use serde::{Serialize, Deserialize};
 
 use std::collections::HashMap;
 
-use crate::types::game::{Team, Class};
-use crate::types::game::entities::Player;
-use crate::types::demo::TickData;
+use crate::types::game::Team;
+use crate::types::entities::Player;
 use crate::types::math::Vector;
 
-#[derive(Debug, Clone)]
-pub struct TickPlayerData<'a> {
-    pub player: &'a Player,
-    pub dist_from_team_avg: f32,
-    pub dist_from_group_avg: f32,
-    pub dist_from_medic: f32,
-}
+use super::{GroupingType, Grouping, PlayerData, TeamData, TickAnalysisData};
+use super::grouping::{ClusterMethod, GroupingConfig};
 
-#[pyclass(name="TickPlayerData", get_all)]
-#[derive(Default, Debug, Clone)]
+/// Analysis data for a single player during a single tick.
+#[pyclass(name="TickPlayerData", get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TickPlayerDataPy {
     pub entity_id: u32,
     pub user_id: u16,
+    /// The `id` of the player's group.
+    pub group_id: u32,
+    /// Distance from the team average position XYZ.
+    /// If the player is the only person on the team, distance is ~0.
     pub dist_from_team_avg: f32,
+    /// Distance from the average position XYZ of the player's group.
+    /// If the player is alone in their group, distance is ~0.
     pub dist_from_group_avg: f32,
+    /// Distance in XYZ from the medic on the team.
+    /// If the player IS the medic the distance is ~0,
+    /// if the medic is dead, or the team has no medic, distance is -1.
     pub dist_from_medic: f32,
 }
 
-impl IntoPy<TickPlayerDataPy> for TickPlayerData<'_> {
-    fn into_py(self, _py: Python<'_>) -> TickPlayerDataPy {
+impl From<&PlayerData> for TickPlayerDataPy {
+    fn from(data: &PlayerData) -> Self {
         TickPlayerDataPy {
-            entity_id: self.player.info.clone().unwrap().entity_id,
-            user_id: self.player.info.clone().unwrap().user_id,
-            dist_from_group_avg: self.dist_from_group_avg,
-            dist_from_medic: self.dist_from_medic,
-            dist_from_team_avg: self.dist_from_team_avg,
+            entity_id: data.id,
+            user_id: data.user_id,
+            group_id: data.group_id,
+            dist_from_team_avg: data.dist_from_team_avg,
+            dist_from_group_avg: data.dist_from_group_avg,
+            dist_from_medic: data.dist_from_medic,
         }
     }
 }
 
-#[allow(dead_code)]
-#[derive(Debug, Clone, Default)]
-#[repr(u8)]
-pub enum GroupingType {
-    #[default]
-    None = 0,           // Not valid
-	Isolated = 1,   	// (1)  Only one player.
-	IsolatedCombo = 2,  // (2)  Contains the medic and one other players.
-	Combo = 3,      	// (3+) Contains the medic and at least two other players.
-	Flank = 4,      	// (2+) Does not contain the medic, but has at least two players.
-}
-
-#[derive(Default, Debug, Clone)]
-pub struct TickPlayerGrouping<'a> {
+/// Teammates that are close to each other.
+#[pyclass(name="TickPlayerGrouping", get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TickPlayerGroupingPy {
+    /// Stays the same from tick to tick while the group does.
+    /// Only `DemoData.analyze` follows groups across ticks.
+    pub id: u32,
+    /// IDs of the groups on the previous tick that had any of these players.
+    /// More than one means groups merged; an ID that isn't `id` means this
+    /// group split off from it.
+    pub previous_ids: Vec<u32>,
     pub group_type: GroupingType,
-    pub players: Vec<&'a Player>,
+    pub players: Vec<Player>,
     pub avg_pos: Vector,
 }
 
-#[derive(Default, Debug, Clone)]
-pub struct TickTeamAnalysis<'a> {
-    pub team: Team,
-    pub medic: Option<&'a Player>,
-    pub playerdata: HashMap<u16, TickPlayerData<'a>>,
-}
-
-impl IntoPy<TickTeamAnalysisPy> for TickTeamAnalysis<'_> {
-    fn into_py(self, py: Python<'_>) -> TickTeamAnalysisPy {
-        TickTeamAnalysisPy {
-            team: self.team,
-            medic: self.medic.cloned(),
-            playerdata: self.playerdata.iter().map(|(id, pd)| (*id, pd.clone().into_py(py))).collect()
-        }
-    }
-}
-
-#[pyclass(name="TickTeamAnalysis", get_all)]
-#[derive(Default, Debug, Clone)]
+/// One team during a single tick. Only living players are included.
+#[pyclass(name="TickTeamAnalysis", get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TickTeamAnalysisPy {
     pub team: Team,
+    /// The average position of the team's living players.
+    pub avg_position: Vector,
+    /// If the team has no medic or their medic is dead,
+    /// this is None.
     pub medic: Option<Player>,
+    /// Keyed by user ID.
     pub playerdata: HashMap<u16, TickPlayerDataPy>,
+    pub groupings: Vec<TickPlayerGroupingPy>,
 }
 
-impl<'a> TickTeamAnalysis<'a> {
-    pub fn new<T, U>(team: Team, playerlist: T, _other_team: U) -> Self
-    where
-        T: Iterator<Item = &'a Player> + Clone,
-        U: Iterator<Item = &'a Player> + Clone
-    {
-        let mut playerdata = HashMap::new();
-        let mut medic: Option<&'a Player> = None;
-
-        // Step 1: Generate groupings
-        // TODO
+impl TickTeamAnalysisPy {
+    fn new(analysis: &TickAnalysisData<'_>, team: &TeamData) -> Self {
+        let grouping = |grouping: &Grouping| TickPlayerGroupingPy {
+            id: grouping.id,
+            previous_ids: grouping.previous_ids.clone(),
+            group_type: grouping.r#type,
+            players: grouping.player_ids.iter().map(|id| analysis.id_to_player(*id).clone()).collect(),
+            avg_pos: grouping.avg_position,
+        };
 
-        // Step 2: Find the medic & generate averages
-        let mut avg_position = Vector::default();
-        for (i, player) in playerlist.clone().enumerate() {
-            if player.class == Class::Medic {
-                medic = Some(&player);
-            }
-
-            // Rolling average to preserve floating point precision?
-            avg_position = ((i-1) as f32) / (i as f32) * avg_position 
-                         + (1 as f32) / (i as f32) * player.position;
-        }
-
-        // Step 3: Generate player data
-        for player in playerlist {
-            playerdata.insert(player.info.as_ref().unwrap().user_id, TickPlayerData {
-                player: &player,
-                dist_from_group_avg: 0f32, // TODO
-                dist_from_medic: match medic {Some(m) => player.distance_from(m), None=> -1f32},
-                dist_from_team_avg: player.position.dist_to(&avg_position),
-            });
-        }
-
-        TickTeamAnalysis {
-            team,
-            medic,
-            playerdata
+        TickTeamAnalysisPy {
+            team: team.team,
+            avg_position: team.avg_position,
+            medic: team.medic.map(|id| analysis.id_to_player(id).clone()),
+            playerdata: team.player_data.values().map(|data| (data.user_id, data.into())).collect(),
+            groupings: team.groupings.iter().map(grouping).collect(),
         }
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct TickAnalysis<'a> {
-    pub tickdata: &'a TickData,
-    pub redteam: Option<TickTeamAnalysis<'a>>,
-    pub bluteam: Option<TickTeamAnalysis<'a>>,
-
-}
-
-#[pyclass(name="TickAnalysis", get_all)]
-#[derive(Default, Debug, Clone)]
+/// The analysis of a single tick. Made by `TickData.analyze`,
+/// or for many ticks at once by `DemoData.analyze`.
+#[pyclass(name="TickAnalysis", get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TickAnalysisPy {
+    pub tick: u32,
+    /// None if the team has no living players.
     pub redteam: Option<TickTeamAnalysisPy>,
+    /// None if the team has no living players.
     pub bluteam: Option<TickTeamAnalysisPy>,
-
 }
 
-impl<'a> From<&'a TickData> for TickAnalysis<'a> {
-    fn from(value: &'a TickData) -> Self {
-        let red_iter = value.players.iter().filter(|p| p.team == Team::Red);
-        let blu_iter = value.players.iter().filter(|p| p.team == Team::Blue);
+#[pymethods]
+impl TickAnalysisPy {
+    /// `redteam` or `bluteam`. None for any other team.
+    pub fn team(&self, team: Team) -> Option<TickTeamAnalysisPy> {
+        match team {
+            Team::Red => self.redteam.clone(),
+            Team::Blue => self.bluteam.clone(),
+            _ => None
+        }
+    }
+}
 
-        TickAnalysis {
-            tickdata: value,
-            redteam: Some(TickTeamAnalysis::new(Team::Red, red_iter.clone(), blu_iter.clone())),
-            bluteam: Some(TickTeamAnalysis::new(Team::Blue, blu_iter, red_iter))
+impl From<&TickAnalysisData<'_>> for TickAnalysisPy {
+    fn from(analysis: &TickAnalysisData<'_>) -> Self {
+        TickAnalysisPy {
+            tick: u32::from(analysis.state.tick),
+            redteam: analysis.red_teamdata.as_ref().map(|team| TickTeamAnalysisPy::new(analysis, team)),
+            bluteam: analysis.blue_teamdata.as_ref().map(|team| TickTeamAnalysisPy::new(analysis, team)),
         }
     }
-}
\ No newline at end of file
+}
+
+py_pickle!(GroupingType, ClusterMethod, GroupingConfig, TickPlayerDataPy, TickPlayerGroupingPy, TickTeamAnalysisPy, TickAnalysisPy);
+py_eq!(GroupingConfig, TickPlayerDataPy, TickPlayerGroupingPy, TickTeamAnalysisPy, TickAnalysisPy);
+py_debug_repr!(GroupingConfig, TickPlayerDataPy, TickPlayerGroupingPy, TickTeamAnalysisPy, TickAnalysisPy);
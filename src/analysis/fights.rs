@@ -0,0 +1,299 @@
+//! Splitting rounds into fights, using damage, kills and ubercharges.
+
+use pyo3::prelude::*;
+use serde::{Serialize, Deserialize};
+
+use crate::types::demo::DemoData;
+use crate::types::events::Kill;
+use crate::types::game::{Round, Team};
+use crate::types::math::Vector;
+
+/// Settings for `DemoData.fights`.
+#[pyclass(get_all, set_all, module = "tf2dal.analysis")]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct FightConfig {
+    /// Seconds without any damage, kills or ubers before a fight is over.
+    pub gap: f32,
+    /// Combat further than this from where a fight is happening
+    /// is a separate fight.
+    pub radius: f32,
+    /// Fights with less damage than this and no kills are left out.
+    pub min_damage: u32,
+}
+
+impl Default for FightConfig {
+    fn default() -> Self {
+        FightConfig {
+            gap: 4.0,
+            radius: 1500.0,
+            min_damage: 300,
+        }
+    }
+}
+
+#[pymethods]
+impl FightConfig {
+    #[new]
+    #[pyo3(signature = (gap=4.0, radius=1500.0, min_damage=300))]
+    fn new(gap: f32, radius: f32, min_damage: u32) -> Self {
+        FightConfig { gap, radius, min_damage }
+    }
+}
+
+/// A single engagement between the teams. Kills, damage and ubers are
+/// counted for the team that did them.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Fight {
+    /// Tick of the first damage, kill or uber.
+    pub start_tick: u32,
+    /// Tick of the last damage, kill or uber.
+    pub end_tick: u32,
+    /// User IDs of everyone that did or took damage, died, or ubered, ascending.
+    pub participants: Vec<u16>,
+    /// The average position of the players that were hurt or killed.
+    pub location: Vector,
+    pub red_kills: u32,
+    pub blue_kills: u32,
+    pub red_damage: u32,
+    pub blue_damage: u32,
+    pub red_ubers: u32,
+    pub blue_ubers: u32,
+    /// The team with more kills, or `Team.Other` if they are even.
+    pub winner: Team,
+    /// The first kill of the fight.
+    pub first_pick: Option<Kill>,
+}
+
+#[derive(Debug, Clone)]
+enum CombatKind {
+    Damage(u16),
+    Kill(Kill),
+    Ubercharge,
+}
+
+/// Damage, a kill or an uber, done by `team`.
+#[derive(Debug, Clone)]
+struct CombatEvent {
+    tick: u32,
+    team: Team,
+    kind: CombatKind,
+    participants: Vec<u16>,
+    /// Where the victim was, or the medic for ubers.
+    position: Vector,
+}
+
+/// Damage and kills between players on different teams, and ubercharges,
+/// in `round` or the whole demo, in tick order.
+fn combat_events(demo: &DemoData, round: Option<&Round>) -> Vec<CombatEvent> {
+    let in_round = |tick: u32| round.map_or(true, |r| r.contains(tick));
+    let mut events = Vec::new();
+
+    let mut push_attack = |tick: u32, kind: CombatKind, attacker_id: u16, victim_id: u16| {
+        let (Some(attacker), Some(victim)) = (demo.player_at(tick, attacker_id), demo.player_at(tick, victim_id)) else {
+            return;
+        };
+        if attacker.team != victim.team {
+            events.push(CombatEvent {
+                tick,
+                team: attacker.team,
+                kind,
+                participants: vec![attacker_id, victim_id],
+                position: victim.position,
+            });
+        }
+    };
+
+    for hurt in demo.damage.iter().filter(|d| in_round(d.tick)) {
+        push_attack(hurt.tick, CombatKind::Damage(hurt.amount), hurt.attacker_id, hurt.victim_id);
+    }
+    for kill in demo.kills.iter().filter(|k| in_round(u32::from(k.tick))) {
+        push_attack(u32::from(kill.tick), CombatKind::Kill(kill.clone()), kill.attacker_id, kill.dead_id);
+    }
+
+    for uber in demo.ubercharges.iter().filter(|u| in_round(u.tick)) {
+        if let Some(medic) = demo.player_at(uber.tick, uber.medic_id) {
+            events.push(CombatEvent {
+                tick: uber.tick,
+                team: medic.team,
+                kind: CombatKind::Ubercharge,
+                participants: vec![uber.medic_id, uber.ubered_id],
+                position: medic.position,
+            });
+        }
+    }
+
+    events.sort_by_key(|e| e.tick);
+    events
+}
+
+/// A fight that is still being added to.
+#[derive(Debug, Default)]
+struct OpenFight {
+    fight: Fight,
+    positions: u32,
+}
+
+impl OpenFight {
+    fn add(&mut self, event: &CombatEvent) {
+        let fight = &mut self.fight;
+        if fight.participants.is_empty() {
+            fight.start_tick = event.tick;
+        }
+        fight.end_tick = event.tick;
+        fight.participants.extend(&event.participants);
+
+        // Ubers are where the medic is, not where the fighting is
+        if !matches!(event.kind, CombatKind::Ubercharge) {
+            self.positions += 1;
+            fight.location = fight.location + (event.position - fight.location) / self.positions as f32;
+        }
+        else if self.positions == 0 {
+            fight.location = event.position;
+        }
+
+        let (kills, damage, ubers) = match event.team {
+            Team::Red => (&mut fight.red_kills, &mut fight.red_damage, &mut fight.red_ubers),
+            _ => (&mut fight.blue_kills, &mut fight.blue_damage, &mut fight.blue_ubers),
+        };
+        match &event.kind {
+            CombatKind::Damage(amount) => *damage += *amount as u32,
+            CombatKind::Kill(kill) => {
+                *kills += 1;
+                if fight.first_pick.is_none() {
+                    fight.first_pick = Some(kill.clone());
+                }
+            },
+            CombatKind::Ubercharge => *ubers += 1,
+        }
+    }
+
+    fn finish(mut self) -> Fight {
+        let fight = &mut self.fight;
+        fight.participants.sort();
+        fight.participants.dedup();
+        fight.winner = match fight.red_kills.cmp(&fight.blue_kills) {
+            std::cmp::Ordering::Greater => Team::Red,
+            std::cmp::Ordering::Less => Team::Blue,
+            std::cmp::Ordering::Equal => Team::Other,
+        };
+        self.fight
+    }
+}
+
+/// Fights in `round` or the whole demo, in order of when they started.
+fn round_fights(demo: &DemoData, round: Option<&Round>, config: &FightConfig, tick_rate: f32) -> Vec<Fight> {
+    let gap_ticks = (config.gap * tick_rate) as u32;
+    let mut open: Vec<OpenFight> = Vec::new();
+    let mut done = Vec::new();
+
+    for event in combat_events(demo, round) {
+        let (ended, ongoing): (Vec<_>, Vec<_>) = open.into_iter().partition(|f| f.fight.end_tick + gap_ticks < event.tick);
+        done.extend(ended);
+        open = ongoing;
+
+        let nearest = open.iter_mut()
+            .map(|f| (f.fight.location.dist_to(&event.position), f))
+            .filter(|(dist, _)| *dist <= config.radius)
+            .min_by(|a, b| a.0.total_cmp(&b.0));
+
+        match nearest {
+            Some((_, fight)) => fight.add(&event),
+            None => {
+                let mut fight = OpenFight::default();
+                fight.add(&event);
+                open.push(fight);
+            }
+        }
+    }
+    done.extend(open);
+
+    let mut fights = done.into_iter()
+        .map(OpenFight::finish)
+        .filter(|f| f.red_kills + f.blue_kills > 0 || f.red_damage + f.blue_damage >= config.min_damage)
+        .collect::<Vec<Fight>>();
+    fights.sort_by_key(|f| f.start_tick);
+    fights
+}
+
+/// Every fight in `round`, or in each round of `demo`. Fights never carry
+/// over from one round to the next. Needs the tick states to know who was
+/// on which team and where.
+pub fn detect_fights(demo: &DemoData, round: Option<&Round>, config: &FightConfig) -> Vec<Fight> {
+    let tick_rate = demo.tick_rate();
+
+    let rounds = match round {
+        Some(round) => vec![Some(round)],
+        None if demo.rounds.is_empty() => vec![None],
+        None => demo.rounds.iter().map(Some).collect(),
+    };
+
+    rounds.into_iter()
+        .flat_map(|round| round_fights(demo, round, config, tick_rate))
+        .collect()
+}
+
+py_pickle!(FightConfig, Fight);
+py_eq!(FightConfig, Fight);
+py_debug_repr!(FightConfig, Fight);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::demo::TickData;
+    use crate::types::entities::{Player, UserInfo};
+    use crate::types::events::Damage;
+    use crate::types::game::ClassList;
+
+    fn player(user_id: u16, team: Team) -> Player {
+        Player {
+            entity: user_id as u32,
+            team,
+            info: Some(UserInfo {
+                classes: ClassList::default(),
+                name: format!("player {}", user_id),
+                user_id,
+                steam_id: String::new(),
+                entity_id: user_id as u32,
+                team,
+            }),
+            ..Default::default()
+        }
+    }
+
+    /// Red hurting blue for 500 on tick 200.
+    fn demo(rounds: Vec<Round>) -> DemoData {
+        let state = TickData { players: vec![player(1, Team::Red), player(2, Team::Blue)], ..Default::default() };
+        DemoData {
+            rounds,
+            damage: vec![Damage { attacker_id: 1, victim_id: 2, amount: 500, tick: 200, ..Default::default() }],
+            tick_states: [(200, state)].into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn unfinished_round() {
+        let round = Round { start_tick: 100, end_tick: 0, winner: Team::Other };
+        let demo = demo(vec![round]);
+        let config = FightConfig::default();
+
+        let fights = detect_fights(&demo, Some(&round), &config);
+        assert_eq!(fights.len(), 1);
+        assert_eq!((fights[0].start_tick, fights[0].red_damage, fights[0].participants.clone()), (200, 500, vec![1, 2]));
+        assert_eq!(detect_fights(&demo, None, &config), fights);
+    }
+
+    #[test]
+    fn outside_the_round() {
+        let round = Round { start_tick: 100, end_tick: 150, winner: Team::Red };
+        let demo = demo(vec![round]);
+        assert!(detect_fights(&demo, Some(&round), &FightConfig::default()).is_empty());
+        assert!(detect_fights(&demo, None, &FightConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn without_rounds() {
+        assert_eq!(detect_fights(&demo(Vec::new()), None, &FightConfig::default()).len(), 1);
+    }
+}
@@ -0,0 +1,327 @@
+//! Clustering teammates into groups, and following those groups from tick to tick.
+
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+use serde::{Serialize, Deserialize};
+
+use crate::types::game::Team;
+use crate::types::math::Vector;
+
+use super::Grouping;
+
+/// How teammates are clustered into groups.
+#[pyclass(module = "tf2dal.analysis")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ClusterMethod {
+    /// Players closer than the threshold are in the same group, and so is
+    /// anyone within the threshold of any of them.
+    #[default]
+    Connected = 0,
+    /// DBSCAN, with the threshold as epsilon. Players that aren't part of
+    /// a cluster are put in a group of their own.
+    Dbscan = 1,
+}
+
+/// Settings for `TickData.analyze` and `DemoData.analyze`.
+#[pyclass(get_all, set_all, module = "tf2dal.analysis")]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GroupingConfig {
+    pub method: ClusterMethod,
+    /// Players within this distance of each other are neighbours.
+    pub threshold: f32,
+    /// DBSCAN only. How many neighbours, counting the player itself,
+    /// a player needs to start or grow a group.
+    pub min_players: usize,
+    /// Height differences are multiplied by this before measuring distance.
+    /// Above 1 splits players on different levels, 0 ignores height.
+    pub height_weight: f32,
+}
+
+impl Default for GroupingConfig {
+    fn default() -> Self {
+        GroupingConfig {
+            method: ClusterMethod::Connected,
+            threshold: 600.0,
+            min_players: 2,
+            height_weight: 1.0,
+        }
+    }
+}
+
+#[pymethods]
+impl GroupingConfig {
+    #[new]
+    #[pyo3(signature = (method=ClusterMethod::Connected, threshold=600.0, min_players=2, height_weight=1.0))]
+    fn new(method: ClusterMethod, threshold: f32, min_players: usize, height_weight: f32) -> Self {
+        GroupingConfig { method, threshold, min_players, height_weight }
+    }
+}
+
+impl GroupingConfig {
+    /// The distance used for clustering, with height weighted.
+    pub fn distance(&self, a: &Vector, b: &Vector) -> f32 {
+        let diff = *a - *b;
+        Vector::new(diff.x, diff.y, diff.z * self.height_weight).len()
+    }
+
+    fn neighbours(&self, positions: &[Vector], i: usize) -> Vec<usize> {
+        (0..positions.len())
+            .filter(|j| self.distance(&positions[i], &positions[*j]) < self.threshold)
+            .collect()
+    }
+}
+
+/// Clusters `positions`, returning the indices in each group.
+/// Every index is in exactly one group.
+pub fn cluster(positions: &[Vector], config: &GroupingConfig) -> Vec<Vec<usize>> {
+    let mut labels = match config.method {
+        ClusterMethod::Connected => connected_components(positions, config),
+        ClusterMethod::Dbscan => dbscan(positions, config),
+    };
+
+    // Anything left over is on its own
+    let mut next = labels.iter().flatten().max().map_or(0, |l| l + 1);
+    for label in labels.iter_mut().filter(|l| l.is_none()) {
+        *label = Some(next);
+        next += 1;
+    }
+
+    let mut groups = vec![Vec::new(); next];
+    for (i, label) in labels.into_iter().enumerate() {
+        groups[label.unwrap()].push(i);
+    }
+    groups
+}
+
+fn connected_components(positions: &[Vector], config: &GroupingConfig) -> Vec<Option<usize>> {
+    let mut labels = vec![None; positions.len()];
+    let mut next = 0;
+
+    for start in 0..positions.len() {
+        if labels[start].is_some() {continue;}
+
+        labels[start] = Some(next);
+        let mut stack = vec![start];
+        while let Some(i) = stack.pop() {
+            for j in config.neighbours(positions, i) {
+                if labels[j].is_none() {
+                    labels[j] = Some(next);
+                    stack.push(j);
+                }
+            }
+        }
+        next += 1;
+    }
+
+    labels
+}
+
+/// Noise is left unlabelled.
+fn dbscan(positions: &[Vector], config: &GroupingConfig) -> Vec<Option<usize>> {
+    let mut labels = vec![None; positions.len()];
+    let mut visited = vec![false; positions.len()];
+    let mut next = 0;
+
+    for start in 0..positions.len() {
+        if visited[start] {continue;}
+        visited[start] = true;
+
+        let neighbours = config.neighbours(positions, start);
+        if neighbours.len() < config.min_players {continue;}
+
+        labels[start] = Some(next);
+        let mut queue = neighbours;
+        while let Some(i) = queue.pop() {
+            // Border players join the first group that reaches them
+            if labels[i].is_none() {
+                labels[i] = Some(next);
+            }
+            if visited[i] {continue;}
+            visited[i] = true;
+
+            let neighbours = config.neighbours(positions, i);
+            if neighbours.len() >= config.min_players {
+                queue.extend(neighbours);
+            }
+        }
+        next += 1;
+    }
+
+    labels
+}
+
+/// Gives groups IDs that last from tick to tick. A group keeps the ID of the
+/// group on the previous tick it shares the most players with; when a group
+/// splits, only the biggest part keeps its ID.
+#[derive(Debug, Clone, Default)]
+pub struct GroupTracker {
+    next_id: u32,
+    /// The ID and player entity IDs of each group on the previous tick.
+    previous: HashMap<Team, Vec<(u32, Vec<u32>)>>,
+}
+
+impl GroupTracker {
+    /// Forgets the previous tick, so every group gets a new ID.
+    pub fn reset(&mut self) {
+        self.previous.clear();
+    }
+
+    /// Sets the `id` and `previous_ids` of `groupings`, which are the groups
+    /// of `team` on the tick after the one last tracked.
+    pub fn track(&mut self, team: Team, groupings: &mut [Grouping]) {
+        let previous = self.previous.remove(&team).unwrap_or_default();
+
+        let mut overlaps = Vec::new();
+        for (g, grouping) in groupings.iter_mut().enumerate() {
+            grouping.previous_ids.clear();
+            for (id, players) in &previous {
+                let shared = grouping.player_ids.iter().filter(|p| players.contains(p)).count();
+                if shared > 0 {
+                    grouping.previous_ids.push(*id);
+                    overlaps.push((shared, g, *id));
+                }
+            }
+            grouping.previous_ids.sort();
+        }
+
+        // Most shared players first, ties going to the older group
+        overlaps.sort_by(|a, b| b.0.cmp(&a.0).then(a.2.cmp(&b.2)).then(a.1.cmp(&b.1)));
+
+        let mut assigned = vec![None; groupings.len()];
+        let mut taken = Vec::new();
+        for (_, g, id) in overlaps {
+            if assigned[g].is_none() && !taken.contains(&id) {
+                assigned[g] = Some(id);
+                taken.push(id);
+            }
+        }
+
+        for (grouping, id) in groupings.iter_mut().zip(assigned) {
+            grouping.id = id.unwrap_or_else(|| {
+                self.next_id += 1;
+                self.next_id - 1
+            });
+        }
+
+        self.previous.insert(team, groupings.iter().map(|g| (g.id, g.player_ids.clone())).collect());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::GroupingType;
+
+    fn along_x(xs: &[f32]) -> Vec<Vector> {
+        xs.iter().map(|x| Vector::new(*x, 0.0, 0.0)).collect()
+    }
+
+    #[test]
+    fn connected_chains_through_neighbours() {
+        let config = GroupingConfig::default();
+        let positions = along_x(&[0.0, 500.0, 1000.0, 3000.0]);
+        assert_eq!(cluster(&positions, &config), vec![vec![0, 1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn connected_weights_height() {
+        let positions = vec![Vector::new(0.0, 0.0, 0.0), Vector::new(0.0, 0.0, 400.0)];
+        let split = GroupingConfig { height_weight: 2.0, ..Default::default() };
+        let flat = GroupingConfig { height_weight: 0.0, ..Default::default() };
+        assert_eq!(cluster(&positions, &split), vec![vec![0], vec![1]]);
+        assert_eq!(cluster(&positions, &flat), vec![vec![0, 1]]);
+    }
+
+    #[test]
+    fn dbscan_keeps_border_players_and_isolates_noise() {
+        let config = GroupingConfig { method: ClusterMethod::Dbscan, min_players: 3, ..Default::default() };
+        // Only the middle player has enough neighbours, the ends join as borders
+        let positions = along_x(&[0.0, 500.0, 1000.0, 3000.0]);
+        assert_eq!(dbscan(&positions, &config), vec![Some(0), Some(0), Some(0), None]);
+        assert_eq!(cluster(&positions, &config), vec![vec![0, 1, 2], vec![3]]);
+    }
+
+    #[test]
+    fn dbscan_without_a_core_is_all_noise() {
+        let config = GroupingConfig { method: ClusterMethod::Dbscan, min_players: 3, ..Default::default() };
+        let positions = along_x(&[0.0, 500.0]);
+        assert_eq!(dbscan(&positions, &config), vec![None, None]);
+        assert_eq!(cluster(&positions, &config), vec![vec![0], vec![1]]);
+    }
+
+    #[test]
+    fn no_players_no_groups() {
+        assert!(cluster(&[], &GroupingConfig::default()).is_empty());
+    }
+
+    fn groups(players: &[&[u32]]) -> Vec<Grouping> {
+        players.iter()
+            .map(|ids| Grouping {
+                id: 0,
+                previous_ids: Vec::new(),
+                r#type: GroupingType::None,
+                player_ids: ids.to_vec(),
+                avg_position: Vector::default(),
+            })
+            .collect()
+    }
+
+    fn ids(groupings: &[Grouping]) -> Vec<(u32, Vec<u32>)> {
+        groupings.iter().map(|g| (g.id, g.previous_ids.clone())).collect()
+    }
+
+    #[test]
+    fn biggest_part_of_a_split_keeps_the_id() {
+        let mut tracker = GroupTracker::default();
+        let mut first = groups(&[&[1, 2, 3]]);
+        tracker.track(Team::Red, &mut first);
+        assert_eq!(ids(&first), vec![(0, vec![])]);
+
+        let mut second = groups(&[&[3], &[1, 2]]);
+        tracker.track(Team::Red, &mut second);
+        assert_eq!(ids(&second), vec![(1, vec![0]), (0, vec![0])]);
+    }
+
+    #[test]
+    fn older_id_wins_a_tied_merge() {
+        let mut tracker = GroupTracker::default();
+        let mut first = groups(&[&[1, 2], &[3, 4]]);
+        tracker.track(Team::Red, &mut first);
+        assert_eq!(ids(&first), vec![(0, vec![]), (1, vec![])]);
+
+        let mut second = groups(&[&[3, 4, 1, 2]]);
+        tracker.track(Team::Red, &mut second);
+        assert_eq!(ids(&second), vec![(0, vec![0, 1])]);
+    }
+
+    #[test]
+    fn reset_hands_out_new_ids() {
+        let mut tracker = GroupTracker::default();
+        let mut first = groups(&[&[1, 2]]);
+        tracker.track(Team::Red, &mut first);
+        tracker.reset();
+
+        let mut second = groups(&[&[1, 2]]);
+        tracker.track(Team::Red, &mut second);
+        assert_eq!(ids(&second), vec![(1, vec![])]);
+    }
+
+    #[test]
+    fn teams_are_tracked_separately() {
+        let mut tracker = GroupTracker::default();
+        let mut red = groups(&[&[1, 2]]);
+        let mut blue = groups(&[&[1, 2]]);
+        tracker.track(Team::Red, &mut red);
+        tracker.track(Team::Blue, &mut blue);
+        assert_eq!((red[0].id, blue[0].id), (0, 1));
+
+        // Blue's groups don't count as red's previous tick, or the other way around
+        let mut red = groups(&[&[1, 2]]);
+        let mut blue = groups(&[&[1, 2]]);
+        tracker.track(Team::Red, &mut red);
+        tracker.track(Team::Blue, &mut blue);
+        assert_eq!(ids(&red), vec![(0, vec![0])]);
+        assert_eq!(ids(&blue), vec![(1, vec![1])]);
+    }
+}
@@ -0,0 +1,308 @@
+//! Who each medic healed, for how long, and how they spread their heals.
+
+use std::collections::{BTreeMap, HashMap};
+
+use pyo3::prelude::*;
+use serde::{Serialize, Deserialize};
+
+use crate::analysisresult::{AnalysisResult, Table};
+use crate::types::demo::DemoData;
+use crate::types::entities::Player;
+use crate::types::events::Damage;
+use crate::types::game::{Class, Round, Team};
+
+/// Damaged teammates that aren't healed within this many seconds are unanswered.
+const TIME_TO_HEAL_LIMIT: f32 = 10.0;
+/// A medic spending at least this share of their healing on one player is a pocket medic.
+const POCKET_SHARE: f32 = 0.6;
+/// A medic switching patients at least this often per minute of healing,
+/// while overhealing for at least `JUGGLE_OVERHEAL_SHARE` of it, is juggling.
+const JUGGLE_SWITCHES_PER_MINUTE: f32 = 6.0;
+const JUGGLE_OVERHEAL_SHARE: f32 = 0.3;
+
+/// How a medic spread their heals.
+#[pyclass(module = "tf2dal.analysis")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HealStyle {
+    /// Neither of the others.
+    #[default]
+    Mixed = 0,
+    /// Most of the healing went to a single player.
+    Pocket = 1,
+    /// Switched patients often, keeping several of them overhealed.
+    Juggling = 2,
+}
+
+impl HealStyle {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HealStyle::Mixed => "Mixed",
+            HealStyle::Pocket => "Pocket",
+            HealStyle::Juggling => "Juggling",
+        }
+    }
+}
+
+/// A medic healing the same patient without stopping.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct HealSegment {
+    pub patient_id: u16,
+    pub patient_name: String,
+    pub patient_class: Class,
+    pub start_tick: u32,
+    /// The last tick the patient was still being healed.
+    pub end_tick: u32,
+    /// Seconds.
+    pub duration: f32,
+    pub start_health: u16,
+    pub end_health: u16,
+}
+
+/// The heal targets of a single medic, and what they say about the medic.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MedicReport {
+    pub user_id: u16,
+    pub name: String,
+    pub team: Team,
+    /// In tick order.
+    pub segments: Vec<HealSegment>,
+    /// Seconds spent healing.
+    pub healing_time: f32,
+    /// Seconds spent healing each class.
+    pub class_time: HashMap<Class, f32>,
+    /// Seconds from a teammate being damaged until this medic started
+    /// healing them, for each time they did.
+    pub time_to_heal: Vec<f32>,
+    /// Times a teammate was damaged while this medic was alive, and wasn't
+    /// healed before dying, getting back to full health, or 10 seconds passing.
+    pub unanswered: u32,
+    /// The patient healed the longest.
+    pub pocket_id: Option<u16>,
+    /// The share of `healing_time` spent on `pocket_id`.
+    pub pocket_share: f32,
+    /// Patient switches per minute of healing.
+    pub switches_per_minute: f32,
+    /// The share of `healing_time` the patient was overhealed.
+    pub overheal_share: f32,
+    pub style: HealStyle,
+}
+
+#[pymethods]
+impl MedicReport {
+    /// The average of `time_to_heal`, or None if it is empty.
+    #[getter]
+    fn avg_time_to_heal(&self) -> Option<f32> {
+        if self.time_to_heal.is_empty() {
+            return None;
+        }
+        Some(self.time_to_heal.iter().sum::<f32>() / self.time_to_heal.len() as f32)
+    }
+}
+
+fn user_id(player: &Player) -> u16 {
+    player.info.as_ref().unwrap().user_id
+}
+
+fn new_segment(tick: u32, patient: &Player) -> HealSegment {
+    HealSegment {
+        patient_id: user_id(patient),
+        patient_name: patient.info.as_ref().unwrap().name.clone(),
+        patient_class: patient.class,
+        start_tick: tick,
+        end_tick: tick,
+        duration: 0.0,
+        start_health: patient.health,
+        end_health: patient.health,
+    }
+}
+
+/// A report for every medic that healed or was alive while a teammate was
+/// damaged, over the demo or only `round`. Ordered by team, then user ID.
+pub fn medic_reports(demo: &DemoData, round: Option<&Round>) -> Vec<MedicReport> {
+    let in_round = |tick: u32| round.map_or(true, |r| r.contains(tick));
+    let rate = demo.tick_rate();
+    let dt = 1.0 / rate;
+
+    let mut damage = BTreeMap::<u32, Vec<&Damage>>::new();
+    for hurt in demo.damage.iter().filter(|d| in_round(d.tick)) {
+        damage.entry(hurt.tick).or_default().push(hurt);
+    }
+
+    let mut reports = BTreeMap::<u16, MedicReport>::new();
+    let mut open = HashMap::<u16, HealSegment>::new();
+    let mut overhealed = HashMap::<u16, f32>::new();
+    // Damaged teammates waiting to be healed, and when they were damaged
+    let mut pending = HashMap::<u16, u32>::new();
+
+    for tick in demo.sorted_ticks().into_iter().filter(|t| in_round(*t)) {
+        let state = &demo.tick_states[&tick];
+        let targets = state.heal_targets();
+        let medics = state.players.iter()
+            .filter(|p| p.is_alive() && p.class == Class::Medic && p.info.is_some())
+            .collect::<Vec<&Player>>();
+
+        for medic in &medics {
+            let report = reports.entry(user_id(medic)).or_default();
+            report.user_id = user_id(medic);
+            report.name = medic.info.as_ref().unwrap().name.clone();
+            report.team = medic.team;
+        }
+
+        // Close segments that ended, and extend or open the rest
+        let healing = targets.iter()
+            .filter(|(medic, _)| medic.class == Class::Medic)
+            .map(|(medic, patient)| (user_id(medic), *patient))
+            .collect::<HashMap<u16, &Player>>();
+        for (medic_id, segment) in std::mem::take(&mut open) {
+            match healing.get(&medic_id) {
+                Some(patient) if user_id(patient) == segment.patient_id => {
+                    open.insert(medic_id, segment);
+                },
+                _ => reports.get_mut(&medic_id).unwrap().segments.push(segment),
+            }
+        }
+        for (medic_id, patient) in &healing {
+            let segment = open.entry(*medic_id).or_insert_with(|| new_segment(tick, patient));
+            segment.end_tick = tick;
+            segment.end_health = patient.health;
+            segment.duration += dt;
+
+            let report = reports.get_mut(medic_id).unwrap();
+            report.healing_time += dt;
+            *report.class_time.entry(patient.class).or_default() += dt;
+            if patient.health > patient.max_health {
+                *overhealed.entry(*medic_id).or_default() += dt;
+            }
+        }
+
+        // Answer, or give up on, damaged teammates
+        pending.retain(|victim_id, damaged_tick| {
+            let victim = state.get_player_by_userid(*victim_id);
+            if let Some((medic_id, _)) = healing.iter().find(|(_, p)| user_id(p) == *victim_id) {
+                reports.get_mut(medic_id).unwrap().time_to_heal.push((tick - *damaged_tick) as f32 / rate);
+                return false;
+            }
+
+            let gave_up = victim.map_or(true, |v| !v.is_alive() || v.health >= v.max_health)
+                || (tick - *damaged_tick) as f32 / rate > TIME_TO_HEAL_LIMIT;
+            if gave_up {
+                let team = victim.map_or(Team::Other, |v| v.team);
+                for medic in medics.iter().filter(|m| m.team == team) {
+                    reports.get_mut(&user_id(medic)).unwrap().unanswered += 1;
+                }
+            }
+            !gave_up
+        });
+
+        for hurt in damage.get(&tick).into_iter().flatten() {
+            let Some(victim) = state.get_player_by_userid(hurt.victim_id) else {
+                continue;
+            };
+            let has_medic = medics.iter().any(|m| m.team == victim.team && m.entity != victim.entity);
+            let being_healed = healing.values().any(|p| p.entity == victim.entity);
+            if victim.is_alive() && has_medic && !being_healed && hurt.attacker_id != hurt.victim_id {
+                pending.entry(hurt.victim_id).or_insert(tick);
+            }
+        }
+    }
+
+    for (medic_id, segment) in open {
+        reports.get_mut(&medic_id).unwrap().segments.push(segment);
+    }
+
+    let mut reports = reports.into_values().collect::<Vec<_>>();
+    for report in &mut reports {
+        report.segments.sort_by_key(|s| s.start_tick);
+
+        let mut by_patient = HashMap::<u16, f32>::new();
+        for segment in &report.segments {
+            *by_patient.entry(segment.patient_id).or_default() += segment.duration;
+        }
+        if let Some((pocket, time)) = by_patient.into_iter().max_by(|a, b| a.1.total_cmp(&b.1)) {
+            report.pocket_id = Some(pocket);
+            report.pocket_share = time / report.healing_time;
+        }
+
+        if report.healing_time > 0.0 {
+            let switches = report.segments.len().saturating_sub(1) as f32;
+            report.switches_per_minute = switches / (report.healing_time / 60.0);
+            report.overheal_share = overhealed.get(&report.user_id).copied().unwrap_or(0.0) / report.healing_time;
+        }
+
+        report.style = if report.pocket_share >= POCKET_SHARE {
+            HealStyle::Pocket
+        } else if report.switches_per_minute >= JUGGLE_SWITCHES_PER_MINUTE
+                && report.overheal_share >= JUGGLE_OVERHEAL_SHARE {
+            HealStyle::Juggling
+        } else {
+            HealStyle::Mixed
+        };
+    }
+
+    reports.sort_by_key(|r| (r.team, r.user_id));
+    reports
+}
+
+/// The reports as tables, for the viewer and CSV export.
+pub fn reports_to_result(reports: &[MedicReport]) -> AnalysisResult {
+    let medics = Table {
+        name: "medics".to_string(),
+        columns: ["name", "team", "style", "healing", "pocket %", "switches/min",
+                  "overheal %", "avg time to heal", "unanswered"]
+            .map(String::from).to_vec(),
+        rows: reports.iter().map(|r| vec![
+            r.name.clone(),
+            r.team.name().to_string(),
+            r.style.name().to_string(),
+            format!("{:.1}", r.healing_time),
+            format!("{:.0}", r.pocket_share * 100.0),
+            format!("{:.1}", r.switches_per_minute),
+            format!("{:.0}", r.overheal_share * 100.0),
+            r.avg_time_to_heal().map_or(String::new(), |t| format!("{:.2}", t)),
+            r.unanswered.to_string(),
+        ]).collect(),
+    };
+
+    let classes = Table {
+        name: "classes".to_string(),
+        columns: ["medic", "class", "healing", "share %"].map(String::from).to_vec(),
+        rows: reports.iter()
+            .flat_map(|r| Class::ALL.iter()
+                .filter_map(|class| r.class_time.get(class).map(|time| vec![
+                    r.name.clone(),
+                    class.name().to_string(),
+                    format!("{:.1}", time),
+                    format!("{:.0}", time / r.healing_time * 100.0),
+                ])))
+            .collect(),
+    };
+
+    let segments = Table {
+        name: "segments".to_string(),
+        columns: ["medic", "patient", "class", "start", "end", "duration", "start hp", "end hp"]
+            .map(String::from).to_vec(),
+        rows: reports.iter()
+            .flat_map(|r| r.segments.iter().map(|s| vec![
+                r.name.clone(),
+                s.patient_name.clone(),
+                s.patient_class.name().to_string(),
+                s.start_tick.to_string(),
+                s.end_tick.to_string(),
+                format!("{:.2}", s.duration),
+                s.start_health.to_string(),
+                s.end_health.to_string(),
+            ]))
+            .collect(),
+    };
+
+    AnalysisResult {
+        tables: vec![medics, classes, segments],
+        ..Default::default()
+    }
+}
+
+py_pickle!(HealStyle, HealSegment, MedicReport);
+py_eq!(HealSegment, MedicReport);
+py_debug_repr!(HealSegment, MedicReport);
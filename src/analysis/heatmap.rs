@@ -0,0 +1,328 @@
+//! Where players spent their time, got kills and died, as 2D grids over the map.
+//!
+//! Every kind of heatmap takes positions from the tick states, kills and
+//! deaths included, as the events don't say where anyone was. Without tick
+//! states, as with `store_ticks=False`, every heatmap is empty.
+
+use std::fmt::Write as _;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use numpy::{IntoPyArray, PyArray2};
+use numpy::ndarray::Array2;
+use pyo3::prelude::*;
+use serde::{Serialize, Deserialize};
+
+use crate::types::demo::DemoData;
+use crate::types::entities::Player;
+use crate::types::game::{Class, Round, Team, World};
+use crate::types::math::Vector;
+
+/// How many pixels wide each cell is in PNG files.
+const PNG_CELL_PIXELS: u32 = 4;
+
+/// What a heatmap counts.
+#[pyclass(module = "tf2dal.analysis")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HeatmapKind {
+    /// Seconds spent in each cell.
+    #[default]
+    Occupancy = 0,
+    /// Kills of enemies, where the attacker was standing.
+    Kills = 1,
+    /// Deaths, where the player died.
+    Deaths = 2,
+}
+
+impl HeatmapKind {
+    pub const ALL: [HeatmapKind; 3] = [HeatmapKind::Occupancy, HeatmapKind::Kills, HeatmapKind::Deaths];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            HeatmapKind::Occupancy => "Occupancy",
+            HeatmapKind::Kills => "Kills",
+            HeatmapKind::Deaths => "Deaths",
+        }
+    }
+}
+
+/// Case insensitive.
+impl FromStr for HeatmapKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_lowercase();
+        HeatmapKind::ALL.iter()
+            .find(|k| k.name().to_lowercase() == lower)
+            .copied()
+            .ok_or_else(|| format!("unknown heatmap kind \"{}\"", s))
+    }
+}
+
+/// Which players and ticks go into a heatmap, for `DemoData.heatmap`.
+/// Anything left as None isn't filtered on. For kills the filter applies
+/// to the attacker, for deaths to the player that died.
+#[pyclass(get_all, set_all, module = "tf2dal.analysis")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeatmapFilter {
+    pub user_id: Option<u16>,
+    pub player_class: Option<Class>,
+    pub team: Option<Team>,
+    pub round: Option<Round>,
+    /// (start, end) seconds since the start of the round, e.g. (0, 30)
+    /// for the opening of each round. Ticks outside of a round are left out.
+    pub phase: Option<(f32, f32)>,
+    /// Occupancy only. Leaves out dead players, whose positions stay
+    /// where they died.
+    pub alive_only: bool,
+}
+
+impl Default for HeatmapFilter {
+    fn default() -> Self {
+        HeatmapFilter {
+            user_id: None,
+            player_class: None,
+            team: None,
+            round: None,
+            phase: None,
+            alive_only: true,
+        }
+    }
+}
+
+#[pymethods]
+impl HeatmapFilter {
+    #[new]
+    #[pyo3(signature = (user_id=None, player_class=None, team=None, round=None, phase=None, alive_only=true))]
+    fn new(user_id: Option<u16>, player_class: Option<Class>, team: Option<Team>, round: Option<Round>,
+           phase: Option<(f32, f32)>, alive_only: bool) -> Self {
+        HeatmapFilter { user_id, player_class, team, round, phase, alive_only }
+    }
+}
+
+impl HeatmapFilter {
+    fn matches(&self, demo: &DemoData, tick: u32, player: &Player) -> bool {
+        let Some(info) = &player.info else {
+            return false;
+        };
+        if self.user_id.is_some_and(|id| id != info.user_id)
+            || self.player_class.is_some_and(|class| class != player.class)
+            || self.team.is_some_and(|team| team != player.team) {
+            return false;
+        }
+
+        let round = match &self.round {
+            Some(round) if !round.contains(tick) => return false,
+            Some(round) => Some(round),
+            None => demo.rounds.iter().find(|r| r.contains(tick)),
+        };
+
+        match (self.phase, round) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some((start, end)), Some(round)) => {
+                let seconds = (tick - round.start_tick) as f32 / demo.tick_rate();
+                seconds >= start && seconds <= end
+            }
+        }
+    }
+}
+
+/// A grid over the map, counting something per cell. Made by `DemoData.heatmap`.
+#[pyclass(module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Heatmap {
+    #[pyo3(get)]
+    pub kind: HeatmapKind,
+    /// The area covered. Cell (0, 0) starts at `bound_min`.
+    #[pyo3(get)]
+    pub bounds: World,
+    /// Width and height of each cell, in world units.
+    #[pyo3(get)]
+    pub cell_size: f32,
+    /// Cells along X.
+    #[pyo3(get)]
+    pub width: usize,
+    /// Cells along Y.
+    #[pyo3(get)]
+    pub height: usize,
+    /// Row by row, starting at the lowest Y.
+    pub values: Vec<f32>,
+}
+
+#[pymethods]
+impl Heatmap {
+    /// (height, width) array, indexed by [y, x] with y increasing upwards,
+    /// so it displays right side up with `imshow(origin="lower")`.
+    #[getter]
+    fn values<'py>(&self, py: Python<'py>) -> &'py PyArray2<f32> {
+        Array2::from_shape_vec((self.height, self.width), self.values.clone())
+            .unwrap()
+            .into_pyarray(py)
+    }
+
+    /// The highest value of any cell.
+    #[getter]
+    pub fn max(&self) -> f32 {
+        self.values.iter().copied().fold(0.0, f32::max)
+    }
+
+    /// Writes the heatmap to `path` as an SVG if it ends in `.svg`,
+    /// otherwise as a PNG. Empty cells are transparent.
+    #[pyo3(name = "save")]
+    fn py_save(&self, path: std::path::PathBuf) -> PyResult<()> {
+        Ok(self.save(&path)?)
+    }
+}
+
+impl Heatmap {
+    /// An empty heatmap covering the X and Y of `bounds`.
+    pub fn new(kind: HeatmapKind, bounds: World, cell_size: f32) -> Self {
+        let cells = |min: f32, max: f32| (((max - min) / cell_size).ceil() as usize).max(1);
+        let width = cells(bounds.bound_min.x, bounds.bound_max.x);
+        let height = cells(bounds.bound_min.y, bounds.bound_max.y);
+        Heatmap {
+            kind,
+            bounds,
+            cell_size,
+            width,
+            height,
+            values: vec![0.0; width * height],
+        }
+    }
+
+    /// Adds `amount` to the cell containing `position`. Positions outside
+    /// of the bounds, or that aren't finite, are ignored.
+    pub fn add(&mut self, position: Vector, amount: f32) {
+        let x = (position.x - self.bounds.bound_min.x) / self.cell_size;
+        let y = (position.y - self.bounds.bound_min.y) / self.cell_size;
+        if !x.is_finite() || !y.is_finite() || x < 0.0 || y < 0.0 || x as usize >= self.width || y as usize >= self.height {
+            return;
+        }
+        self.values[y as usize * self.width + x as usize] += amount;
+    }
+
+    /// The world position of the lowest corner of cell (x, y).
+    pub fn cell_min(&self, x: usize, y: usize) -> Vector {
+        Vector::new(
+            self.bounds.bound_min.x + x as f32 * self.cell_size,
+            self.bounds.bound_min.y + y as f32 * self.cell_size,
+            0.0,
+        )
+    }
+
+    /// The colour of each cell, in the same order as `values`: dark red
+    /// through yellow to white, or None if the cell is empty. Scaled by the
+    /// square root so a few busy cells, like spawn rooms, don't wash out
+    /// the rest of the map.
+    pub fn colors(&self) -> Vec<Option<[u8; 3]>> {
+        let max = self.max();
+        self.values.iter()
+            .map(|value| {
+                if *value <= 0.0 {
+                    return None;
+                }
+                let t = (value / max).sqrt();
+                let channel = |offset: f32| ((t * 3.0 - offset).clamp(0.0, 1.0) * 255.0) as u8;
+                Some([channel(0.0).max(64), channel(1.0), channel(2.0)])
+            })
+            .collect()
+    }
+
+    /// Writes the heatmap as an SVG if `path` ends in `.svg`, otherwise as a PNG.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("svg") => std::fs::write(path, self.to_svg()),
+            _ => self.write_png(path),
+        }
+    }
+
+    /// An RGBA PNG with `PNG_CELL_PIXELS` pixels per cell, north up.
+    pub fn write_png(&self, path: &Path) -> io::Result<()> {
+        let (width, height) = (self.width as u32 * PNG_CELL_PIXELS, self.height as u32 * PNG_CELL_PIXELS);
+        let colors = self.colors();
+        let mut pixels = vec![0u8; (width * height * 4) as usize];
+        for row in 0..height {
+            // Images start at the top, which is the highest Y
+            let y = self.height - 1 - (row / PNG_CELL_PIXELS) as usize;
+            for col in 0..width {
+                if let Some([r, g, b]) = colors[y * self.width + (col / PNG_CELL_PIXELS) as usize] {
+                    let i = ((row * width + col) * 4) as usize;
+                    pixels[i..i + 4].copy_from_slice(&[r, g, b, 255]);
+                }
+            }
+        }
+
+        let file = io::BufWriter::new(std::fs::File::create(path)?);
+        let mut encoder = png::Encoder::new(file, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder.write_header()
+            .and_then(|mut writer| writer.write_image_data(&pixels))
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))
+    }
+
+    /// An SVG with one unit per cell and a rectangle per non-empty cell, north up.
+    pub fn to_svg(&self) -> String {
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\" shape-rendering=\"crispEdges\">\n",
+            self.width, self.height);
+        let colors = self.colors();
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if let Some([r, g, b]) = colors[y * self.width + x] {
+                    let _ = writeln!(svg, "<rect x=\"{}\" y=\"{}\" width=\"1\" height=\"1\" fill=\"#{:02x}{:02x}{:02x}\"/>",
+                        x, self.height - 1 - y, r, g, b);
+                }
+            }
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+/// A `kind` heatmap of `demo` over `demo.player_reach_bounds`, counting only
+/// what passes `filter`. Empty without the tick states.
+pub fn heatmap(demo: &DemoData, kind: HeatmapKind, filter: &HeatmapFilter, cell_size: f32) -> Heatmap {
+    let mut map = Heatmap::new(kind, demo.player_reach_bounds.clone(), cell_size);
+
+    match kind {
+        HeatmapKind::Occupancy => {
+            let dt = 1.0 / demo.tick_rate();
+            for (tick, state) in &demo.tick_states {
+                for player in &state.players {
+                    if (!filter.alive_only || player.is_alive()) && filter.matches(demo, *tick, player) {
+                        map.add(player.position, dt);
+                    }
+                }
+            }
+        },
+        HeatmapKind::Kills | HeatmapKind::Deaths => {
+            for kill in &demo.kills {
+                let tick = u32::from(kill.tick);
+                let Some(dead) = demo.player_at(tick, kill.dead_id) else {
+                    continue;
+                };
+                let player = match kind {
+                    HeatmapKind::Deaths => dead,
+                    // Suicides, teamkills and kills by the world aren't kills
+                    _ => match demo.player_at(tick, kill.attacker_id) {
+                        Some(attacker) if attacker.team != dead.team => attacker,
+                        _ => continue,
+                    },
+                };
+                if filter.matches(demo, tick, player) {
+                    map.add(player.position, 1.0);
+                }
+            }
+        },
+    }
+
+    map
+}
+
+py_pickle!(HeatmapKind, HeatmapFilter, Heatmap);
+py_eq!(HeatmapFilter, Heatmap);
+py_debug_repr!(HeatmapFilter, Heatmap);
@@ -0,0 +1,279 @@
+//! What was going on around medics when they died.
+//!
+//! Where everyone was and how much charge the medic had are taken from the
+//! last recorded tick before the death, as the medic is already dead on the
+//! tick of the kill. Dying with a full charge is a drop.
+
+use std::collections::BTreeMap;
+
+use pyo3::prelude::*;
+use serde::{Serialize, Deserialize};
+
+use crate::analysisresult::{AnalysisResult, Annotation, Table};
+use crate::types::demo::{DemoData, TickData};
+use crate::types::entities::Player;
+use crate::types::events::Kill;
+use crate::types::game::{Class, Round, Team};
+use crate::types::math::Vector;
+use super::season::Lives;
+
+/// Settings for `DemoData.medic_deaths`.
+#[pyclass(get_all, set_all, module = "tf2dal.analysis")]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MedicDeathConfig {
+    /// Living enemies at most this far from the medic were in range.
+    pub range: f32,
+    /// Seconds before the death an uber counts as deployed.
+    pub uber_window: f32,
+    /// Dying with at least this much charge, from 0 to 1, is a drop.
+    pub drop_charge: f32,
+}
+
+impl Default for MedicDeathConfig {
+    fn default() -> Self {
+        MedicDeathConfig {
+            range: 1000.0,
+            uber_window: 8.0,
+            drop_charge: 1.0,
+        }
+    }
+}
+
+#[pymethods]
+impl MedicDeathConfig {
+    #[new]
+    #[pyo3(signature = (range=1000.0, uber_window=8.0, drop_charge=1.0))]
+    fn new(range: f32, uber_window: f32, drop_charge: f32) -> Self {
+        MedicDeathConfig { range, uber_window, drop_charge }
+    }
+}
+
+/// A medic's death, and what was around them.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MedicDeath {
+    pub kill: Kill,
+    pub name: String,
+    pub team: Team,
+    /// The tick positions and charge were taken from. None without tick states.
+    pub state_tick: Option<u32>,
+    pub position: Option<Vector>,
+    /// From 0 to 1.
+    pub charge: Option<f32>,
+    /// If `charge` was at least `MedicDeathConfig.drop_charge`.
+    pub dropped: bool,
+    pub nearest_teammate: Option<u16>,
+    pub teammate_distance: Option<f32>,
+    pub nearest_enemy: Option<u16>,
+    pub enemy_distance: Option<f32>,
+    /// User IDs of the living enemies within `MedicDeathConfig.range`, nearest first.
+    pub enemies_in_range: Vec<u16>,
+    /// Seconds since another player last hurt the medic. None if nobody
+    /// did since they spawned.
+    pub time_since_damaged: Option<f32>,
+    /// Seconds since the medic last ubered. None if they didn't since they spawned.
+    pub time_since_uber: Option<f32>,
+    /// If `time_since_uber` is within `MedicDeathConfig.uber_window`.
+    pub uber_deployed: bool,
+}
+
+/// The deaths of a single medic.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MedicDeathTotals {
+    pub user_id: u16,
+    pub name: String,
+    pub team: Team,
+    pub deaths: u32,
+    pub drops: u32,
+    /// Deaths within `MedicDeathConfig.uber_window` of ubering.
+    pub deaths_after_uber: u32,
+    /// Over the deaths with a known charge.
+    pub avg_charge: f32,
+    /// Over the deaths with a living teammate.
+    pub avg_teammate_distance: f32,
+}
+
+/// Every medic death in a demo or round. Made by `DemoData.medic_deaths`.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MedicDeathReport {
+    /// In tick order.
+    pub deaths: Vec<MedicDeath>,
+    /// Ordered by team, then user ID.
+    pub medics: Vec<MedicDeathTotals>,
+}
+
+/// The charge of the medigun `medic` is holding, or the highest of the
+/// mediguns they own if they have it put away.
+fn charge_of(state: &TickData, medic: &Player) -> Option<f32> {
+    let owned = state.mediguns.values().filter(|gun| gun.owner == medic.entity).collect::<Vec<_>>();
+    owned.iter().find(|gun| !gun.is_holstered)
+        .map(|gun| gun.charge)
+        .or_else(|| owned.iter().map(|gun| gun.charge).reduce(f32::max))
+}
+
+/// Living players of `team` other than `medic`, nearest first, with their distance.
+fn nearest(state: &TickData, medic: &Player, team: impl Fn(Team) -> bool) -> Vec<(u16, f32)> {
+    let mut players = state.players.iter()
+        .filter(|p| p.is_alive() && p.entity != medic.entity && team(p.team))
+        .filter_map(|p| Some((p.info.as_ref()?.user_id, medic.distance_from(p))))
+        .collect::<Vec<_>>();
+    players.sort_by(|a, b| a.1.total_cmp(&b.1));
+    players
+}
+
+/// The context of every medic death in `demo`, or only in `round`.
+pub fn medic_death_report(demo: &DemoData, round: Option<&Round>, config: &MedicDeathConfig) -> MedicDeathReport {
+    let in_round = |tick: u32| round.map_or(true, |r| r.contains(tick));
+    let tick_rate = demo.tick_rate();
+    let seconds_since = |tick: u32, since: u32| tick.saturating_sub(since) as f32 / tick_rate;
+    let ticks = demo.sorted_ticks();
+    let lives = Lives::new(demo);
+
+    let mut deaths = Vec::new();
+    for kill in demo.kills.iter().filter(|k| in_round(u32::from(k.tick))) {
+        let tick = u32::from(kill.tick);
+        let life = lives.at(kill.dead_id, tick);
+        let spawn_tick = life.map_or(0, |s| s.tick);
+
+        let before = ticks.partition_point(|t| *t < tick).checked_sub(1).map(|i| ticks[i]);
+        let state = before.map(|t| &demo.tick_states[&t]);
+        let medic = state.and_then(|s| s.get_player_by_userid(kill.dead_id));
+
+        // The class they spawned as, unless the tick states say otherwise
+        let class = medic.map(|p| p.class).or(life.map(|s| s.class));
+        if class != Some(Class::Medic) {
+            continue;
+        }
+        let team = medic.map(|p| p.team).or(life.map(|s| s.team)).unwrap_or_default();
+
+        let (teammates, enemies) = match (state, medic) {
+            (Some(state), Some(medic)) => (
+                nearest(state, medic, |t| t == team),
+                nearest(state, medic, |t| t != team && matches!(t, Team::Red | Team::Blue)),
+            ),
+            _ => (Vec::new(), Vec::new()),
+        };
+        let charge = state.zip(medic).and_then(|(state, medic)| charge_of(state, medic));
+
+        let last_hurt = demo.damage.iter()
+            .filter(|d| d.victim_id == kill.dead_id && d.attacker_id != kill.dead_id)
+            .filter(|d| d.tick >= spawn_tick && d.tick <= tick)
+            .map(|d| d.tick)
+            .max();
+        let last_uber = demo.ubercharges.iter()
+            .filter(|u| u.medic_id == kill.dead_id && u.tick >= spawn_tick && u.tick <= tick)
+            .map(|u| u.tick)
+            .max();
+        let time_since_uber = last_uber.map(|t| seconds_since(tick, t));
+
+        deaths.push(MedicDeath {
+            kill: kill.clone(),
+            name: demo.identities.get(kill.dead_id).map(|p| p.name.clone())
+                .or_else(|| medic.and_then(|p| p.info.as_ref()).map(|info| info.name.clone()))
+                .unwrap_or_default(),
+            team,
+            state_tick: before.filter(|_| medic.is_some()),
+            position: medic.map(|p| p.position),
+            charge,
+            dropped: charge.is_some_and(|c| c >= config.drop_charge),
+            nearest_teammate: teammates.first().map(|t| t.0),
+            teammate_distance: teammates.first().map(|t| t.1),
+            nearest_enemy: enemies.first().map(|e| e.0),
+            enemy_distance: enemies.first().map(|e| e.1),
+            enemies_in_range: enemies.iter().take_while(|e| e.1 <= config.range).map(|e| e.0).collect(),
+            time_since_damaged: last_hurt.map(|t| seconds_since(tick, t)),
+            time_since_uber,
+            uber_deployed: time_since_uber.is_some_and(|t| t <= config.uber_window),
+        });
+    }
+    deaths.sort_by_key(|d| u32::from(d.kill.tick));
+
+    let mut medics = BTreeMap::<(Team, u16), (MedicDeathTotals, Vec<f32>, Vec<f32>)>::new();
+    for death in &deaths {
+        let (totals, charges, distances) = medics.entry((death.team, death.kill.dead_id)).or_default();
+        totals.user_id = death.kill.dead_id;
+        totals.name = death.name.clone();
+        totals.team = death.team;
+        totals.deaths += 1;
+        totals.drops += death.dropped as u32;
+        totals.deaths_after_uber += death.uber_deployed as u32;
+        charges.extend(death.charge);
+        distances.extend(death.teammate_distance);
+    }
+    let average = |values: &[f32]| if values.is_empty() {0.0} else {values.iter().sum::<f32>() / values.len() as f32};
+    let medics = medics.into_values()
+        .map(|(mut totals, charges, distances)| {
+            totals.avg_charge = average(&charges);
+            totals.avg_teammate_distance = average(&distances);
+            totals
+        })
+        .collect();
+
+    MedicDeathReport { deaths, medics }
+}
+
+impl MedicDeathReport {
+    /// Each medic's totals and every death, with drops marked on the timeline.
+    pub fn to_result(&self) -> AnalysisResult {
+        let optional = |value: Option<f32>, scale: f32| value.map_or(String::new(), |v| format!("{:.1}", v * scale));
+
+        let medics = Table {
+            name: "medics".to_string(),
+            columns: ["name", "team", "deaths", "drops", "deaths after uber", "avg charge %",
+                      "avg teammate distance"]
+                .map(String::from).to_vec(),
+            rows: self.medics.iter().map(|m| vec![
+                m.name.clone(),
+                m.team.name().to_string(),
+                m.deaths.to_string(),
+                m.drops.to_string(),
+                m.deaths_after_uber.to_string(),
+                format!("{:.1}", m.avg_charge * 100.0),
+                format!("{:.0}", m.avg_teammate_distance),
+            ]).collect(),
+        };
+
+        let deaths = Table {
+            name: "medic deaths".to_string(),
+            columns: ["tick", "name", "team", "weapon", "charge %", "dropped", "teammate distance",
+                      "enemy distance", "enemies in range", "since damaged", "since uber"]
+                .map(String::from).to_vec(),
+            rows: self.deaths.iter().map(|d| vec![
+                u32::from(d.kill.tick).to_string(),
+                d.name.clone(),
+                d.team.name().to_string(),
+                d.kill.weapon.clone(),
+                optional(d.charge, 100.0),
+                d.dropped.to_string(),
+                optional(d.teammate_distance, 1.0),
+                optional(d.enemy_distance, 1.0),
+                d.enemies_in_range.len().to_string(),
+                optional(d.time_since_damaged, 1.0),
+                optional(d.time_since_uber, 1.0),
+            ]).collect(),
+        };
+
+        let annotations = self.deaths.iter()
+            .map(|d| Annotation {
+                tick: u32::from(d.kill.tick),
+                text: match (d.dropped, d.charge) {
+                    (true, _) => format!("{} dropped", d.name),
+                    (false, Some(charge)) => format!("{} died with {:.0}%", d.name, charge * 100.0),
+                    (false, None) => format!("{} died", d.name),
+                },
+            })
+            .collect();
+
+        AnalysisResult {
+            tables: vec![medics, deaths],
+            annotations,
+            ..Default::default()
+        }
+    }
+}
+
+py_pickle!(MedicDeathConfig, MedicDeath, MedicDeathTotals, MedicDeathReport);
+py_eq!(MedicDeathConfig, MedicDeath, MedicDeathTotals, MedicDeathReport);
+py_debug_repr!(MedicDeathConfig, MedicDeath, MedicDeathTotals, MedicDeathReport);
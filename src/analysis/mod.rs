@@ -1,29 +1,125 @@
 //////////////////////////////
-//! Author: David Walker
-//! Date:   11.30.23
-//! Name:   datacollect.rs
-//! Purpose:    
-//!     The [`viewing`] crate wants to be able to see some of the tick information
-//!     gathered, so this 
-//!     analysis is one in rust instead of in python.
+// Author: David Walker
+// Date:   11.30.23
+// Name:   datacollect.rs
+// Purpose:
+//     The [`viewing`] crate wants to be able to see some of the tick information
+//     gathered, so this
+//     analysis is one in rust instead of in python.
 //////////////////////////////
 
+//! Analysis that is done in Rust instead of Python.
+//!
+//! `TickData.analyze()` gives team averages, medic distances and groupings
+//! for a single tick, and `DemoData.analyze()` does the same for every tick
+//! of a demo or round, following each group from tick to tick.
+//! `DemoData.fights()` splits rounds into the fights that happened in them,
+//! and `DemoData.timeline()` follows the alive counts, trades and first pick
+//! of a round. `DemoData.critheals()` reports how crit heals were used, and
+//! `DemoData.medic_heals()` who each medic healed. `DemoData.heatmap()`
+//! counts where players were, got kills and died, and
+//! `DemoData.zone_occupancy()` who held each named zone of the map.
+//! `DemoData.respawns()` times respawns, waves and recoveries from wipes,
+//! and `DemoData.medic_deaths()` what was around medics when they died.
+//! `DemoData.ratings()` rates each player's impact against class baselines.
+//! A `Season` adds up players and teams over many demos.
+
 
 use std::collections::HashMap;
 
-use crate::types::demo::TickData;
-use crate::types::game::{Class, Team};
-use crate::types::game::entities::{Player, PlayerState};
+use pyo3::prelude::*;
+use serde::{Serialize, Deserialize};
+
+use crate::types::demo::{DemoData, TickData};
+use crate::types::game::{Class, Round, Team};
+use crate::types::entities::{Player, PlayerState};
 use crate::types::math::Vector;
 
-#[derive(Debug, Clone, Default)]
+use data::{TickAnalysisPy, TickTeamAnalysisPy, TickPlayerDataPy, TickPlayerGroupingPy};
+use grouping::{ClusterMethod, GroupingConfig, GroupTracker};
+use fights::{Fight, FightConfig};
+use timeline::{ManAdvantage, RoundTimeline, Trade};
+use critheal::{CritHealMedic, CritHealPlayer, CritHealReport};
+use healing::{HealSegment, HealStyle, MedicReport};
+use heatmap::{Heatmap, HeatmapFilter, HeatmapKind};
+use medicdeaths::{MedicDeath, MedicDeathConfig, MedicDeathReport, MedicDeathTotals};
+use rating::{ClassBaseline, ClassRating, PlayerRating, RatingComponent, RatingModel, RatingReport, RatingWeights};
+use respawns::{Respawn, RespawnReport, RespawnWave, TeamRespawns, Wipe};
+use season::{Season, SeasonDemo, SeasonFilter, SeasonPlayer, SeasonSummary, SeasonTeam};
+use zones::{KillZone, Zone, ZoneControl, ZoneReport, ZoneTime};
+
+/// This function is used by lib.rs, but the IDE thinks it's unused.
+#[allow(dead_code)]
+pub(crate) fn get_submod(py: Python<'_>) -> PyResult<&PyModule> {
+    let module = PyModule::new(py, "analysis")?;
+    module.add_class::<GroupingType>()?;
+    module.add_class::<ClusterMethod>()?;
+    module.add_class::<GroupingConfig>()?;
+    module.add_class::<TickAnalysisPy>()?;
+    module.add_class::<TickTeamAnalysisPy>()?;
+    module.add_class::<TickPlayerDataPy>()?;
+    module.add_class::<TickPlayerGroupingPy>()?;
+    module.add_class::<FightConfig>()?;
+    module.add_class::<Fight>()?;
+    module.add_class::<ManAdvantage>()?;
+    module.add_class::<Trade>()?;
+    module.add_class::<RoundTimeline>()?;
+    module.add_class::<CritHealPlayer>()?;
+    module.add_class::<CritHealMedic>()?;
+    module.add_class::<CritHealReport>()?;
+    module.add_class::<HealStyle>()?;
+    module.add_class::<HealSegment>()?;
+    module.add_class::<MedicReport>()?;
+    module.add_class::<HeatmapKind>()?;
+    module.add_class::<HeatmapFilter>()?;
+    module.add_class::<Heatmap>()?;
+    module.add_class::<Zone>()?;
+    module.add_class::<ZoneTime>()?;
+    module.add_class::<ZoneControl>()?;
+    module.add_class::<KillZone>()?;
+    module.add_class::<ZoneReport>()?;
+    module.add_function(wrap_pyfunction!(zones::load_zones, module)?)?;
+    module.add_class::<Respawn>()?;
+    module.add_class::<RespawnWave>()?;
+    module.add_class::<TeamRespawns>()?;
+    module.add_class::<Wipe>()?;
+    module.add_class::<RespawnReport>()?;
+    module.add_class::<MedicDeathConfig>()?;
+    module.add_class::<MedicDeath>()?;
+    module.add_class::<MedicDeathTotals>()?;
+    module.add_class::<MedicDeathReport>()?;
+    module.add_class::<ClassBaseline>()?;
+    module.add_class::<RatingWeights>()?;
+    module.add_class::<RatingModel>()?;
+    module.add_class::<RatingComponent>()?;
+    module.add_class::<ClassRating>()?;
+    module.add_class::<PlayerRating>()?;
+    module.add_class::<RatingReport>()?;
+    module.add_class::<SeasonFilter>()?;
+    module.add_class::<SeasonDemo>()?;
+    module.add_class::<SeasonPlayer>()?;
+    module.add_class::<SeasonTeam>()?;
+    module.add_class::<SeasonSummary>()?;
+    module.add_class::<Season>()?;
+    Ok(module)
+}
+
+/// What kind of group a player is in.
+#[pyclass(module = "tf2dal.analysis")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[repr(u8)]
 pub enum GroupingType {
+    /// Not valid.
     #[default]
-    None = 0,           // Not valid
-	Isolated = 1,   	// (1)  Only one player.
-	IsolatedCombo = 2,  // (2)  Contains the medic and one other players.
-	Combo = 3,      	// (3+) Contains the medic and at least two other players.
-	Flank = 4,      	// (2+) Does not contain the medic, but has at least two players.
+    None = 0,
+    /// (1) Only one player.
+    Isolated = 1,
+    /// (2) Contains the medic and one other player.
+    IsolatedCombo = 2,
+    /// (3+) Contains the medic and at least two other players.
+    Combo = 3,
+    /// (2+) Does not contain the medic, but has at least two players.
+    Flank = 4,
 }
 
 /*
@@ -56,19 +152,25 @@ fn get_avg_pos(players: &Vec<&Player>) -> Vector {
 #[derive(Default, Debug, Clone)]
 pub struct PlayerData {
     pub id: u32,
+    pub user_id: u16,
+    pub group_id: u32,
     pub dist_from_team_avg: f32,
     pub dist_from_group_avg: f32,
+    /// -1 if the team has no living medic.
     pub dist_from_medic: f32,
 }
 
 // This is the only structure
 impl PlayerData {
     pub fn new(player: &Player, team: &TeamData, grouping: &Grouping) -> Self {
+        let info = player.info.as_ref().unwrap();
         PlayerData {
-            id: player.info.as_ref().unwrap().entity_id,
+            id: info.entity_id,
+            user_id: info.user_id,
+            group_id: grouping.id,
             dist_from_team_avg: player.position.dist_to(&team.avg_position),
             dist_from_group_avg: player.position.dist_to(&grouping.avg_position),
-            dist_from_medic: player.position.dist_to(&team.medic_position),
+            dist_from_medic: team.medic_position.map_or(-1.0, |pos| player.position.dist_to(&pos)),
         }
     }
 }
@@ -95,8 +197,14 @@ impl From<&Vec<&Player>> for GroupingType {
 	}
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct Grouping {
+    /// Set by [`GroupTracker::track`]. Stays the same while the group does.
+    pub id: u32,
+    /// IDs of the groups on the previous tick that had any of these players.
+    /// More than one means groups merged; an ID that isn't `id` means this
+    /// group split off from it.
+    pub previous_ids: Vec<u32>,
 	pub r#type: GroupingType,
 	pub player_ids: Vec<u32>,
     pub avg_position: Vector,
@@ -105,6 +213,8 @@ pub struct Grouping {
 impl From<&Vec<&Player>> for Grouping {
     fn from(value: &Vec<&Player>) -> Self {
         Grouping {
+            id: 0,
+            previous_ids: Vec::new(),
             r#type: GroupingType::from(value),
             player_ids: value.iter().map(|p| p.info.as_ref().unwrap().entity_id).collect(),
             avg_position: get_avg_pos(&value),
@@ -112,41 +222,31 @@ impl From<&Vec<&Player>> for Grouping {
     }
 }
 
-pub fn build_groupings(players: &Vec<&Player>, grouping_threshold: f32) -> Vec<Grouping> {
-    let mut close = Vec::<Vec<&Player>>::new();
-
-    let grouped: Vec<usize> = Vec::new();
-    for i in 0..players.len() {
-        if grouped.contains(&i) {continue;}
-        let player = players.get(i).unwrap();
-        let mut group = Vec::<&Player>::new();
+/// Clusters `players` using `config`. Every player is in exactly one grouping.
+pub fn build_groupings(players: &Vec<&Player>, config: &GroupingConfig) -> Vec<Grouping> {
+    let positions = players.iter().map(|p| p.position).collect::<Vec<Vector>>();
 
-        for j in i..players.len() {
-            if grouped.contains(&j) {continue;}
-            
-            if player.position.dist_to(&players.get(j).unwrap().position) < grouping_threshold {
-                group.push(players.get(j).unwrap());
-            }
-        }
-
-        group.push(player);
-        close.push(group);
-    }
-
-    close.iter().cloned().map(|c| Grouping::from(&c)).collect()
+    grouping::cluster(&positions, config)
+        .into_iter()
+        .map(|group| Grouping::from(&group.into_iter().map(|i| players[i]).collect::<Vec<&Player>>()))
+        .collect()
 }
 
-#[derive(Clone)]
+#[derive(Debug, Clone)]
 pub struct TeamData {
     pub team: Team,
     pub groupings: Vec<Grouping>,
     pub avg_position: Vector,
-    pub medic_position: Vector,
+    /// Entity ID of the team's medic, if they have a living one.
+    pub medic: Option<u32>,
+    pub medic_position: Option<Vector>,
+    /// Keyed by entity ID.
     pub player_data: HashMap<u32, PlayerData>,
 }
 
 impl TeamData {
-    pub fn new(players: &Vec<&Player>, id_to_player: &HashMap<u32, &Player>) -> Option<Self> {
+    pub fn new(players: &Vec<&Player>, id_to_player: &HashMap<u32, &Player>,
+               config: &GroupingConfig, tracker: &mut GroupTracker) -> Option<Self> {
         // each player will have the same team as the first player
         // as they were already filtered
 
@@ -157,16 +257,18 @@ impl TeamData {
 
         let avg_pos = get_avg_pos(&players);
 
-        let medic_pos = players.iter().filter(|p| p.class == Class::Medic)
-                                      .map(|p| p.position)
-                                      .collect::<Vec<Vector>>()
-                                      .get(0).map_or(Vector::default(), |x| *x);
-        
+        let medic = players.iter().find(|p| p.class == Class::Medic);
+
+        let team = players.first().unwrap().team;
+        let mut groupings = build_groupings(&players, config);
+        tracker.track(team, &mut groupings);
+
         Some(TeamData {
-            team: players.first().unwrap().team,
-            groupings: build_groupings(&players, 600.0),
+            team,
+            groupings,
             avg_position: avg_pos,
-            medic_position: medic_pos,
+            medic: medic.map(|p| p.info.as_ref().unwrap().entity_id),
+            medic_position: medic.map(|p| p.position),
             player_data: HashMap::new()
         }.fill_player_data(&id_to_player))
     }
@@ -187,43 +289,67 @@ impl TeamData {
     }
 }
 
-#[derive(Clone)]
-pub struct TickAnalysisData {
-    pub state: TickData,
+#[derive(Debug, Clone)]
+pub struct TickAnalysisData<'a> {
+    pub state: &'a TickData,
     pub red_teamdata: Option<TeamData>,
     pub blue_teamdata: Option<TeamData>,
     player_id_map: HashMap<u32, usize>,
 }
 
-#[allow(dead_code)]
-impl TickAnalysisData {
-    pub fn id_to_player<'a>(&'a self, id: u32) -> &'a Player {
+impl<'a> TickAnalysisData<'a> {
+    pub fn id_to_player(&self, id: u32) -> &'a Player {
         self.state.players.get(self.player_id_map[&id]).unwrap()
     }
+
+    pub fn team_data(&self, team: Team) -> Option<&TeamData> {
+        match team {
+            Team::Red => self.red_teamdata.as_ref(),
+            Team::Blue => self.blue_teamdata.as_ref(),
+            _ => None
+        }
+    }
+}
+
+impl<'a> From<&'a TickData> for TickAnalysisData<'a> {
+    fn from(value: &'a TickData) -> Self {
+        TickAnalysisData::new(value, &GroupingConfig::default(), &mut GroupTracker::default())
+    }
 }
 
 // THIS IS THE FUNCTION WHERE STATE ANALYSIS STARTS!
-impl From<&TickData> for TickAnalysisData {
-    fn from(value: &TickData) -> Self {
-        let red_iter = value.players.iter().filter(|p| p.team == Team::Red);
-        let blu_iter = value.players.iter().filter(|p| p.team == Team::Blue);
+impl<'a> TickAnalysisData<'a> {
+    /// `tracker` should have last tracked the tick before this one.
+    pub fn new(value: &'a TickData, config: &GroupingConfig, tracker: &mut GroupTracker) -> Self {
+        // Only living players that we know the identity of are analysed
+        let analysed = |p: &&Player| p.info.is_some() && p.state == PlayerState::Alive;
+        let red_iter = value.players.iter().filter(analysed).filter(|p| p.team == Team::Red);
+        let blu_iter = value.players.iter().filter(analysed).filter(|p| p.team == Team::Blue);
 
         let id_to_player = value.players.iter()
-                                           .map(|p| p.info.as_ref().unwrap().entity_id)
-                                           .zip(&value.players)
+                                           .filter(analysed)
+                                           .map(|p| (p.info.as_ref().unwrap().entity_id, p))
                                            .collect::<HashMap<u32, &Player>>();
 
         let player_id_map = value.players.iter()
-                                            .map(|p| p.info.as_ref().unwrap().entity_id)
                                             .enumerate()
-                                            .map(|(i,p)| (p, i)) // Reverse order
+                                            .filter(|(_, p)| analysed(p))
+                                            .map(|(i, p)| (p.info.as_ref().unwrap().entity_id, i))
                                             .collect::<HashMap<u32, usize>>();
 
-        let red_data = TeamData::new(&red_iter.collect::<Vec<&Player>>(), &id_to_player);
-        let blue_data = TeamData::new(&blu_iter.collect::<Vec<&Player>>(), &id_to_player);
+        let red_data = TeamData::new(&red_iter.collect::<Vec<&Player>>(), &id_to_player, config, tracker);
+        let blue_data = TeamData::new(&blu_iter.collect::<Vec<&Player>>(), &id_to_player, config, tracker);
+
+        // A team with nobody alive has no groups to carry over
+        if red_data.is_none() {
+            tracker.track(Team::Red, &mut []);
+        }
+        if blue_data.is_none() {
+            tracker.track(Team::Blue, &mut []);
+        }
         
         TickAnalysisData { 
-            state: value.clone(),
+            state: value,
             red_teamdata: red_data,
             blue_teamdata: blue_data,
             player_id_map,
@@ -231,12 +357,41 @@ impl From<&TickData> for TickAnalysisData {
     }
 }
 
+/// Analyses every tick of `demo`, or only the ticks of `round`, in tick order.
+/// Groups are followed from tick to tick, starting over every round.
+pub fn analyze_ticks<'a>(demo: &'a DemoData, round: Option<&Round>, config: &GroupingConfig) -> Vec<TickAnalysisData<'a>> {
+    let mut tracker = GroupTracker::default();
+    let mut current_round = None;
+
+    demo.sorted_ticks()
+        .into_iter()
+        .filter(|tick| round.map_or(true, |r| r.contains(*tick)))
+        .map(|tick| {
+            let tick_round = demo.rounds.iter().position(|r| r.contains(tick));
+            if tick_round != current_round {
+                tracker.reset();
+                current_round = tick_round;
+            }
+            TickAnalysisData::new(&demo.tick_states[&tick], config, &mut tracker)
+        })
+        .collect()
+}
+
 
 //use self::data::PlayerData;
 
 //pub mod sums;
-//pub mod data;
 pub mod grouping;
+pub mod fights;
+pub mod timeline;
+pub mod critheal;
+pub mod healing;
+pub mod heatmap;
+pub mod zones;
+pub mod respawns;
+pub mod medicdeaths;
+pub mod season;
+pub mod rating;
 
 pub mod data;
 
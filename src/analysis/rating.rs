@@ -0,0 +1,524 @@
+//! A player impact rating, weighted by class.
+//!
+//! Each stat is turned into a rate per minute on a class, counting from a
+//! spawn until the next spawn or the end of the round, so time spent dead
+//! counts too. Each rate is compared against a baseline for the class:
+//!
+//! ```text
+//! score    = min(rate / baseline, max_score)
+//! survival = min(baseline deaths per minute / deaths per minute, max_score)
+//! ```
+//!
+//! A class rating is the sum of the scores times the class's weights, which
+//! add up to 1, so a player right at every baseline rates 1.0 and one doing
+//! twice as well rates 2.0. Players who played several classes get the
+//! average of their class ratings, weighted by the time on each.
+//!
+//! The default baselines are rough averages of competitive 6v6 per class,
+//! and the default weights lean medics on healing and staying alive rather
+//! than kills. Both can be changed through `RatingModel`.
+
+use std::collections::{BTreeMap, HashMap};
+
+use pyo3::prelude::*;
+use serde::{Serialize, Deserialize};
+
+use crate::analysisresult::{AnalysisResult, Table};
+use crate::types::demo::DemoData;
+use crate::types::game::{Class, Team};
+use crate::types::identity::SteamId;
+use super::season::{Lives, Season, SeasonFilter};
+
+/// What an average player of a class does per minute.
+#[pyclass(get_all, set_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ClassBaseline {
+    pub kills: f32,
+    pub assists: f32,
+    pub deaths: f32,
+    pub damage: f32,
+    pub healing: f32,
+    pub ubercharges: f32,
+}
+
+#[pymethods]
+impl ClassBaseline {
+    #[new]
+    #[pyo3(signature = (kills=0.0, assists=0.0, deaths=0.0, damage=0.0, healing=0.0, ubercharges=0.0))]
+    fn new(kills: f32, assists: f32, deaths: f32, damage: f32, healing: f32, ubercharges: f32) -> Self {
+        ClassBaseline { kills, assists, deaths, damage, healing, ubercharges }
+    }
+}
+
+/// How much each score counts towards a class's rating. Should add up to 1.
+#[pyclass(get_all, set_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RatingWeights {
+    pub kills: f32,
+    pub assists: f32,
+    pub damage: f32,
+    pub healing: f32,
+    pub ubercharges: f32,
+    pub survival: f32,
+}
+
+#[pymethods]
+impl RatingWeights {
+    #[new]
+    #[pyo3(signature = (kills=0.0, assists=0.0, damage=0.0, healing=0.0, ubercharges=0.0, survival=0.0))]
+    fn new(kills: f32, assists: f32, damage: f32, healing: f32, ubercharges: f32, survival: f32) -> Self {
+        RatingWeights { kills, assists, damage, healing, ubercharges, survival }
+    }
+}
+
+/// The baselines and weights ratings are worked out with. Changes to the
+/// dicts have to be assigned back, e.g. `model.baselines = baselines`.
+#[pyclass(get_all, set_all, module = "tf2dal.analysis")]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RatingModel {
+    /// Classes missing from here use the baseline of `Class.Other`.
+    pub baselines: HashMap<Class, ClassBaseline>,
+    /// Classes missing from here use the weights of `Class.Other`.
+    pub weights: HashMap<Class, RatingWeights>,
+    /// The highest any one score can be, so one lucky stat can't carry a rating.
+    pub max_score: f32,
+    /// Seconds on a class below which it isn't rated, as the rates mean little.
+    pub min_time: f32,
+}
+
+impl Default for RatingModel {
+    fn default() -> Self {
+        let baseline = |kills, assists, deaths, damage, healing, ubercharges|
+            ClassBaseline { kills, assists, deaths, damage, healing, ubercharges };
+        let weights = |kills, assists, damage, healing, ubercharges, survival|
+            RatingWeights { kills, assists, damage, healing, ubercharges, survival };
+        let fragger = weights(0.35, 0.15, 0.30, 0.0, 0.0, 0.20);
+
+        RatingModel {
+            baselines: HashMap::from([
+                (Class::Other, baseline(0.55, 0.30, 0.55, 230.0, 0.0, 0.0)),
+                (Class::Scout, baseline(0.75, 0.30, 0.65, 240.0, 0.0, 0.0)),
+                (Class::Soldier, baseline(0.60, 0.35, 0.55, 260.0, 0.0, 0.0)),
+                (Class::Demoman, baseline(0.65, 0.30, 0.55, 290.0, 0.0, 0.0)),
+                (Class::Medic, baseline(0.05, 0.80, 0.30, 40.0, 1100.0, 0.40)),
+                (Class::Heavy, baseline(0.70, 0.25, 0.50, 300.0, 0.0, 0.0)),
+                (Class::Pyro, baseline(0.50, 0.25, 0.60, 220.0, 0.0, 0.0)),
+                (Class::Engineer, baseline(0.40, 0.25, 0.45, 180.0, 0.0, 0.0)),
+                (Class::Sniper, baseline(0.55, 0.10, 0.45, 180.0, 0.0, 0.0)),
+                (Class::Spy, baseline(0.40, 0.10, 0.60, 130.0, 0.0, 0.0)),
+            ]),
+            weights: HashMap::from([
+                (Class::Other, fragger),
+                (Class::Soldier, weights(0.30, 0.15, 0.35, 0.0, 0.0, 0.20)),
+                (Class::Demoman, weights(0.30, 0.15, 0.35, 0.0, 0.0, 0.20)),
+                (Class::Medic, weights(0.0, 0.10, 0.0, 0.35, 0.20, 0.35)),
+                (Class::Sniper, weights(0.45, 0.10, 0.20, 0.0, 0.0, 0.25)),
+            ]),
+            max_score: 3.0,
+            min_time: 30.0,
+        }
+    }
+}
+
+#[pymethods]
+impl RatingModel {
+    /// The default baselines and weights.
+    #[new]
+    fn new() -> Self {
+        RatingModel::default()
+    }
+}
+
+impl RatingModel {
+    fn baseline(&self, class: Class) -> ClassBaseline {
+        self.baselines.get(&class).or_else(|| self.baselines.get(&Class::Other)).copied().unwrap_or_default()
+    }
+
+    fn weights(&self, class: Class) -> RatingWeights {
+        self.weights.get(&class).or_else(|| self.weights.get(&Class::Other)).copied().unwrap_or_default()
+    }
+}
+
+/// One stat's part of a class rating.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RatingComponent {
+    /// "kills", "assists", "damage", "healing", "ubercharges" or "survival".
+    pub name: String,
+    /// Per minute. Deaths per minute for survival.
+    pub rate: f32,
+    /// Per minute, from the model.
+    pub baseline: f32,
+    /// `rate` over `baseline` (the other way around for survival),
+    /// capped at `RatingModel.max_score`.
+    pub score: f32,
+    pub weight: f32,
+    /// `score * weight`. These add up to the class rating.
+    pub contribution: f32,
+}
+
+/// A player's rating on a single class.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ClassRating {
+    pub player_class: Class,
+    /// Seconds on the class, dead or alive.
+    pub time_played: f32,
+    pub rating: f32,
+    pub components: Vec<RatingComponent>,
+}
+
+/// A player's rating over a round, demo or season.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlayerRating {
+    /// None for season ratings, which span many user IDs.
+    pub user_id: Option<u16>,
+    pub steam_id: Option<SteamId>,
+    pub name: String,
+    /// The team they last spawned on. `Team.Other` for season ratings.
+    pub team: Team,
+    /// The time-weighted average of `classes`, or None if they didn't
+    /// play any class for `RatingModel.min_time`.
+    pub rating: Option<f32>,
+    /// Seconds, on every class.
+    pub time_played: f32,
+    /// Only the classes played for at least `RatingModel.min_time`, longest first.
+    pub classes: Vec<ClassRating>,
+}
+
+/// The ratings of everyone in a demo. Made by `DemoData.ratings`.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RatingReport {
+    /// Over the whole demo, best first.
+    pub players: Vec<PlayerRating>,
+    /// Over each round, in the order of `DemoData.rounds`, best first.
+    pub rounds: Vec<Vec<PlayerRating>>,
+}
+
+/// What a player did on a class.
+#[derive(Default, Debug, Clone, Copy)]
+struct Tally {
+    /// Seconds.
+    time: f32,
+    kills: f32,
+    assists: f32,
+    deaths: f32,
+    damage: f32,
+    healing: f32,
+    ubercharges: f32,
+}
+
+impl std::ops::AddAssign for Tally {
+    fn add_assign(&mut self, other: Tally) {
+        self.time += other.time;
+        self.kills += other.kills;
+        self.assists += other.assists;
+        self.deaths += other.deaths;
+        self.damage += other.damage;
+        self.healing += other.healing;
+        self.ubercharges += other.ubercharges;
+    }
+}
+
+/// Round index, user ID and class to what they did. Only what happened
+/// during rounds is counted.
+type Tallies = BTreeMap<(usize, u16, Class), Tally>;
+
+fn tally_demo(demo: &DemoData) -> Tallies {
+    let lives = Lives::new(demo);
+    let dt = 1.0 / demo.tick_rate();
+    // A round that hasn't ended runs to the end of the demo
+    let last_tick = (demo.duration * demo.tick_rate()) as u32;
+    let end_of = |round: usize| match demo.rounds[round].end_tick {
+        0 => last_tick,
+        end => end,
+    };
+    let round_of = |tick: u32| demo.rounds.iter().position(|r| r.contains(tick));
+
+    let mut tallies = Tallies::new();
+    let mut add = |user_id: u16, tick: u32, count: &dyn Fn(&mut Tally)| {
+        if let (Some(round), Some(life)) = (round_of(tick), lives.at(user_id, tick)) {
+            count(tallies.entry((round, user_id, life.class)).or_default());
+        }
+    };
+
+    for kill in &demo.kills {
+        let tick = u32::from(kill.tick);
+        add(kill.dead_id, tick, &|t| t.deaths += 1.0);
+        if kill.attacker_id != kill.dead_id {
+            add(kill.attacker_id, tick, &|t| t.kills += 1.0);
+        }
+        if let Some(assister) = kill.assister {
+            add(assister, tick, &|t| t.assists += 1.0);
+        }
+    }
+    for hurt in demo.damage.iter().filter(|d| d.attacker_id != d.victim_id) {
+        add(hurt.attacker_id, hurt.tick, &|t| t.damage += hurt.amount as f32);
+    }
+    for heal in demo.heals.iter().filter(|h| h.healer_id != h.patient_id) {
+        add(heal.healer_id, heal.tick, &|t| t.healing += heal.amount as f32);
+    }
+    for uber in &demo.ubercharges {
+        add(uber.medic_id, uber.tick, &|t| t.ubercharges += 1.0);
+    }
+
+    // Time on a class runs from a spawn to the next one, or the end of the round
+    for (user_id, spawns) in &lives.spawns {
+        for (i, spawn) in spawns.iter().enumerate() {
+            let Some(round) = round_of(spawn.tick) else {
+                continue;
+            };
+            let end = spawns.get(i + 1).map_or(u32::MAX, |s| s.tick).min(end_of(round));
+            let seconds = end.saturating_sub(spawn.tick) as f32 * dt;
+            add(*user_id, spawn.tick, &|t| t.time += seconds);
+        }
+    }
+
+    tallies
+}
+
+fn component(name: &str, rate: f32, baseline: f32, weight: f32, max_score: f32) -> RatingComponent {
+    let score = match name {
+        // Dying less than the baseline is better. Not dying at all gets the max.
+        "survival" if rate <= 0.0 => max_score,
+        "survival" => baseline / rate,
+        _ if baseline <= 0.0 => 0.0,
+        _ => rate / baseline,
+    }.min(max_score);
+    RatingComponent {
+        name: name.to_string(),
+        rate,
+        baseline,
+        score,
+        weight,
+        contribution: score * weight,
+    }
+}
+
+fn rate_class(model: &RatingModel, class: Class, tally: &Tally) -> ClassRating {
+    let baseline = model.baseline(class);
+    let weights = model.weights(class);
+    let minutes = tally.time / 60.0;
+    let per_minute = |value: f32| value / minutes;
+
+    let components = [
+        ("kills", per_minute(tally.kills), baseline.kills, weights.kills),
+        ("assists", per_minute(tally.assists), baseline.assists, weights.assists),
+        ("damage", per_minute(tally.damage), baseline.damage, weights.damage),
+        ("healing", per_minute(tally.healing), baseline.healing, weights.healing),
+        ("ubercharges", per_minute(tally.ubercharges), baseline.ubercharges, weights.ubercharges),
+        ("survival", per_minute(tally.deaths), baseline.deaths, weights.survival),
+    ].into_iter()
+        .filter(|(_, _, _, weight)| *weight > 0.0)
+        .map(|(name, rate, base, weight)| component(name, rate, base, weight, model.max_score))
+        .collect::<Vec<_>>();
+
+    ClassRating {
+        player_class: class,
+        time_played: tally.time,
+        rating: components.iter().map(|c| c.contribution).sum(),
+        components,
+    }
+}
+
+/// Rates a player from what they did on each class.
+fn rate_player(model: &RatingModel, classes: &BTreeMap<Class, Tally>) -> (Option<f32>, f32, Vec<ClassRating>) {
+    let time_played = classes.values().map(|t| t.time).sum::<f32>();
+    let mut rated = classes.iter()
+        .filter(|(_, tally)| tally.time >= model.min_time && tally.time > 0.0)
+        .map(|(class, tally)| rate_class(model, *class, tally))
+        .collect::<Vec<_>>();
+    rated.sort_by(|a, b| b.time_played.total_cmp(&a.time_played));
+
+    let rated_time = rated.iter().map(|c| c.time_played).sum::<f32>();
+    let rating = (rated_time > 0.0)
+        .then(|| rated.iter().map(|c| c.rating * c.time_played).sum::<f32>() / rated_time);
+    (rating, time_played, rated)
+}
+
+fn sort_best_first(players: &mut [PlayerRating]) {
+    players.sort_by(|a, b| b.rating.unwrap_or(-1.0).total_cmp(&a.rating.unwrap_or(-1.0)));
+}
+
+/// Demo and per-round ratings of everyone in `demo`.
+pub fn rating_report(demo: &DemoData, model: &RatingModel) -> RatingReport {
+    let tallies = tally_demo(demo);
+    let lives = Lives::new(demo);
+
+    let rate = |user_id: u16, classes: &BTreeMap<Class, Tally>| {
+        let (rating, time_played, classes) = rate_player(model, classes);
+        PlayerRating {
+            user_id: Some(user_id),
+            steam_id: demo.identities.user_ids.get(&user_id).copied(),
+            name: demo.identities.get(user_id).map_or(String::new(), |p| p.name.clone()),
+            team: lives.spawns.get(&user_id).and_then(|s| s.last()).map_or(Team::Other, |s| s.team),
+            rating,
+            time_played,
+            classes,
+        }
+    };
+
+    let mut by_round = vec![BTreeMap::<u16, BTreeMap<Class, Tally>>::new(); demo.rounds.len()];
+    let mut whole = BTreeMap::<u16, BTreeMap<Class, Tally>>::new();
+    for ((round, user_id, class), tally) in &tallies {
+        *by_round[*round].entry(*user_id).or_default().entry(*class).or_default() += *tally;
+        *whole.entry(*user_id).or_default().entry(*class).or_default() += *tally;
+    }
+
+    let mut players = whole.iter().map(|(user_id, classes)| rate(*user_id, classes)).collect::<Vec<_>>();
+    sort_best_first(&mut players);
+    let rounds = by_round.iter()
+        .map(|round| {
+            let mut players = round.iter().map(|(user_id, classes)| rate(*user_id, classes)).collect::<Vec<_>>();
+            sort_best_first(&mut players);
+            players
+        })
+        .collect();
+
+    RatingReport { players, rounds }
+}
+
+/// Ratings by SteamID over every demo of `season` that passes `filter`,
+/// best first. With `filter.player_class`, only that class is rated.
+pub fn season_ratings(season: &Season, model: &RatingModel, filter: &SeasonFilter) -> Vec<PlayerRating> {
+    let mut classes = BTreeMap::<SteamId, BTreeMap<Class, Tally>>::new();
+    let mut names = HashMap::<SteamId, String>::new();
+
+    for (demo, _) in season.demos.iter().zip(&season.info).filter(|(_, info)| filter.matches(info)) {
+        for ((_, user_id, class), tally) in tally_demo(demo) {
+            let Some(steam_id) = demo.identities.user_ids.get(&user_id).copied() else {
+                continue;
+            };
+            if filter.player_class.is_some_and(|c| c != class) {
+                continue;
+            }
+            *classes.entry(steam_id).or_default().entry(class).or_default() += tally;
+            // Demos are oldest first, so this ends up as the latest name
+            if let Some(identity) = demo.identities.get(user_id) {
+                names.insert(steam_id, identity.name.clone());
+            }
+        }
+    }
+
+    let mut players = classes.iter()
+        .map(|(steam_id, classes)| {
+            let (rating, time_played, classes) = rate_player(model, classes);
+            PlayerRating {
+                user_id: None,
+                steam_id: Some(*steam_id),
+                name: names.get(steam_id).cloned().unwrap_or_default(),
+                team: Team::Other,
+                rating,
+                time_played,
+                classes,
+            }
+        })
+        .collect::<Vec<_>>();
+    sort_best_first(&mut players);
+    players
+}
+
+/// The ratings, and each class's breakdown, as tables.
+pub fn ratings_to_result(players: &[PlayerRating]) -> AnalysisResult {
+    let rating = |r: Option<f32>| r.map_or(String::new(), |r| format!("{:.2}", r));
+    let ratings = Table {
+        name: "ratings".to_string(),
+        columns: ["name", "team", "rating", "minutes", "main class"].map(String::from).to_vec(),
+        rows: players.iter().map(|p| vec![
+            p.name.clone(),
+            p.team.name().to_string(),
+            rating(p.rating),
+            format!("{:.1}", p.time_played / 60.0),
+            p.classes.first().map_or(String::new(), |c| c.player_class.name().to_string()),
+        ]).collect(),
+    };
+
+    let components = Table {
+        name: "components".to_string(),
+        columns: ["name", "class", "minutes", "class rating", "component", "per minute",
+                  "baseline", "score", "weight", "contribution"]
+            .map(String::from).to_vec(),
+        rows: players.iter()
+            .flat_map(|p| p.classes.iter().flat_map(move |c| c.components.iter().map(move |part| vec![
+                p.name.clone(),
+                c.player_class.name().to_string(),
+                format!("{:.1}", c.time_played / 60.0),
+                format!("{:.2}", c.rating),
+                part.name.clone(),
+                format!("{:.2}", part.rate),
+                format!("{:.2}", part.baseline),
+                format!("{:.2}", part.score),
+                format!("{:.2}", part.weight),
+                format!("{:.2}", part.contribution),
+            ])))
+            .collect(),
+    };
+
+    AnalysisResult {
+        tables: vec![ratings, components],
+        ..Default::default()
+    }
+}
+
+impl RatingReport {
+    /// The demo ratings and breakdown, plus each round's ratings.
+    pub fn to_result(&self) -> AnalysisResult {
+        let mut result = ratings_to_result(&self.players);
+        result.tables.push(Table {
+            name: "rounds".to_string(),
+            columns: ["round", "name", "team", "rating", "minutes"].map(String::from).to_vec(),
+            rows: self.rounds.iter().enumerate()
+                .flat_map(|(i, players)| players.iter().map(move |p| vec![
+                    (i + 1).to_string(),
+                    p.name.clone(),
+                    p.team.name().to_string(),
+                    p.rating.map_or(String::new(), |r| format!("{:.2}", r)),
+                    format!("{:.1}", p.time_played / 60.0),
+                ]))
+                .collect(),
+        });
+        result
+    }
+}
+
+py_pickle!(ClassBaseline, RatingWeights, RatingModel, RatingComponent, ClassRating, PlayerRating, RatingReport);
+py_eq!(ClassBaseline, RatingWeights, RatingModel, RatingComponent, ClassRating, PlayerRating, RatingReport);
+py_debug_repr!(ClassBaseline, RatingWeights, RatingModel, RatingComponent, ClassRating, PlayerRating, RatingReport);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn score_is_rate_over_baseline() {
+        let kills = component("kills", 1.5, 1.0, 2.0, 3.0);
+        assert_eq!(kills.score, 1.5);
+        assert_eq!(kills.contribution, 3.0);
+        assert_eq!(component("kills", 0.0, 1.0, 2.0, 3.0).score, 0.0);
+    }
+
+    #[test]
+    fn score_is_capped() {
+        let kills = component("kills", 10.0, 1.0, 2.0, 3.0);
+        assert_eq!(kills.score, 3.0);
+        assert_eq!(kills.contribution, 6.0);
+        assert_eq!(component("survival", 0.1, 1.0, 1.0, 3.0).score, 3.0);
+    }
+
+    #[test]
+    fn zero_baseline_scores_nothing() {
+        assert_eq!(component("damage", 300.0, 0.0, 1.0, 3.0).score, 0.0);
+    }
+
+    #[test]
+    fn survival_is_inverted() {
+        assert_eq!(component("survival", 0.5, 1.0, 1.0, 3.0).score, 2.0);
+        assert_eq!(component("survival", 2.0, 1.0, 1.0, 3.0).score, 0.5);
+        // Not dying at all gets the max rather than dividing by zero
+        let survival = component("survival", 0.0, 1.0, 1.5, 3.0);
+        assert_eq!(survival.score, 3.0);
+        assert_eq!(survival.contribution, 4.5);
+    }
+}
@@ -0,0 +1,334 @@
+//! Respawn times, respawn waves, and how long teams took to recover from wipes.
+//!
+//! TF2 respawns each team in waves, so how long a player waits after dying
+//! depends on when they died relative to their team's next wave. Dying just
+//! after a wave means waiting nearly the longest respawn there is.
+
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+use serde::{Serialize, Deserialize};
+
+use crate::analysisresult::{AnalysisResult, Annotation, Table};
+use crate::types::demo::DemoData;
+use crate::types::events::{Kill, Spawn};
+use crate::types::game::{Class, Round, Team};
+
+/// Spawns of a team less than this many seconds apart are in the same wave.
+const WAVE_GAP: f32 = 1.0;
+/// A death with at least this `wave_fraction` just missed a wave.
+const BAD_WAVE: f32 = 0.75;
+/// A death with at most this `wave_fraction` made it in time for a wave.
+const GOOD_WAVE: f32 = 0.25;
+
+/// How long a player waited to respawn after dying.
+#[pyclass(module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Respawn {
+    #[pyo3(get)]
+    pub kill: Kill,
+    #[pyo3(get)]
+    pub name: String,
+    /// The team and class the player last spawned as.
+    #[pyo3(get)]
+    pub team: Team,
+    #[pyo3(get, name="player_class")]
+    pub class: Class,
+    /// None if the round or demo ended before the player spawned again.
+    #[pyo3(get)]
+    pub spawn_tick: Option<u32>,
+    /// Seconds from the death to the spawn.
+    #[pyo3(get)]
+    pub respawn_time: Option<f32>,
+    /// `respawn_time` over the longest respawn of the team. Near 1 the player
+    /// just missed a wave, near 0 they made it in time for one.
+    #[pyo3(get)]
+    pub wave_fraction: Option<f32>,
+}
+
+/// Players of a team spawning together.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RespawnWave {
+    pub team: Team,
+    pub start_tick: u32,
+    pub end_tick: u32,
+    /// In the order they spawned.
+    pub user_ids: Vec<u16>,
+}
+
+/// Respawn timing of a team.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TeamRespawns {
+    pub team: Team,
+    pub deaths: u32,
+    /// Seconds, over the deaths that were followed by a spawn.
+    pub avg_respawn_time: f32,
+    pub longest_respawn: f32,
+    /// Deaths that just missed a wave, with a `wave_fraction` of 0.75 or more.
+    pub bad_waves: u32,
+    /// Deaths that made it in time for a wave, with a `wave_fraction` of 0.25 or less.
+    pub good_waves: u32,
+    /// Kills by this team that made the victim just miss a wave.
+    pub forced_bad_waves: u32,
+}
+
+/// Every living player of a team dying.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Wipe {
+    pub team: Team,
+    /// The tick of the last death.
+    pub tick: u32,
+    /// The tick everyone on the team was alive again, if that happened
+    /// before the round ended.
+    pub back_tick: Option<u32>,
+    /// Seconds from `tick` to `back_tick`.
+    pub time_to_full: Option<f32>,
+}
+
+/// Spawns, respawns and wipes of a demo or round. Made by `DemoData.respawns`.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RespawnReport {
+    /// One for each death of a player that had spawned, in tick order.
+    pub respawns: Vec<Respawn>,
+    /// In the order they started.
+    pub waves: Vec<RespawnWave>,
+    /// RED, then BLU.
+    pub teams: Vec<TeamRespawns>,
+    /// In tick order.
+    pub wipes: Vec<Wipe>,
+}
+
+fn waves(spawns: &[&Spawn], gap_ticks: u32) -> Vec<RespawnWave> {
+    let mut waves: Vec<RespawnWave> = Vec::new();
+    for team in [Team::Red, Team::Blue] {
+        let mut current: Option<RespawnWave> = None;
+        for spawn in spawns.iter().filter(|s| s.team == team) {
+            if let Some(wave) = current.as_mut() {
+                if spawn.tick - wave.end_tick <= gap_ticks {
+                    wave.end_tick = spawn.tick;
+                    wave.user_ids.push(spawn.user_id);
+                    continue;
+                }
+            }
+            waves.extend(current.take());
+            current = Some(RespawnWave {
+                team,
+                start_tick: spawn.tick,
+                end_tick: spawn.tick,
+                user_ids: vec![spawn.user_id],
+            });
+        }
+        waves.extend(current);
+    }
+
+    waves.sort_by_key(|w| w.start_tick);
+    waves
+}
+
+enum LifeEvent<'a> {
+    Death(&'a Kill),
+    Spawn(&'a Spawn),
+}
+
+/// Follows who is alive from spawns and deaths. A team is back once as many
+/// of its players are alive as have spawned on it this round.
+fn wipes(kills: &[&Kill], spawns: &[&Spawn], round_start: impl Fn(u32) -> u32, tick_rate: f32) -> Vec<Wipe> {
+    // Deaths go first when they happen on the same tick as spawns
+    let mut events = kills.iter().map(|k| (u32::from(k.tick), 0, LifeEvent::Death(k)))
+        .chain(spawns.iter().map(|s| (s.tick, 1, LifeEvent::Spawn(s))))
+        .collect::<Vec<_>>();
+    events.sort_by_key(|(tick, order, _)| (*tick, *order));
+
+    let mut wipes: Vec<Wipe> = Vec::new();
+    let mut alive = HashMap::<u16, Team>::new();
+    let mut members = HashMap::<u16, Team>::new();
+    // Index in `wipes` of each team that hasn't recovered yet
+    let mut open = HashMap::<Team, usize>::new();
+    let mut current_round = None;
+
+    for (tick, _, event) in events {
+        if current_round != Some(round_start(tick)) {
+            current_round = Some(round_start(tick));
+            alive.clear();
+            members.clear();
+            open.clear();
+        }
+
+        match event {
+            LifeEvent::Death(kill) => {
+                let Some(team) = alive.remove(&kill.dead_id) else {
+                    continue;
+                };
+                if team.is_player() && !alive.values().any(|t| *t == team) && !open.contains_key(&team) {
+                    open.insert(team, wipes.len());
+                    wipes.push(Wipe {team, tick, ..Default::default()});
+                }
+            },
+            LifeEvent::Spawn(spawn) => {
+                alive.insert(spawn.user_id, spawn.team);
+                members.insert(spawn.user_id, spawn.team);
+
+                let count = |players: &HashMap<u16, Team>| players.values().filter(|t| **t == spawn.team).count();
+                if let (Some(i), true) = (open.get(&spawn.team).copied(), count(&alive) >= count(&members)) {
+                    let wipe = &mut wipes[i];
+                    wipe.back_tick = Some(tick);
+                    wipe.time_to_full = Some((tick - wipe.tick) as f32 / tick_rate);
+                    open.remove(&spawn.team);
+                }
+            },
+        }
+    }
+
+    wipes
+}
+
+/// Respawn times, waves and wipes over every round of `demo`, or only `round`.
+/// Deaths and spawns between rounds are left out. Only needs the events,
+/// so it works with `store_ticks=False`, though names are then empty.
+pub fn respawn_report(demo: &DemoData, round: Option<&Round>) -> RespawnReport {
+    let rate = demo.tick_rate();
+    let rounds = match round {
+        Some(round) => vec![*round],
+        None => demo.rounds.clone(),
+    };
+    let round_of = |tick: u32| rounds.iter().find(|r| r.contains(tick));
+
+    let mut spawns = demo.spawns.iter().filter(|s| round_of(s.tick).is_some()).collect::<Vec<&Spawn>>();
+    spawns.sort_by_key(|s| s.tick);
+    let mut kills = demo.kills.iter().filter(|k| round_of(u32::from(k.tick)).is_some()).collect::<Vec<&Kill>>();
+    kills.sort_by_key(|k| u32::from(k.tick));
+
+    let last_spawn = |user_id: u16, tick: u32| spawns.iter().rev().find(|s| s.user_id == user_id && s.tick <= tick);
+
+    let mut respawns = kills.iter()
+        .filter_map(|kill| {
+            let tick = u32::from(kill.tick);
+            let life = last_spawn(kill.dead_id, tick)?;
+            let round = round_of(tick)?;
+            let spawn = spawns.iter().find(|s| s.user_id == kill.dead_id && s.tick > tick && round.contains(s.tick));
+            Some(Respawn {
+                kill: (*kill).clone(),
+                name: demo.player_at(tick, kill.dead_id)
+                    .and_then(|p| p.info.as_ref())
+                    .map_or(String::new(), |info| info.name.clone()),
+                team: life.team,
+                class: life.class,
+                spawn_tick: spawn.map(|s| s.tick),
+                respawn_time: spawn.map(|s| (s.tick - tick) as f32 / rate),
+                wave_fraction: None,
+            })
+        })
+        .collect::<Vec<Respawn>>();
+
+    let longest = |team: Team| respawns.iter()
+        .filter(|r| r.team == team)
+        .filter_map(|r| r.respawn_time)
+        .fold(0.0, f32::max);
+    let longest = HashMap::from([(Team::Red, longest(Team::Red)), (Team::Blue, longest(Team::Blue))]);
+    for respawn in &mut respawns {
+        let max = longest.get(&respawn.team).copied().unwrap_or(0.0);
+        respawn.wave_fraction = respawn.respawn_time.filter(|_| max > 0.0).map(|t| t / max);
+    }
+
+    let teams = [Team::Red, Team::Blue].into_iter()
+        .map(|team| {
+            let own = respawns.iter().filter(|r| r.team == team).collect::<Vec<&Respawn>>();
+            let times = own.iter().filter_map(|r| r.respawn_time).collect::<Vec<f32>>();
+            let fraction = |r: &Respawn| r.wave_fraction.unwrap_or(f32::NAN);
+            let forced = respawns.iter()
+                .filter(|r| r.team != team && fraction(r) >= BAD_WAVE)
+                .filter(|r| last_spawn(r.kill.attacker_id, u32::from(r.kill.tick)).is_some_and(|s| s.team == team))
+                .count();
+            TeamRespawns {
+                team,
+                deaths: own.len() as u32,
+                avg_respawn_time: if times.is_empty() {0.0} else {times.iter().sum::<f32>() / times.len() as f32},
+                longest_respawn: longest[&team],
+                bad_waves: own.iter().filter(|r| fraction(r) >= BAD_WAVE).count() as u32,
+                good_waves: own.iter().filter(|r| fraction(r) <= GOOD_WAVE).count() as u32,
+                forced_bad_waves: forced as u32,
+            }
+        })
+        .collect();
+
+    let round_start = |tick: u32| round_of(tick).map_or(0, |r| r.start_tick);
+    RespawnReport {
+        waves: waves(&spawns, (WAVE_GAP * rate) as u32),
+        wipes: wipes(&kills, &spawns, round_start, rate),
+        respawns,
+        teams,
+    }
+}
+
+impl RespawnReport {
+    /// The report as tables, with an annotation for each wipe.
+    pub fn to_result(&self) -> AnalysisResult {
+        let optional = |value: Option<f32>, scale: f32| value.map_or(String::new(), |v| format!("{:.1}", v * scale));
+
+        let teams = Table {
+            name: "teams".to_string(),
+            columns: ["team", "deaths", "avg respawn", "longest respawn", "bad waves",
+                      "good waves", "forced bad waves"]
+                .map(String::from).to_vec(),
+            rows: self.teams.iter().map(|t| vec![
+                t.team.name().to_string(),
+                t.deaths.to_string(),
+                format!("{:.1}", t.avg_respawn_time),
+                format!("{:.1}", t.longest_respawn),
+                t.bad_waves.to_string(),
+                t.good_waves.to_string(),
+                t.forced_bad_waves.to_string(),
+            ]).collect(),
+        };
+
+        let respawns = Table {
+            name: "respawns".to_string(),
+            columns: ["name", "team", "class", "death", "spawn", "respawn time", "wave %"]
+                .map(String::from).to_vec(),
+            rows: self.respawns.iter().map(|r| vec![
+                r.name.clone(),
+                r.team.name().to_string(),
+                r.class.name().to_string(),
+                u32::from(r.kill.tick).to_string(),
+                r.spawn_tick.map_or(String::new(), |t| t.to_string()),
+                optional(r.respawn_time, 1.0),
+                optional(r.wave_fraction, 100.0),
+            ]).collect(),
+        };
+
+        let wipes = Table {
+            name: "wipes".to_string(),
+            columns: ["team", "tick", "back", "time to full"].map(String::from).to_vec(),
+            rows: self.wipes.iter().map(|w| vec![
+                w.team.name().to_string(),
+                w.tick.to_string(),
+                w.back_tick.map_or(String::new(), |t| t.to_string()),
+                optional(w.time_to_full, 1.0),
+            ]).collect(),
+        };
+
+        let annotations = self.wipes.iter()
+            .map(|w| Annotation {
+                tick: w.tick,
+                text: match w.time_to_full {
+                    Some(time) => format!("{} wiped, back in {:.1}s", w.team.name(), time),
+                    None => format!("{} wiped", w.team.name()),
+                },
+            })
+            .collect();
+
+        AnalysisResult {
+            tables: vec![teams, respawns, wipes],
+            annotations,
+            ..Default::default()
+        }
+    }
+}
+
+py_pickle!(Respawn, RespawnWave, TeamRespawns, Wipe, RespawnReport);
+py_eq!(Respawn, RespawnWave, TeamRespawns, Wipe, RespawnReport);
+py_debug_repr!(Respawn, RespawnWave, TeamRespawns, Wipe, RespawnReport);
@@ -0,0 +1,610 @@
+//! Totals over many demos, like a season of matches, by player and by team.
+//!
+//! Players are followed across demos by SteamID. Which side of a demo is
+//! which team comes from rosters of SteamIDs, like the `[season]` section
+//! of the config:
+//!
+//! ```toml
+//! [season]
+//! cache_dir = "parse_cache"
+//! team = "us"
+//!
+//! [season.teams]
+//! us = ["[U:1:22202]", "[U:1:22203]"]
+//! froyotech = ["76561197960287932"]
+//! ```
+//!
+//! Only what happens during rounds is counted, and none of it needs the
+//! tick states, so seasons are parsed without them.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use itertools::Itertools;
+use pyo3::prelude::*;
+use serde::{Serialize, Deserialize};
+
+use crate::analysisresult::{AnalysisResult, Table};
+use crate::types::demo::DemoData;
+use crate::types::events::Spawn;
+use crate::types::game::{Class, Round, Team};
+use crate::types::identity::SteamId;
+use super::rating::{self, PlayerRating, RatingModel};
+
+/// Which demos, and which of each player's time, go into a season summary.
+/// Anything left as None isn't filtered on.
+#[pyclass(get_all, set_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SeasonFilter {
+    /// Part of the map name, ignoring case, e.g. "process".
+    pub map: Option<String>,
+    /// The name of a team that played against `Season.our_team`,
+    /// or played at all if there is no `our_team`.
+    pub opponent: Option<String>,
+    /// The earliest date, as YYYY-MM-DD. Demos without a date are left out.
+    pub since: Option<String>,
+    /// The latest date, as YYYY-MM-DD. Demos without a date are left out.
+    pub until: Option<String>,
+    /// Only count players' stats while they were this class.
+    /// Team summaries aren't affected.
+    pub player_class: Option<Class>,
+}
+
+#[pymethods]
+impl SeasonFilter {
+    #[new]
+    #[pyo3(signature = (map=None, opponent=None, since=None, until=None, player_class=None))]
+    fn new(map: Option<String>, opponent: Option<String>, since: Option<String>, until: Option<String>,
+           player_class: Option<Class>) -> Self {
+        SeasonFilter { map, opponent, since, until, player_class }
+    }
+}
+
+impl SeasonFilter {
+    pub(crate) fn matches(&self, demo: &SeasonDemo) -> bool {
+        let date = demo.date.as_deref();
+        self.map.as_ref().map_or(true, |map| demo.map_name.to_lowercase().contains(&map.to_lowercase()))
+            && self.opponent.as_ref().map_or(true, |name| demo.opponents().any(|o| o.eq_ignore_ascii_case(name)))
+            && self.since.as_ref().map_or(true, |since| date.is_some_and(|d| d >= since.as_str()))
+            // Only compare the date itself, so "2024-03" includes all of March
+            && self.until.as_ref().map_or(true, |until| date.is_some_and(|d| &d[..until.len().min(d.len())] <= until.as_str()))
+    }
+}
+
+/// What a season knows about one of its demos.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SeasonDemo {
+    pub demo_filename: PathBuf,
+    pub map_name: String,
+    /// YYYY-MM-DD, from the first date in the file name, or else the
+    /// day the file was last modified. None if that was before 1970.
+    pub date: Option<String>,
+    /// The roster that had the most players on RED, or "RED" if none did.
+    pub red: String,
+    /// The roster that had the most players on BLU, or "BLU" if none did.
+    pub blue: String,
+    /// The side `Season.our_team` played on, if they played.
+    pub our_side: Option<Team>,
+    pub red_rounds: u32,
+    pub blue_rounds: u32,
+}
+
+impl SeasonDemo {
+    fn side_name(&self, team: Team) -> &str {
+        if team == Team::Red {&self.red} else {&self.blue}
+    }
+
+    /// The names of the teams that aren't ours.
+    fn opponents(&self) -> impl Iterator<Item = &str> {
+        [Team::Red, Team::Blue].into_iter()
+            .filter(|team| self.our_side != Some(*team))
+            .map(|team| self.side_name(team))
+    }
+}
+
+/// A player's totals over a season.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SeasonPlayer {
+    pub steam_id: SteamId,
+    /// The name in the latest demo they were in.
+    pub name: String,
+    /// Demos they spawned in.
+    pub demos: u32,
+    pub kills: u32,
+    pub assists: u32,
+    pub deaths: u32,
+    pub damage: u32,
+    pub damage_taken: u32,
+    /// Healing done to other players.
+    pub healing: u32,
+    pub ubercharges: u32,
+    /// Seconds alive.
+    pub alive_time: f32,
+    /// Seconds alive as each class.
+    pub class_time: HashMap<Class, f32>,
+}
+
+#[pymethods]
+impl SeasonPlayer {
+    /// Kills per death, or kills if they never died.
+    #[getter]
+    fn kd(&self) -> f32 {
+        self.kills as f32 / self.deaths.max(1) as f32
+    }
+
+    /// Damage per minute alive.
+    #[getter]
+    fn damage_per_minute(&self) -> f32 {
+        if self.alive_time > 0.0 {self.damage as f32 / (self.alive_time / 60.0)} else {0.0}
+    }
+}
+
+/// A team's totals over a season. Teams without a roster are named after
+/// their side, so all of the unknown REDs are added up together.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SeasonTeam {
+    pub name: String,
+    pub demos: u32,
+    /// Demos they won more rounds in than the other team.
+    pub wins: u32,
+    pub losses: u32,
+    pub rounds_won: u32,
+    pub rounds_lost: u32,
+    pub kills: u32,
+    pub deaths: u32,
+    pub damage: u32,
+    pub ubercharges: u32,
+}
+
+/// Totals of the demos that passed a filter. Made by `Season.summary`.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SeasonSummary {
+    /// The demos counted, oldest first.
+    pub demos: Vec<SeasonDemo>,
+    /// Ordered by SteamID.
+    pub players: Vec<SeasonPlayer>,
+    /// Ordered by name.
+    pub teams: Vec<SeasonTeam>,
+}
+
+/// Many demos and the rosters of the teams that played in them.
+/// Made by `tf2dal.load_season`, or from demos that are already loaded.
+#[pyclass(module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Season {
+    /// Oldest first. Demos without a date go last.
+    pub demos: Vec<DemoData>,
+    #[pyo3(get)]
+    pub info: Vec<SeasonDemo>,
+    /// Team name to the SteamIDs of its players.
+    #[pyo3(get)]
+    pub teams: HashMap<String, Vec<SteamId>>,
+    #[pyo3(get)]
+    pub our_team: Option<String>,
+}
+
+#[pymethods]
+impl Season {
+    #[new]
+    #[pyo3(signature = (demos, teams=HashMap::new(), our_team=None))]
+    fn py_new(py: Python<'_>, demos: Vec<DemoData>, teams: HashMap<String, Vec<SteamId>>, our_team: Option<String>) -> Self {
+        py.allow_threads(|| Season::new(demos, teams, our_team))
+    }
+
+    /// Every demo, in the same order as `info`. Copies them all.
+    #[getter]
+    fn demos(&self) -> Vec<DemoData> {
+        self.demos.clone()
+    }
+
+    /// Player and team totals over the demos that pass `filter`.
+    #[pyo3(name = "summary", signature = (filter=None))]
+    fn py_summary(&self, py: Python<'_>, filter: Option<SeasonFilter>) -> SeasonSummary {
+        let filter = filter.unwrap_or_default();
+        py.allow_threads(|| self.summary(&filter))
+    }
+
+    /// Impact ratings by SteamID over the demos that pass `filter`, best
+    /// first. Uses `RatingModel()` unless `model` is given.
+    #[pyo3(signature = (model=None, filter=None))]
+    fn ratings(&self, py: Python<'_>, model: Option<RatingModel>, filter: Option<SeasonFilter>) -> Vec<PlayerRating> {
+        let model = model.unwrap_or_default();
+        let filter = filter.unwrap_or_default();
+        py.allow_threads(|| rating::season_ratings(self, &model, &filter))
+    }
+
+    fn __len__(&self) -> usize {
+        self.demos.len()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Season(demos={}, teams={:?}, our_team={:?})",
+            self.demos.len(), self.teams.keys().sorted().collect_vec(), self.our_team)
+    }
+}
+
+/// The first YYYY-MM-DD or YYYY_MM_DD in `name`.
+fn date_in_name(name: &str) -> Option<String> {
+    name.as_bytes().windows(10)
+        .find(|w| {
+            let digits = |range: std::ops::Range<usize>| w[range].iter().all(u8::is_ascii_digit);
+            let number = |range: std::ops::Range<usize>| std::str::from_utf8(&w[range]).ok()?.parse::<u32>().ok();
+            digits(0..4) && digits(5..7) && digits(8..10)
+                && (w[4] == b'-' || w[4] == b'_') && w[7] == w[4]
+                && number(5..7).is_some_and(|m| (1..=12).contains(&m))
+                && number(8..10).is_some_and(|d| (1..=31).contains(&d))
+        })
+        .map(|w| format!("{}-{}-{}",
+            String::from_utf8_lossy(&w[0..4]), String::from_utf8_lossy(&w[5..7]), String::from_utf8_lossy(&w[8..10])))
+}
+
+/// YYYY-MM-DD of `time`, in UTC, or None before 1970.
+fn utc_date(time: SystemTime) -> Option<String> {
+    // Howard Hinnant's civil_from_days
+    let days = (time.duration_since(UNIX_EPOCH).ok()?.as_secs() / 86400) as i64 + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 {mp + 3} else {mp - 9};
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    Some(format!("{:04}-{:02}-{:02}", year, month, day))
+}
+
+fn demo_date(path: &Path) -> Option<String> {
+    path.file_name()
+        .and_then(|name| date_in_name(&name.to_string_lossy()))
+        .or_else(|| std::fs::metadata(path).and_then(|m| m.modified()).ok().and_then(utc_date))
+}
+
+/// Looks up who played what when in a demo, from its spawns and deaths.
+pub(crate) struct Lives<'a> {
+    pub spawns: HashMap<u16, Vec<&'a Spawn>>,
+    deaths: HashMap<u16, Vec<u32>>,
+}
+
+impl<'a> Lives<'a> {
+    pub fn new(demo: &'a DemoData) -> Self {
+        let mut spawns = HashMap::<u16, Vec<&Spawn>>::new();
+        for spawn in &demo.spawns {
+            spawns.entry(spawn.user_id).or_default().push(spawn);
+        }
+        for list in spawns.values_mut() {
+            list.sort_by_key(|s| s.tick);
+        }
+
+        let mut deaths = HashMap::<u16, Vec<u32>>::new();
+        for kill in &demo.kills {
+            deaths.entry(kill.dead_id).or_default().push(u32::from(kill.tick));
+        }
+        for list in deaths.values_mut() {
+            list.sort();
+        }
+
+        Lives { spawns, deaths }
+    }
+
+    /// The last spawn of `user_id` at or before `tick`.
+    pub fn at(&self, user_id: u16, tick: u32) -> Option<&'a Spawn> {
+        let list = self.spawns.get(&user_id)?;
+        let index = list.partition_point(|s| s.tick <= tick);
+        index.checked_sub(1).map(|i| list[i])
+    }
+
+    /// The first death of `user_id` after `tick`.
+    fn death_after(&self, user_id: u16, tick: u32) -> Option<u32> {
+        let list = self.deaths.get(&user_id)?;
+        list.get(list.partition_point(|t| *t <= tick)).copied()
+    }
+}
+
+/// A demo being added to a season summary.
+struct SummaryDemo<'a> {
+    demo: &'a DemoData,
+    lives: Lives<'a>,
+    filter: &'a SeasonFilter,
+}
+
+impl SummaryDemo<'_> {
+    /// The totals of the player with `user_id`, if what they did at `tick` counts.
+    fn player<'p>(&self, players: &'p mut BTreeMap<SteamId, SeasonPlayer>, user_id: u16, tick: u32)
+            -> Option<&'p mut SeasonPlayer> {
+        let steam_id = *self.demo.identities.user_ids.get(&user_id)?;
+        let life = self.lives.at(user_id, tick)?;
+        if round_at(&self.demo.rounds, tick).is_none() || self.filter.player_class.is_some_and(|c| c != life.class) {
+            return None;
+        }
+
+        let entry = players.entry(steam_id).or_insert_with(|| SeasonPlayer {steam_id, ..Default::default()});
+        if let Some(identity) = self.demo.identities.get(user_id) {
+            entry.name = identity.name.clone();
+        }
+        Some(entry)
+    }
+}
+
+/// The round `tick` is in, if any. A round that hasn't ended has an end tick of 0.
+fn round_at(rounds: &[Round], tick: u32) -> Option<&Round> {
+    rounds.iter().find(|r| r.contains(tick))
+}
+
+impl Season {
+    /// Orders `demos` by date and works out which roster played on which side.
+    pub fn new(mut demos: Vec<DemoData>, teams: HashMap<String, Vec<SteamId>>, our_team: Option<String>) -> Self {
+        let mut info = demos.iter()
+            .map(|demo| SeasonDemo {
+                demo_filename: demo.demo_filename.clone(),
+                map_name: demo.map_name.clone(),
+                date: demo_date(&demo.demo_filename),
+                ..Default::default()
+            })
+            .collect::<Vec<_>>();
+
+        for (demo, info) in demos.iter().zip(&mut info) {
+            let lives = Lives::new(demo);
+            for team in [Team::Red, Team::Blue] {
+                // Everyone who mostly spawned on this side
+                let players = demo.identities.user_ids.iter()
+                    .filter(|(user_id, _)| lives.spawns.get(user_id).is_some_and(|spawns| {
+                        spawns.iter().filter(|s| s.team == team).count() * 2 > spawns.len()
+                    }))
+                    .map(|(_, steam_id)| *steam_id)
+                    .collect::<Vec<SteamId>>();
+                let roster = teams.iter()
+                    .map(|(name, members)| (name, players.iter().filter(|p| members.contains(p)).count()))
+                    .filter(|(_, count)| *count > 0)
+                    .max_by(|a, b| a.1.cmp(&b.1).then_with(|| b.0.cmp(a.0)))
+                    .map_or_else(|| team.name().to_string(), |(name, _)| name.clone());
+
+                if our_team.as_ref() == Some(&roster) {
+                    info.our_side = Some(team);
+                }
+                let won = demo.rounds.iter().filter(|r| r.winner == team).count() as u32;
+                match team {
+                    Team::Red => {info.red = roster; info.red_rounds = won},
+                    _ => {info.blue = roster; info.blue_rounds = won},
+                }
+            }
+        }
+
+        // Sort both the same way, undated demos last
+        let mut order = (0..demos.len()).collect::<Vec<usize>>();
+        order.sort_by_key(|i| (info[*i].date.is_none(), info[*i].date.clone()));
+        let demos = order.iter().map(|i| std::mem::take(&mut demos[*i])).collect();
+        let info = order.iter().map(|i| info[*i].clone()).collect();
+
+        Season { demos, info, teams, our_team }
+    }
+
+    /// Player and team totals over the demos that pass `filter`.
+    pub fn summary(&self, filter: &SeasonFilter) -> SeasonSummary {
+        let mut players = BTreeMap::<SteamId, SeasonPlayer>::new();
+        let mut teams = BTreeMap::<String, SeasonTeam>::new();
+        let mut demos = Vec::new();
+
+        for (demo, info) in self.demos.iter().zip(&self.info).filter(|(_, info)| filter.matches(info)) {
+            demos.push(info.clone());
+            let current = SummaryDemo {demo, lives: Lives::new(demo), filter};
+            let lives = &current.lives;
+            let in_round = |tick: u32| round_at(&demo.rounds, tick).is_some();
+
+            for kill in &demo.kills {
+                let tick = u32::from(kill.tick);
+                if let Some(entry) = current.player(&mut players, kill.dead_id, tick) {
+                    entry.deaths += 1;
+                }
+                if kill.attacker_id != kill.dead_id {
+                    if let Some(entry) = current.player(&mut players, kill.attacker_id, tick) {
+                        entry.kills += 1;
+                    }
+                }
+                if let Some(entry) = kill.assister.and_then(|id| current.player(&mut players, id, tick)) {
+                    entry.assists += 1;
+                }
+            }
+            for hurt in demo.damage.iter().filter(|d| d.attacker_id != d.victim_id) {
+                if let Some(entry) = current.player(&mut players, hurt.attacker_id, hurt.tick) {
+                    entry.damage += hurt.amount as u32;
+                }
+                if let Some(entry) = current.player(&mut players, hurt.victim_id, hurt.tick) {
+                    entry.damage_taken += hurt.amount as u32;
+                }
+            }
+            for heal in demo.heals.iter().filter(|h| h.healer_id != h.patient_id) {
+                if let Some(entry) = current.player(&mut players, heal.healer_id, heal.tick) {
+                    entry.healing += heal.amount as u32;
+                }
+            }
+            for uber in &demo.ubercharges {
+                if let Some(entry) = current.player(&mut players, uber.medic_id, uber.tick) {
+                    entry.ubercharges += 1;
+                }
+            }
+
+            // Lives last until the player dies, spawns again, or the round ends
+            let dt = 1.0 / demo.tick_rate();
+            let mut played = Vec::new();
+            for (user_id, spawns) in &lives.spawns {
+                for (i, spawn) in spawns.iter().enumerate() {
+                    let Some(round) = round_at(&demo.rounds, spawn.tick) else {
+                        continue;
+                    };
+                    let end = [
+                        lives.death_after(*user_id, spawn.tick),
+                        spawns.get(i + 1).map(|s| s.tick),
+                        (round.end_tick > 0).then_some(round.end_tick),
+                    ].into_iter().flatten().min();
+                    let (Some(end), Some(entry)) = (end, current.player(&mut players, *user_id, spawn.tick)) else {
+                        continue;
+                    };
+                    let seconds = (end - spawn.tick) as f32 * dt;
+                    entry.alive_time += seconds;
+                    *entry.class_time.entry(spawn.class).or_default() += seconds;
+                    played.push(entry.steam_id);
+                }
+            }
+            played.sort();
+            played.dedup();
+            for steam_id in played {
+                players.get_mut(&steam_id).unwrap().demos += 1;
+            }
+
+            // Team totals aren't filtered by class
+            for team in [Team::Red, Team::Blue] {
+                let other = if team == Team::Red {Team::Blue} else {Team::Red};
+                let name = info.side_name(team).to_string();
+                let entry = teams.entry(name.clone()).or_insert_with(|| SeasonTeam {name, ..Default::default()});
+                let (won, lost) = match team {
+                    Team::Red => (info.red_rounds, info.blue_rounds),
+                    _ => (info.blue_rounds, info.red_rounds),
+                };
+                entry.demos += 1;
+                entry.rounds_won += won;
+                entry.rounds_lost += lost;
+                entry.wins += u32::from(won > lost);
+                entry.losses += u32::from(won < lost);
+
+                let on_team = |user_id: u16, tick: u32| in_round(tick) && lives.at(user_id, tick).is_some_and(|s| s.team == team);
+                entry.kills += demo.kills.iter()
+                    .filter(|k| k.attacker_id != k.dead_id && on_team(k.attacker_id, u32::from(k.tick))
+                        && !on_team(k.dead_id, u32::from(k.tick)))
+                    .count() as u32;
+                entry.deaths += demo.kills.iter().filter(|k| on_team(k.dead_id, u32::from(k.tick))).count() as u32;
+                entry.damage += demo.damage.iter()
+                    .filter(|d| on_team(d.attacker_id, d.tick) && lives.at(d.victim_id, d.tick).is_some_and(|s| s.team == other))
+                    .map(|d| d.amount as u32)
+                    .sum::<u32>();
+                entry.ubercharges += demo.ubercharges.iter().filter(|u| on_team(u.medic_id, u.tick)).count() as u32;
+            }
+        }
+
+        SeasonSummary {
+            demos,
+            players: players.into_values().collect(),
+            teams: teams.into_values().collect(),
+        }
+    }
+}
+
+impl SeasonSummary {
+    /// The summary as tables, for printing and CSV export.
+    pub fn to_result(&self) -> AnalysisResult {
+        let players = Table {
+            name: "players".to_string(),
+            columns: ["steam id", "name", "demos", "main class", "kills", "assists", "deaths", "kd",
+                      "damage", "dpm", "damage taken", "healing", "ubers", "alive"]
+                .map(String::from).to_vec(),
+            rows: self.players.iter().map(|p| vec![
+                p.steam_id.steam3(),
+                p.name.clone(),
+                p.demos.to_string(),
+                p.class_time.iter()
+                    .max_by(|a, b| a.1.total_cmp(b.1))
+                    .map_or(String::new(), |(class, _)| class.name().to_string()),
+                p.kills.to_string(),
+                p.assists.to_string(),
+                p.deaths.to_string(),
+                format!("{:.2}", p.kd()),
+                p.damage.to_string(),
+                format!("{:.0}", p.damage_per_minute()),
+                p.damage_taken.to_string(),
+                p.healing.to_string(),
+                p.ubercharges.to_string(),
+                format!("{:.0}", p.alive_time),
+            ]).collect(),
+        };
+
+        let teams = Table {
+            name: "teams".to_string(),
+            columns: ["team", "demos", "wins", "losses", "rounds won", "rounds lost",
+                      "kills", "deaths", "damage", "ubers"]
+                .map(String::from).to_vec(),
+            rows: self.teams.iter().map(|t| vec![
+                t.name.clone(),
+                t.demos.to_string(),
+                t.wins.to_string(),
+                t.losses.to_string(),
+                t.rounds_won.to_string(),
+                t.rounds_lost.to_string(),
+                t.kills.to_string(),
+                t.deaths.to_string(),
+                t.damage.to_string(),
+                t.ubercharges.to_string(),
+            ]).collect(),
+        };
+
+        let demos = Table {
+            name: "demos".to_string(),
+            columns: ["file", "map", "date", "red", "blue", "red rounds", "blue rounds"]
+                .map(String::from).to_vec(),
+            rows: self.demos.iter().map(|d| vec![
+                d.demo_filename.file_name().unwrap_or_default().to_string_lossy().to_string(),
+                d.map_name.clone(),
+                d.date.clone().unwrap_or_default(),
+                d.red.clone(),
+                d.blue.clone(),
+                d.red_rounds.to_string(),
+                d.blue_rounds.to_string(),
+            ]).collect(),
+        };
+
+        AnalysisResult {
+            metrics: vec![("demos".to_string(), self.demos.len() as f64)],
+            tables: vec![teams, players, demos],
+            ..Default::default()
+        }
+    }
+}
+
+py_pickle!(SeasonFilter, SeasonDemo, SeasonPlayer, SeasonTeam, SeasonSummary, Season);
+py_eq!(SeasonFilter, SeasonDemo, SeasonPlayer, SeasonTeam, SeasonSummary, Season);
+py_debug_repr!(SeasonFilter, SeasonDemo, SeasonPlayer, SeasonTeam, SeasonSummary);
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn finds_dates_in_names() {
+        assert_eq!(date_in_name("match_2023-04-15_pl_upward.dem").as_deref(), Some("2023-04-15"));
+        assert_eq!(date_in_name("2023_04_15.dem").as_deref(), Some("2023-04-15"));
+        // An impossible date earlier in the name is skipped
+        assert_eq!(date_in_name("1234-99-99 2024-01-02.dem").as_deref(), Some("2024-01-02"));
+        assert_eq!(date_in_name("é2023-04-15").as_deref(), Some("2023-04-15"));
+    }
+
+    #[test]
+    fn ignores_non_dates() {
+        for name in ["20230415.dem", "2023-04_15.dem", "2023-13-01.dem", "2023-04-00.dem", "2023-4-15.dem", "demo.dem"] {
+            assert_eq!(date_in_name(name), None, "{}", name);
+        }
+    }
+
+    #[test]
+    fn utc_dates() {
+        let at = |secs: u64| utc_date(UNIX_EPOCH + Duration::from_secs(secs)).unwrap();
+        assert_eq!(at(0), "1970-01-01");
+        assert_eq!(at(951782400), "2000-02-29");
+        assert_eq!(at(951868800), "2000-03-01");
+        assert_eq!(at(1704067199), "2023-12-31");
+        assert_eq!(at(1704067200), "2024-01-01");
+        assert_eq!(at(1709164800), "2024-02-29");
+        // Not a leap year
+        assert_eq!(at(4107456000), "2100-02-28");
+        assert_eq!(at(4107542400), "2100-03-01");
+    }
+
+    #[test]
+    fn no_utc_date_before_epoch() {
+        assert_eq!(utc_date(UNIX_EPOCH - Duration::from_secs(86400)), None);
+    }
+}
@@ -0,0 +1,168 @@
+//! Alive counts, man advantages, trades and first picks over a round.
+
+use numpy::{IntoPyArray, PyArray1};
+use pyo3::prelude::*;
+use serde::{Serialize, Deserialize};
+
+use crate::types::demo::DemoData;
+use crate::types::events::Kill;
+use crate::types::game::{Round, Team};
+
+/// A stretch of ticks where one team had more players alive than the other.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ManAdvantage {
+    /// The team with more players alive.
+    pub team: Team,
+    pub start_tick: u32,
+    /// The last tick the team was still ahead.
+    pub end_tick: u32,
+    /// The most players the team was ahead by.
+    pub max_advantage: u8,
+}
+
+/// A kill answered by the death of the killer.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Trade {
+    pub kill: Kill,
+    /// The kill of `kill`'s attacker.
+    pub response: Kill,
+    /// Seconds between the kills.
+    pub delay: f32,
+}
+
+/// What happened over a round, tick by tick. The arrays are in tick order
+/// and only have the recorded ticks.
+#[pyclass(module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RoundTimeline {
+    #[pyo3(get)]
+    pub round: Round,
+    pub ticks: Vec<u32>,
+    pub red_alive: Vec<u8>,
+    pub blue_alive: Vec<u8>,
+    /// Every time a team was ahead, in order.
+    #[pyo3(get)]
+    pub advantages: Vec<ManAdvantage>,
+    #[pyo3(get)]
+    pub trades: Vec<Trade>,
+    /// The first kill of the round.
+    #[pyo3(get)]
+    pub first_pick: Option<Kill>,
+}
+
+#[pymethods]
+impl RoundTimeline {
+    #[getter]
+    fn ticks<'py>(&self, py: Python<'py>) -> &'py PyArray1<u32> {
+        self.ticks.clone().into_pyarray(py)
+    }
+
+    /// Living players on RED at each tick.
+    #[getter]
+    fn red_alive<'py>(&self, py: Python<'py>) -> &'py PyArray1<u8> {
+        self.red_alive.clone().into_pyarray(py)
+    }
+
+    /// Living players on BLU at each tick.
+    #[getter]
+    fn blue_alive<'py>(&self, py: Python<'py>) -> &'py PyArray1<u8> {
+        self.blue_alive.clone().into_pyarray(py)
+    }
+}
+
+/// Kills in `round` of a player by someone on the other team, in tick order.
+fn team_kills<'a>(demo: &'a DemoData, round: &Round) -> Vec<&'a Kill> {
+    let mut kills = demo.kills.iter()
+        .filter(|k| round.contains(u32::from(k.tick)))
+        .filter(|k| {
+            let tick = u32::from(k.tick);
+            match (demo.player_at(tick, k.attacker_id), demo.player_at(tick, k.dead_id)) {
+                (Some(attacker), Some(dead)) => attacker.team != dead.team,
+                _ => false
+            }
+        })
+        .collect::<Vec<&Kill>>();
+    kills.sort_by_key(|k| u32::from(k.tick));
+    kills
+}
+
+fn advantages(ticks: &[u32], red_alive: &[u8], blue_alive: &[u8]) -> Vec<ManAdvantage> {
+    let mut advantages = Vec::new();
+    let mut current: Option<ManAdvantage> = None;
+
+    for ((tick, red), blue) in ticks.iter().zip(red_alive).zip(blue_alive) {
+        let ahead = match red.cmp(blue) {
+            std::cmp::Ordering::Greater => Some((Team::Red, red - blue)),
+            std::cmp::Ordering::Less => Some((Team::Blue, blue - red)),
+            std::cmp::Ordering::Equal => None,
+        };
+
+        if let (Some(window), Some((team, by))) = (current.as_mut(), ahead) {
+            if window.team == team {
+                window.end_tick = *tick;
+                window.max_advantage = window.max_advantage.max(by);
+                continue;
+            }
+        }
+
+        advantages.extend(current.take());
+        current = ahead.map(|(team, by)| ManAdvantage {
+            team,
+            start_tick: *tick,
+            end_tick: *tick,
+            max_advantage: by,
+        });
+    }
+
+    advantages.extend(current);
+    advantages
+}
+
+/// The timeline of `round`. A kill is traded if its attacker is killed
+/// within `trade_window` seconds. Kills of teammates and by the world are
+/// not counted as picks or trades.
+pub fn round_timeline(demo: &DemoData, round: &Round, trade_window: f32) -> RoundTimeline {
+    let ticks = demo.sorted_ticks()
+        .into_iter()
+        .filter(|tick| round.contains(*tick))
+        .collect::<Vec<u32>>();
+
+    let alive = |tick: &u32, team: Team| demo.tick_states[tick].players.iter()
+        .filter(|p| p.team == team && p.is_alive())
+        .count() as u8;
+    let red_alive = ticks.iter().map(|t| alive(t, Team::Red)).collect::<Vec<u8>>();
+    let blue_alive = ticks.iter().map(|t| alive(t, Team::Blue)).collect::<Vec<u8>>();
+
+    let kills = team_kills(demo, round);
+    let window = (trade_window * demo.tick_rate()) as u32;
+    let trades = kills.iter()
+        .filter_map(|kill| {
+            let tick = u32::from(kill.tick);
+            kills.iter()
+                .find(|response| response.dead_id == kill.attacker_id
+                    && u32::from(response.tick) > tick
+                    && u32::from(response.tick) <= tick + window)
+                .map(|response| Trade {
+                    kill: (*kill).clone(),
+                    response: (*response).clone(),
+                    delay: (u32::from(response.tick) - tick) as f32 / demo.tick_rate(),
+                })
+        })
+        .collect();
+
+    RoundTimeline {
+        round: round.clone(),
+        advantages: advantages(&ticks, &red_alive, &blue_alive),
+        ticks,
+        red_alive,
+        blue_alive,
+        trades,
+        first_pick: kills.first().map(|k| (*k).clone()),
+    }
+}
+
+py_pickle!(ManAdvantage, Trade, RoundTimeline);
+py_eq!(ManAdvantage, Trade, RoundTimeline);
+py_debug_repr!(ManAdvantage, Trade, RoundTimeline);
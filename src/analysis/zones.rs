@@ -0,0 +1,357 @@
+//! Named areas of a map, and who was in them.
+//!
+//! Zones are read from the `[zones]` section of the config, keyed by map name:
+//!
+//! ```toml
+//! [[zones.cp_process_final]]
+//! name = "mid"
+//! points = [[-1100, -600], [1100, -600], [1100, 600], [-1100, 600]]
+//! max_z = 400
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+
+use numpy::{IntoPyArray, PyArray1, PyArray2};
+use numpy::ndarray::Array2;
+use pyo3::prelude::*;
+use serde::{Serialize, Deserialize};
+
+use crate::config::{Config, DEFAULT_CONFIG_PATH};
+use crate::types::demo::DemoData;
+use crate::types::entities::Player;
+use crate::types::events::Kill;
+use crate::types::game::{Round, Team};
+use crate::types::math::Vector;
+
+/// A named polygon on the map, seen from above.
+#[pyclass(get_all, set_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Zone {
+    pub name: String,
+    /// The corners, as (x, y), in order around the edge.
+    pub points: Vec<(f32, f32)>,
+    /// Only positions at or above this height are in the zone.
+    #[serde(default)]
+    pub min_z: Option<f32>,
+    /// Only positions at or below this height are in the zone.
+    #[serde(default)]
+    pub max_z: Option<f32>,
+}
+
+#[pymethods]
+impl Zone {
+    #[new]
+    #[pyo3(signature = (name, points, min_z=None, max_z=None))]
+    fn new(name: String, points: Vec<(f32, f32)>, min_z: Option<f32>, max_z: Option<f32>) -> Self {
+        Zone { name, points, min_z, max_z }
+    }
+
+    /// If `position` is inside the polygon and within the height limits.
+    pub fn contains(&self, position: Vector) -> bool {
+        if self.min_z.is_some_and(|z| position.z < z) || self.max_z.is_some_and(|z| position.z > z) {
+            return false;
+        }
+
+        // Even-odd rule: count the edges a ray to +X crosses
+        let (x, y) = (position.x, position.y);
+        let n = self.points.len();
+        let mut inside = false;
+        for i in 0..n {
+            let (xi, yi) = self.points[i];
+            let (xj, yj) = self.points[(i + n - 1) % n];
+            if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+
+    /// The average of the corners, for placing labels.
+    pub fn center(&self) -> Vector {
+        let n = self.points.len().max(1) as f32;
+        let (x, y) = self.points.iter().fold((0.0, 0.0), |(x, y), p| (x + p.0, y + p.1));
+        Vector::new(x / n, y / n, 0.0)
+    }
+}
+
+/// The zones of every map in the `[zones]` section of the config at `path`,
+/// or `demo_analysis.toml`, keyed by map name. A missing file has no zones.
+#[pyfunction]
+#[pyo3(signature = (path=None))]
+pub fn load_zones(path: Option<PathBuf>) -> PyResult<HashMap<String, Vec<Zone>>> {
+    let path = path.unwrap_or_else(|| PathBuf::from(DEFAULT_CONFIG_PATH));
+    Ok(Config::load(&path)?.zones)
+}
+
+/// The index of the first of `zones` containing `position`, if any.
+/// Where zones overlap, the one listed first wins.
+pub fn zone_index(zones: &[Zone], position: Vector) -> Option<usize> {
+    zones.iter().position(|zone| zone.contains(position))
+}
+
+/// Seconds a player spent alive in a zone.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ZoneTime {
+    pub user_id: u16,
+    pub name: String,
+    pub team: Team,
+    pub zone: String,
+    pub seconds: f32,
+}
+
+/// Seconds each team held a zone, by having more living players in it.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ZoneControl {
+    pub zone: String,
+    pub red_time: f32,
+    pub blue_time: f32,
+    /// Seconds with the same number of players from each team in the zone,
+    /// and at least one.
+    pub contested_time: f32,
+}
+
+/// Where the players in a kill were.
+#[pyclass(get_all, module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KillZone {
+    pub kill: Kill,
+    pub attacker_zone: Option<String>,
+    pub victim_zone: Option<String>,
+}
+
+/// Who was in which zone over a demo or round. Made by `DemoData.zone_occupancy`.
+#[pyclass(module = "tf2dal.analysis")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ZoneReport {
+    /// The zone names, in the order given. Zone indices refer to these.
+    #[pyo3(get)]
+    pub zones: Vec<String>,
+    pub ticks: Vec<u32>,
+    pub user_ids: Vec<u16>,
+    /// (ticks, players), row by row.
+    pub player_zones: Vec<i16>,
+    /// (ticks, zones) of Team values, row by row.
+    pub control: Vec<u8>,
+    /// Ordered by user ID, then zone.
+    #[pyo3(get)]
+    pub time_in_zone: Vec<ZoneTime>,
+    /// In the same order as `zones`.
+    #[pyo3(get)]
+    pub control_time: Vec<ZoneControl>,
+    /// In tick order.
+    #[pyo3(get)]
+    pub kills: Vec<KillZone>,
+}
+
+#[pymethods]
+impl ZoneReport {
+    /// The tick of each row of the arrays, ascending.
+    #[getter]
+    fn ticks<'py>(&self, py: Python<'py>) -> &'py PyArray1<u32> {
+        self.ticks.clone().into_pyarray(py)
+    }
+
+    /// The user ID of each column of `player_zones`, ascending.
+    #[getter]
+    fn user_ids<'py>(&self, py: Python<'py>) -> &'py PyArray1<u16> {
+        self.user_ids.clone().into_pyarray(py)
+    }
+
+    /// (ticks, players) array of the zone index each living player was in,
+    /// or -1 if they were dead, missing, or in no zone.
+    #[getter]
+    fn player_zones<'py>(&self, py: Python<'py>) -> &'py PyArray2<i16> {
+        Array2::from_shape_vec((self.ticks.len(), self.user_ids.len()), self.player_zones.clone())
+            .unwrap()
+            .into_pyarray(py)
+    }
+
+    /// (ticks, zones) array of the Team value holding each zone, which is
+    /// `Team.Other` if it is empty or contested.
+    #[getter]
+    fn control<'py>(&self, py: Python<'py>) -> &'py PyArray2<u8> {
+        Array2::from_shape_vec((self.ticks.len(), self.zones.len()), self.control.clone())
+            .unwrap()
+            .into_pyarray(py)
+    }
+}
+
+impl ZoneReport {
+    /// The team holding each zone on `tick`, or on the last recorded tick
+    /// before it. Empty if there is no such tick.
+    pub fn control_at(&self, tick: u32) -> Vec<Team> {
+        let row = self.ticks.partition_point(|t| *t <= tick);
+        if row == 0 {
+            return Vec::new();
+        }
+        let zones = self.zones.len();
+        self.control[(row - 1) * zones..row * zones].iter().map(|team| Team::new(*team)).collect()
+    }
+}
+
+/// Where everyone was in `zones` over every tick of `demo`, or only the
+/// ticks of `round`.
+pub fn zone_report(demo: &DemoData, zones: &[Zone], round: Option<&Round>) -> ZoneReport {
+    let in_round = |tick: u32| round.map_or(true, |r| r.contains(tick));
+    let dt = 1.0 / demo.tick_rate();
+    let zone_of = |player: &Player| zone_index(zones, player.position);
+
+    let ticks = demo.sorted_ticks().into_iter().filter(|t| in_round(*t)).collect::<Vec<u32>>();
+    let user_ids = demo.player_user_ids();
+    let column = user_ids.iter().enumerate().map(|(i, id)| (*id, i)).collect::<HashMap<u16, usize>>();
+
+    let mut player_zones = vec![-1; ticks.len() * user_ids.len()];
+    let mut control = Vec::with_capacity(ticks.len() * zones.len());
+    let mut times = BTreeMap::<(u16, usize), ZoneTime>::new();
+    let mut control_time = zones.iter()
+        .map(|zone| ZoneControl {zone: zone.name.clone(), ..Default::default()})
+        .collect::<Vec<_>>();
+
+    for (row, tick) in ticks.iter().enumerate() {
+        // Living players of each team in each zone
+        let mut counts = vec![(0u8, 0u8); zones.len()];
+
+        for player in &demo.tick_states[tick].players {
+            let (Some(info), true) = (&player.info, player.is_alive()) else {
+                continue;
+            };
+            let Some(zone) = zone_of(player) else {
+                continue;
+            };
+
+            if let Some(col) = column.get(&info.user_id) {
+                player_zones[row * user_ids.len() + col] = zone as i16;
+            }
+
+            let time = times.entry((info.user_id, zone)).or_default();
+            time.user_id = info.user_id;
+            time.name = info.name.clone();
+            time.team = player.team;
+            time.zone = zones[zone].name.clone();
+            time.seconds += dt;
+
+            match player.team {
+                Team::Red => counts[zone].0 += 1,
+                Team::Blue => counts[zone].1 += 1,
+                _ => {}
+            }
+        }
+
+        for ((red, blue), held) in counts.into_iter().zip(&mut control_time) {
+            let team = match red.cmp(&blue) {
+                std::cmp::Ordering::Greater => Team::Red,
+                std::cmp::Ordering::Less => Team::Blue,
+                std::cmp::Ordering::Equal => Team::Other,
+            };
+            match team {
+                Team::Red => held.red_time += dt,
+                Team::Blue => held.blue_time += dt,
+                _ if red > 0 => held.contested_time += dt,
+                _ => {}
+            }
+            control.push(team.into());
+        }
+    }
+
+    let mut kills = demo.kills.iter()
+        .filter(|k| in_round(u32::from(k.tick)))
+        .map(|kill| {
+            let tick = u32::from(kill.tick);
+            let zone_name = |user_id: u16| demo.player_at(tick, user_id)
+                .and_then(|p| zone_of(p))
+                .map(|zone| zones[zone].name.clone());
+            KillZone {
+                kill: kill.clone(),
+                attacker_zone: zone_name(kill.attacker_id),
+                victim_zone: zone_name(kill.dead_id),
+            }
+        })
+        .collect::<Vec<_>>();
+    kills.sort_by_key(|k| u32::from(k.kill.tick));
+
+    ZoneReport {
+        zones: zones.iter().map(|zone| zone.name.clone()).collect(),
+        ticks,
+        user_ids,
+        player_zones,
+        control,
+        time_in_zone: times.into_values().collect(),
+        control_time,
+        kills,
+    }
+}
+
+py_pickle!(Zone, ZoneTime, ZoneControl, KillZone, ZoneReport);
+py_eq!(Zone, ZoneTime, ZoneControl, KillZone, ZoneReport);
+py_debug_repr!(Zone, ZoneTime, ZoneControl, KillZone, ZoneReport);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn zone(points: &[(f32, f32)]) -> Zone {
+        Zone { name: "zone".to_string(), points: points.to_vec(), ..Default::default() }
+    }
+
+    #[test]
+    fn square() {
+        let square = zone(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]);
+        assert!(square.contains(Vector::new(5.0, 5.0, 0.0)));
+        assert!(!square.contains(Vector::new(15.0, 5.0, 0.0)));
+        assert!(!square.contains(Vector::new(-5.0, 5.0, 0.0)));
+        assert!(!square.contains(Vector::new(5.0, 15.0, 0.0)));
+    }
+
+    #[test]
+    fn height_limits_are_inclusive() {
+        let square = Zone {
+            min_z: Some(0.0),
+            max_z: Some(100.0),
+            ..zone(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)])
+        };
+        assert!(!square.contains(Vector::new(5.0, 5.0, -1.0)));
+        assert!(square.contains(Vector::new(5.0, 5.0, 0.0)));
+        assert!(square.contains(Vector::new(5.0, 5.0, 100.0)));
+        assert!(!square.contains(Vector::new(5.0, 5.0, 101.0)));
+    }
+
+    #[test]
+    fn concave() {
+        // A U, open at the top between x = 10 and x = 20
+        let u = zone(&[(0.0, 0.0), (30.0, 0.0), (30.0, 30.0), (20.0, 30.0),
+                       (20.0, 10.0), (10.0, 10.0), (10.0, 30.0), (0.0, 30.0)]);
+        assert!(u.contains(Vector::new(5.0, 20.0, 0.0)));
+        assert!(u.contains(Vector::new(25.0, 20.0, 0.0)));
+        assert!(u.contains(Vector::new(15.0, 5.0, 0.0)));
+        assert!(!u.contains(Vector::new(15.0, 20.0, 0.0)));
+    }
+
+    #[test]
+    fn ray_through_vertices() {
+        // The ray from a point level with the side corners passes through both
+        let diamond = zone(&[(0.0, -10.0), (10.0, 0.0), (0.0, 10.0), (-10.0, 0.0)]);
+        assert!(diamond.contains(Vector::new(0.0, 0.0, 0.0)));
+        assert!(!diamond.contains(Vector::new(-20.0, 0.0, 0.0)));
+        assert!(!diamond.contains(Vector::new(20.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn too_few_points() {
+        assert!(!zone(&[]).contains(Vector::new(0.0, 0.0, 0.0)));
+        assert!(!zone(&[(0.0, 0.0), (10.0, 10.0)]).contains(Vector::new(5.0, 5.0, 0.0)));
+    }
+
+    #[test]
+    fn first_overlapping_zone_wins() {
+        let zones = [
+            zone(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)]),
+            zone(&[(-20.0, -20.0), (20.0, -20.0), (20.0, 20.0), (-20.0, 20.0)]),
+        ];
+        assert_eq!(zone_index(&zones, Vector::new(5.0, 5.0, 0.0)), Some(0));
+        assert_eq!(zone_index(&zones, Vector::new(-5.0, 5.0, 0.0)), Some(1));
+        assert_eq!(zone_index(&zones, Vector::new(50.0, 5.0, 0.0)), None);
+    }
+}
@@ -0,0 +1,255 @@
+//! What an analysis hands back to Rust.
+//!
+//! An analysis returns either `None` or a dict with any of these keys:
+//!
+//! ```python
+//! {
+//!     "metrics": {"kills": 12, "damage_per_minute": 243.5},
+//!     "tables": {"kills": kills_df, "groups": {"columns": ["tick", "size"], "rows": [[100, 3]]}},
+//!     "annotations": [{"tick": 5400, "text": "first pick"}],
+//!     "markers": [{"x": 120.0, "y": -340.0, "label": "fight", "color": (255, 0, 0), "tick": 5400}],
+//! }
+//! ```
+//!
+//! Tables can be pandas DataFrames or `columns`/`rows` dicts. Table cells are
+//! kept as the text Python gives for them, since they are only displayed or
+//! written to CSV.
+
+use std::fmt::Write as _;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyTypeError;
+use pyo3::types::{PyDict, PyString};
+
+use crate::types::math::Vector;
+
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisResult {
+    /// Named numbers, in the order Python gave them.
+    pub metrics: Vec<(String, f64)>,
+    pub tables: Vec<Table>,
+    pub annotations: Vec<Annotation>,
+    pub markers: Vec<MapMarker>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Table {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+/// A note attached to a tick.
+#[derive(Debug, Clone, Default)]
+pub struct Annotation {
+    pub tick: u32,
+    pub text: String,
+}
+
+/// A point to draw on the map.
+#[derive(Debug, Clone, Default)]
+pub struct MapMarker {
+    pub position: Vector,
+    pub label: String,
+    pub color: [u8; 3],
+    /// Only shown at this tick, if given. Otherwise always shown.
+    pub tick: Option<u32>,
+}
+
+impl AnalysisResult {
+    pub fn is_empty(&self) -> bool {
+        self.metrics.is_empty() && self.tables.is_empty()
+            && self.annotations.is_empty() && self.markers.is_empty()
+    }
+
+    /// Markers shown at `tick`.
+    pub fn markers_at(&self, tick: u32) -> impl Iterator<Item = &MapMarker> {
+        self.markers.iter().filter(move |m| m.tick.map_or(true, |t| t == tick))
+    }
+
+    /// Prints everything but the tables, which are usually too big for a terminal.
+    pub fn print_summary(&self, name: &str) {
+        println!("== {} ==", name);
+        for (metric, value) in &self.metrics {
+            println!("  {}: {}", metric, value);
+        }
+        for table in &self.tables {
+            println!("  table {}: {} rows", table.name, table.rows.len());
+        }
+        for annotation in &self.annotations {
+            println!("  [{}] {}", annotation.tick, annotation.text);
+        }
+        if !self.markers.is_empty() {
+            println!("  {} map markers", self.markers.len());
+        }
+    }
+
+    /// Prints each table with its columns lined up.
+    pub fn print_tables(&self) {
+        for table in &self.tables {
+            let widths = table.columns.iter().enumerate()
+                .map(|(i, column)| table.rows.iter()
+                    .filter_map(|row| row.get(i))
+                    .map(|cell| cell.chars().count())
+                    .fold(column.chars().count(), usize::max))
+                .collect::<Vec<usize>>();
+            let line = |cells: &[String]| cells.iter().zip(&widths)
+                .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+                .collect::<Vec<_>>()
+                .join("  ");
+
+            println!("-- {} --", table.name);
+            println!("{}", line(&table.columns));
+            for row in &table.rows {
+                println!("{}", line(row));
+            }
+        }
+    }
+
+    /// Writes each table to `<dir>/<prefix>_<table>.csv`, and the metrics to
+    /// `<dir>/<prefix>_metrics.csv`. Returns the files written. Slashes and
+    /// dots in the names are replaced, so nothing is written outside `dir`.
+    pub fn export_csv(&self, dir: &Path, prefix: &str) -> io::Result<Vec<PathBuf>> {
+        std::fs::create_dir_all(dir)?;
+        let mut written = Vec::new();
+
+        if !self.metrics.is_empty() {
+            let path = dir.join(csv_file_name(&format!("{}_metrics", prefix)));
+            let rows = self.metrics.iter()
+                .map(|(name, value)| vec![name.clone(), value.to_string()])
+                .collect::<Vec<_>>();
+            std::fs::write(&path, to_csv(&["metric".to_string(), "value".to_string()], &rows))?;
+            written.push(path);
+        }
+
+        for table in &self.tables {
+            let path = dir.join(csv_file_name(&format!("{}_{}", prefix, table.name)));
+            std::fs::write(&path, to_csv(&table.columns, &table.rows))?;
+            written.push(path);
+        }
+
+        Ok(written)
+    }
+}
+
+fn csv_file_name(stem: &str) -> String {
+    format!("{}.csv", stem.replace(['/', '\\', '.'], "_"))
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn to_csv(columns: &[String], rows: &[Vec<String>]) -> String {
+    let mut out = String::new();
+    for row in std::iter::once(columns).chain(rows.iter().map(Vec::as_slice)) {
+        let line = row.iter().map(|f| csv_field(f)).collect::<Vec<_>>().join(",");
+        let _ = writeln!(out, "{}", line);
+    }
+    out
+}
+
+fn cell_text(cell: &PyAny) -> PyResult<String> {
+    Ok(cell.str()?.to_string())
+}
+
+fn extract_table(name: String, ob: &PyAny) -> PyResult<Table> {
+    // pandas DataFrame
+    if ob.hasattr("columns")? && ob.hasattr("astype")? {
+        let columns = ob.getattr("columns")?
+            .iter()?
+            .map(|c| c.and_then(cell_text))
+            .collect::<PyResult<Vec<String>>>()?;
+        let rows = ob.call_method1("astype", (ob.py().get_type::<PyString>(),))?
+            .getattr("values")?
+            .call_method0("tolist")?
+            .extract()?;
+        return Ok(Table { name, columns, rows });
+    }
+
+    let dict: &PyDict = ob.downcast()?;
+    let columns = match dict.get_item("columns") {
+        Some(columns) => columns.iter()?.map(|c| c.and_then(cell_text)).collect::<PyResult<_>>()?,
+        None => Vec::new()
+    };
+    let mut rows = Vec::new();
+    if let Some(py_rows) = dict.get_item("rows") {
+        for row in py_rows.iter()? {
+            rows.push(row?.iter()?.map(|c| c.and_then(cell_text)).collect::<PyResult<_>>()?);
+        }
+    }
+
+    Ok(Table { name, columns, rows })
+}
+
+fn extract_annotation(ob: &PyAny) -> PyResult<Annotation> {
+    let dict: &PyDict = ob.downcast()?;
+    Ok(Annotation {
+        tick: required(dict, "tick")?.extract()?,
+        text: required(dict, "text")?.extract()?,
+    })
+}
+
+fn extract_marker(ob: &PyAny) -> PyResult<MapMarker> {
+    let dict: &PyDict = ob.downcast()?;
+    Ok(MapMarker {
+        position: Vector::new(
+            required(dict, "x")?.extract()?,
+            required(dict, "y")?.extract()?,
+            dict.get_item("z").map_or(Ok(0.0), |z| z.extract())?,
+        ),
+        label: dict.get_item("label").map_or(Ok(String::new()), |l| l.extract())?,
+        color: dict.get_item("color").map_or(Ok([255, 255, 255]), |c| c.extract())?,
+        tick: dict.get_item("tick").map_or(Ok(None), |t| t.extract())?,
+    })
+}
+
+fn required<'py>(dict: &'py PyDict, key: &str) -> PyResult<&'py PyAny> {
+    dict.get_item(key).ok_or_else(|| PyTypeError::new_err(format!("missing key \"{}\"", key)))
+}
+
+impl<'source> FromPyObject<'source> for AnalysisResult {
+    fn extract(ob: &'source PyAny) -> PyResult<Self> {
+        let mut result = AnalysisResult::default();
+        if ob.is_none() {
+            return Ok(result);
+        }
+
+        let dict: &PyDict = ob.downcast()
+            .map_err(|_| PyTypeError::new_err("an analysis must return None or a dict"))?;
+
+        if let Some(metrics) = dict.get_item("metrics") {
+            let metrics: &PyDict = metrics.downcast()?;
+            for (name, value) in metrics {
+                result.metrics.push((name.extract()?, value.extract()?));
+            }
+        }
+
+        if let Some(tables) = dict.get_item("tables") {
+            let tables: &PyDict = tables.downcast()?;
+            for (name, table) in tables {
+                result.tables.push(extract_table(name.extract()?, table)?);
+            }
+        }
+
+        if let Some(annotations) = dict.get_item("annotations") {
+            for annotation in annotations.iter()? {
+                result.annotations.push(extract_annotation(annotation?)?);
+            }
+        }
+
+        if let Some(markers) = dict.get_item("markers") {
+            for marker in markers.iter()? {
+                result.markers.push(extract_marker(marker?)?);
+            }
+        }
+
+        Ok(result)
+    }
+}
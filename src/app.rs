@@ -3,24 +3,33 @@ use crate::parsing::internals::GatherSeen;
 //use crate::types::demo::{DemoData, TickData};
 use crate::parsing as par;
 use crate::datatransmit as dt;
+use crate::config::{Config, HeatmapConfig, HeatmapSplit};
+use crate::analysis::heatmap::{self, HeatmapFilter};
+use crate::analysis::season::{Season, SeasonFilter};
+use crate::analysis::rating::{self, RatingModel};
 
-use std::path::PathBuf;
+use std::collections::{HashMap, VecDeque};
+use std::path::{Path, PathBuf};
 use std::io;
-use itertools::enumerate;
+use std::thread;
+use std::time::{Duration, UNIX_EPOCH};
 use itertools::Itertools;
 use tf_demo_parser::MessageType;
 
 use log::{info, error};
+use serde::{Serialize, Deserialize};
 
 use crate::types::demo::DemoData;
+use crate::types::game::{Class, Team};
+use crate::types::identity::SteamId;
 
-fn parse_singlethread(fnames: Vec<PathBuf>) -> io::Result<Vec<(PathBuf, DemoData)>> {
+fn parse_singlethread(fnames: Vec<PathBuf>, options: &par::ParseOptions) -> io::Result<Vec<(PathBuf, DemoData)>> {
     info!("Single threaded parse!");
     let mut parse_results = Vec::new();
     for fname in fnames {
         let mut max_ticks = 0;
         let mut last_perc_printed = 0;
-        par::parse_demo(fname.clone(), |prog_rep| {
+        par::parse_demo(fname.clone(), options, |prog_rep| {
             match prog_rep {
                 par::ParseProgressReport::Info(max_tick) => {
                     info!("({:#?}) Max Ticks: {}", fname.file_name().unwrap(), max_tick);
@@ -30,10 +39,12 @@ fn parse_singlethread(fnames: Vec<PathBuf>) -> io::Result<Vec<(PathBuf, DemoData
                     error!("({:#?}) Error: {:?}", fname.file_name().unwrap(), err);
                 },
                 par::ParseProgressReport::Working(tick) => {
-                    let perc = (tick * 100) / max_ticks;
-                    if perc > last_perc_printed && perc % 5 == 0 {
-                        last_perc_printed = perc;
-                        info!("({:#?}) {}%", fname.file_name().unwrap(), perc);
+                    // Broken demos can have a header with no ticks
+                    if let Some(perc) = (tick * 100).checked_div(max_ticks) {
+                        if perc > last_perc_printed && perc % 5 == 0 {
+                            last_perc_printed = perc;
+                            info!("({:#?}) {}%", fname.file_name().unwrap(), perc);
+                        }
                     }
                 },
                 par::ParseProgressReport::Done(data, _) => {
@@ -49,53 +60,228 @@ fn parse_singlethread(fnames: Vec<PathBuf>) -> io::Result<Vec<(PathBuf, DemoData
     Ok(parse_results)
 }
 
-pub fn do_parses(fnames: Vec<PathBuf>, multithread: bool) -> io::Result<Vec<(PathBuf, DemoData)>> {
+/// Parses a single demo on this thread, calling `hook` as it goes.
+pub fn parse_with_hook(fname: PathBuf, options: &par::ParseOptions, hook: par::TickHook<'_>) -> Result<DemoData, par::ParseWorkerError> {
+    let mut result = Err(par::ParseWorkerError::SendError);
+    par::parse_demo_with_hook(fname, options, Some(hook), |prog_rep| {
+        match prog_rep {
+            par::ParseProgressReport::Done(data, _) => result = Ok(data),
+            par::ParseProgressReport::Error(err) => result = Err(err),
+            _ => {}
+        }
+
+        Ok(())
+    });
+
+    result
+}
+
+pub fn do_parses(fnames: Vec<PathBuf>, multithread: bool, options: &par::ParseOptions) -> io::Result<Vec<(PathBuf, DemoData)>> {
     if !multithread {
-        return parse_singlethread(fnames);
+        return parse_singlethread(fnames, options);
     }
 
+    let max_threads = fnames.len();
+    parse_pooled(fnames, max_threads, options)
+}
+
+/// Parses every file on its own thread, with at most `max_threads` parses
+/// running at once. The results are in the same order as `fnames`; files that
+/// failed to parse are left out.
+pub fn parse_pooled(fnames: Vec<PathBuf>, max_threads: usize, options: &par::ParseOptions) -> io::Result<Vec<(PathBuf, DemoData)>> {
+    let parsed = parse_each(fnames.clone(), max_threads, options)?;
+    Ok(fnames.into_iter().zip(parsed).filter_map(|(fname, data)| Some((fname, data?))).collect())
+}
+
+/// Like `parse_pooled`, but with the result of every file in `fnames` at its
+/// index, None if it failed to parse. The same file can be given more than once.
+pub fn parse_each(fnames: Vec<PathBuf>, max_threads: usize, options: &par::ParseOptions) -> io::Result<Vec<Option<DemoData>>> {
+    let max_threads = max_threads.max(1);
+    let mut parse_results = fnames.iter().map(|_| None).collect::<Vec<Option<DemoData>>>();
+    let mut pending = fnames.into_iter().enumerate().collect::<VecDeque<(usize, PathBuf)>>();
+
+    // (index in fnames, file name, worker, max ticks, last percent reported)
     let mut workers = Vec::new();
-    for f in fnames {
-        workers.push((f.clone(), par::ParseWorker::new(f)?, u32::MAX, 0));
+    while !pending.is_empty() || !workers.is_empty() {
+        while workers.len() < max_threads {
+            match pending.pop_front() {
+                Some((index, fname)) => {
+                    let worker = par::ParseWorker::with_options(fname.clone(), options.clone())?;
+                    workers.push((index, fname, worker, u32::MAX, 0));
+                },
+                None => break
+            }
+        }
+
+        // Keep only the workers that are still going
+        workers.retain_mut(|(index, fname, worker, max_ticks, last_report)| {
+            match worker.get_most_recent() {
+                Some(par::ParseProgressReport::Info(max_tick)) => {
+                    info!("({:#?}) Max Ticks: {}", fname.file_name().unwrap(), max_tick);
+                    *max_ticks = max_tick;
+                    true
+                },
+                Some(par::ParseProgressReport::Error(err)) => {
+                    error!("({:#?}) Error: {:?}", fname.file_name().unwrap(), err);
+                    false
+                },
+                Some(par::ParseProgressReport::Working(tick)) => {
+                    // Broken demos can have a header with no ticks
+                    if let Some(perc_done) = (tick * 100).checked_div(*max_ticks) {
+                        if perc_done % 10 == 0 && perc_done > *last_report {
+                            info!("({:#?}) {}%", fname.file_name().unwrap(), perc_done);
+                            *last_report = perc_done;
+                        }
+                    }
+                    true
+                },
+                Some(par::ParseProgressReport::Done(data, _)) => {
+                    parse_results[*index] = Some(data);
+                    false
+                },
+                _ => true
+            }
+        });
+
+        thread::sleep(Duration::from_millis(5));
     }
 
-    let mut parse_results = Vec::new();
-    let mut done_count = 0;
-    let mut last_report = vec![0; workers.len()];
-    while done_count < workers.len() {
-        for (fname, worker, max_ticks, perc_done) in &mut workers {
-            if let Some(prog_rep) = worker.get_most_recent() {
-                match prog_rep {
-                    par::ParseProgressReport::Info(max_tick) => {
-                        info!("({:#?}) Max Ticks: {}", fname.file_name().unwrap(), max_tick);
-                        *max_ticks = max_tick;
-                    },
-                    par::ParseProgressReport::Error(err) => {
-                        error!("({:#?}) Error: {:?}", fname.file_name().unwrap(), err);
-                        *perc_done = 100;
-                        done_count += 1;
-                    },
-                    par::ParseProgressReport::Working(tick) => {
-                        *perc_done = (tick * 100) / *max_ticks;
-                    },
-                    par::ParseProgressReport::Done(data, _) => {
-                        parse_results.push((fname.clone(), data));
-                        done_count += 1;
-                    },
-                    _ => {}
-                }
+    Ok(parse_results)
+}
+
+/// The extension of saved parses, which `parse_cached` reads in place of demos.
+pub const CACHE_EXTENSION: &str = "tf2dal";
+/// The extension of the parses `parse_cached` keeps in its cache directory.
+/// They start with a `CacheKey`, so they can't be read as saved parses.
+const CACHE_ENTRY_EXTENSION: &str = "tf2dal-cache";
+
+/// Which demo a cached parse is of, and its size and modified time when it
+/// was parsed. A cached parse is only used if the demo still matches.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct CacheKey {
+    /// Canonical, so the same demo reached through different paths is cached once.
+    path: PathBuf,
+    size: u64,
+    /// Nanoseconds since the Unix epoch.
+    modified: u128,
+}
+
+impl CacheKey {
+    fn of(fname: &Path) -> io::Result<Self> {
+        let path = std::fs::canonicalize(fname)?;
+        let metadata = std::fs::metadata(&path)?;
+        let modified = metadata.modified()?.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_nanos());
+        Ok(CacheKey { path, size: metadata.len(), modified })
+    }
+
+    /// Where the parse is cached in `cache_dir`: the demo's name, then a hash
+    /// of its full path, so demos with the same name in different
+    /// directories don't share an entry.
+    fn cache_path(&self, cache_dir: &Path) -> PathBuf {
+        // FNV-1a, as the std hashers may change between Rust versions
+        let hash = self.path.to_string_lossy().bytes()
+            .fold(0xcbf2_9ce4_8422_2325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3));
+        let stem = self.path.file_stem().unwrap_or_default().to_string_lossy();
+        cache_dir.join(format!("{}-{:016x}.{}", stem, hash, CACHE_ENTRY_EXTENSION))
+    }
+
+    /// Reads the parse cached at `cache`, if it is of this demo as it is now.
+    fn load(&self, cache: &Path) -> io::Result<DemoData> {
+        let invalid = |err: bincode::Error| io::Error::new(io::ErrorKind::InvalidData, err);
+        let mut file = io::BufReader::new(std::fs::File::open(cache)?);
+        let saved: CacheKey = bincode::deserialize_from(&mut file).map_err(invalid)?;
+        if saved != *self {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, "the demo changed since it was cached"));
+        }
+        bincode::deserialize_from(file).map_err(invalid)
+    }
+
+    fn save(&self, cache: &Path, data: &DemoData) -> io::Result<()> {
+        let mut file = io::BufWriter::new(std::fs::File::create(cache)?);
+        bincode::serialize_into(&mut file, self)
+            .and_then(|_| bincode::serialize_into(&mut file, data))
+            .map_err(|err| io::Error::new(io::ErrorKind::Other, err))
+    }
+}
+
+/// Like `parse_pooled`, but files ending in `.tf2dal` are read as saved
+/// parses, and with a `cache_dir`, demos with the same path, size and
+/// modified time as when they were cached there are read from it. New parses are saved to `cache_dir`.
+/// Caches that can't be read, like ones from older versions, are parsed again.
+pub fn parse_cached(fnames: Vec<PathBuf>, max_threads: usize, options: &par::ParseOptions, cache_dir: Option<&Path>) -> io::Result<Vec<(PathBuf, DemoData)>> {
+    let mut results = fnames.iter().map(|_| None).collect::<Vec<Option<(PathBuf, DemoData)>>>();
+    let mut to_parse = Vec::new();
+    for (index, fname) in fnames.into_iter().enumerate() {
+        if fname.extension().is_some_and(|ext| ext == CACHE_EXTENSION) {
+            match DemoData::load(&fname) {
+                Ok(data) => results[index] = Some((fname, data)),
+                Err(err) => error!("({:#?}) Could not read saved parse: {}", fname.file_name().unwrap(), err),
             }
+            continue;
         }
-    
-        for (i, (fname, _, _, perc_done)) in enumerate(&workers) {
-            if perc_done % 10 == 0 && perc_done > last_report.get(i).unwrap(){
-                info!("({:#?}) {}%", fname.file_name().unwrap(), perc_done);
-                last_report[i] = *perc_done;
+
+        let key = cache_dir.and_then(|_| CacheKey::of(&fname).ok());
+        let cached = cache_dir.zip(key.as_ref()).and_then(|(dir, key)| {
+            let cache = key.cache_path(dir);
+            key.load(&cache)
+                .map_err(|err| if err.kind() != io::ErrorKind::NotFound {
+                    info!("({:#?}) Parsing again: {}", cache, err)
+                })
+                .ok()
+        });
+        match cached {
+            Some(data) => results[index] = Some((fname, data)),
+            None => to_parse.push((index, fname, key)),
+        }
+    }
+
+    let parsed = parse_each(to_parse.iter().map(|(_, fname, _)| fname.clone()).collect(), max_threads, options)?;
+    for ((index, fname, key), data) in to_parse.into_iter().zip(parsed) {
+        let Some(data) = data else {
+            continue;
+        };
+        if let (Some(dir), Some(key)) = (cache_dir, key) {
+            let saved = std::fs::create_dir_all(dir).and_then(|_| key.save(&key.cache_path(dir), &data));
+            if let Err(err) = saved {
+                error!("({:#?}) Could not cache parse: {}", fname.file_name().unwrap(), err);
             }
         }
+        results[index] = Some((fname, data));
     }
 
-    Ok(parse_results)
+    Ok(results.into_iter().flatten().collect())
+}
+
+/// Parses `fnames` without tick states into a season, through the parse
+/// cache in `cache_dir` if given.
+pub fn load_season(fnames: Vec<PathBuf>, max_threads: usize, cache_dir: Option<&Path>,
+                   teams: HashMap<String, Vec<SteamId>>, our_team: Option<String>) -> io::Result<Season> {
+    let mut options = par::ParseOptions::default();
+    options.discard_ticks = true;
+    let parsed = parse_cached(fnames, max_threads, &options, cache_dir)?;
+    let demos = parsed.into_iter().map(|(_, data)| data).collect();
+    Ok(Season::new(demos, teams, our_team))
+}
+
+/// Prints the summary of every demo in `fnames` that passes `filter`, and
+/// writes it to CSV files if the config has an export directory.
+pub fn run_season(fnames: Vec<PathBuf>, filter: &SeasonFilter, config: &Config) -> io::Result<()> {
+    info!("# Season of {} files", fnames.len());
+    let threads = thread::available_parallelism().map_or(4, |n| n.get());
+    let season = load_season(fnames, threads, config.season.cache_dir.as_deref(),
+        config.season.rosters(), config.season.team.clone())?;
+    let mut result = season.summary(filter).to_result();
+    let ratings = rating::season_ratings(&season, &RatingModel::default(), filter);
+    result.tables.extend(rating::ratings_to_result(&ratings).tables);
+
+    result.print_summary("Season");
+    result.print_tables();
+    if let Some(dir) = &config.analysis.export_dir {
+        let files = result.export_csv(dir, "season")?;
+        info!("| Wrote {:?}", files);
+    }
+
+    Ok(())
 }
 
 use io::Write;
@@ -114,22 +300,87 @@ fn pause() {
     let _ = stdin.read(&mut [0u8]).unwrap();
 }
 
+/// Writes a heatmap of `demo` for each team, class or player, as set in
+/// `config`, to `dir`. Heatmaps with nothing on them are skipped.
+fn write_heatmaps(fname: &Path, demo: &DemoData, config: &HeatmapConfig, dir: &Path) -> io::Result<Vec<PathBuf>> {
+    std::fs::create_dir_all(dir)?;
+
+    let groups = match config.by {
+        HeatmapSplit::Team => [Team::Red, Team::Blue].iter()
+            .map(|team| (team.name().to_lowercase(), HeatmapFilter {team: Some(*team), ..Default::default()}))
+            .collect::<Vec<_>>(),
+        HeatmapSplit::Class => Class::ALL[1..].iter()
+            .map(|class| (class.name().to_lowercase(), HeatmapFilter {player_class: Some(*class), ..Default::default()}))
+            .collect(),
+        HeatmapSplit::Player => demo.player_user_ids().into_iter()
+            .map(|user_id| (user_id.to_string(), HeatmapFilter {user_id: Some(user_id), ..Default::default()}))
+            .collect(),
+    };
+
+    let stem = fname.file_stem().unwrap_or_default().to_string_lossy();
+    let mut written = Vec::new();
+    for (group, filter) in groups {
+        let map = heatmap::heatmap(demo, config.kind, &filter, config.cell_size);
+        if map.max() <= 0.0 {
+            continue;
+        }
+        let path = dir.join(format!("{}_{}_{}.{}",
+            stem, config.kind.name().to_lowercase(), group, config.format.extension()));
+        map.save(&path)?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
 #[allow(dead_code)]
-pub fn run(fnames: Vec<PathBuf>, do_analysis: bool) -> io::Result<()> {
+pub fn run(fnames: Vec<PathBuf>, do_analysis: bool, config: &Config) -> io::Result<()> {
     info!("###############################");
     info!("# Beginning Parse: {:?}", fnames);
 
-    if do_analysis {
-        info!("# (with analysis!)");
+    if do_analysis || config.heatmap.dir.is_some() {
+        let parse_results = do_parses(fnames, true, &par::ParseOptions::default())?;
 
-        let parse_results = do_parses(fnames, true)?;
+        if do_analysis {
+            info!("# (with analysis!)");
 
-        info!("~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~");
-        info!("| Starting analysis with pyo3...");
+            info!("~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~");
+            info!("| Starting analysis with pyo3...");
 
-        for (fname, demodata) in &parse_results {
-            info!("| Analyzing: {:#?}", fname.file_name().unwrap().to_str());
-            dt::launch_demo_analysis(demodata);
+            let host = dt::AnalysisHost::load(&config.analysis);
+            for err in host.load_errors() {
+                error!("| Could not load analysis: {}", err);
+            }
+
+            for (fname, demodata) in &parse_results {
+                info!("| Analyzing: {:#?}", fname.file_name().unwrap().to_str());
+                for analysis in host.analyses_of(dt::AnalysisKind::Demo) {
+                    info!("|   Running {} (from {})", analysis.name, analysis.source);
+                    match host.run_demo_analysis(&analysis.name, demodata) {
+                        Ok(result) => {
+                            result.print_summary(&analysis.name);
+                            if let Some(dir) = &config.analysis.export_dir {
+                                let prefix = format!("{}_{}",
+                                    fname.file_stem().unwrap_or_default().to_string_lossy(), analysis.name);
+                                match result.export_csv(dir, &prefix) {
+                                    Ok(files) => info!("|   Wrote {:?}", files),
+                                    Err(err) => error!("| Could not export {}: {}", analysis.name, err),
+                                }
+                            }
+                        },
+                        Err(err) => error!("| {} failed: {}", analysis.name, err),
+                    }
+                }
+            }
+        }
+
+        if let Some(dir) = &config.heatmap.dir {
+            for (fname, demodata) in &parse_results {
+                match write_heatmaps(fname, demodata, &config.heatmap, dir) {
+                    Ok(files) => info!("| Wrote heatmaps {:?}", files),
+                    Err(err) => error!("| Could not write heatmaps of {:?}: {}", fname.file_name().unwrap(), err),
+                }
+            }
         }
     }
 
@@ -139,7 +390,6 @@ pub fn run(fnames: Vec<PathBuf>, do_analysis: bool) -> io::Result<()> {
 
         use tf_demo_parser::{Demo, DemoParser};
         use crate::parsing::internals::Gatherer;
-        use std::collections::HashMap;
 
         for fname in fnames {
             let file = std::fs::read(fname)?;
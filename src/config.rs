@@ -0,0 +1,175 @@
+//! Settings for the executable, read from a TOML file.
+//!
+//! Every section and field is optional; anything missing uses its default.
+//!
+//! ```toml
+//! [analysis]
+//! scripts = ["python/demo_analysis.py"]
+//! modules = ["my_team_analysis"]
+//! export_dir = "analysis_output"
+//!
+//! [heatmap]
+//! dir = "heatmaps"
+//! kind = "deaths"
+//! by = "class"
+//! format = "svg"
+//!
+//! [season]
+//! cache_dir = "parse_cache"
+//! team = "us"
+//!
+//! [season.teams]
+//! us = ["[U:1:22202]", "[U:1:22203]"]
+//!
+//! [[zones.cp_process_final]]
+//! name = "mid"
+//! points = [[-1100, -600], [1100, -600], [1100, 600], [-1100, 600]]
+//! ```
+
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::analysis::heatmap::HeatmapKind;
+use crate::analysis::zones::Zone;
+use crate::types::identity::SteamId;
+
+/// Where the config is read from when `--config` is not given.
+pub const DEFAULT_CONFIG_PATH: &str = "demo_analysis.toml";
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub analysis: AnalysisConfig,
+    pub heatmap: HeatmapConfig,
+    pub season: SeasonConfig,
+    /// Named areas of each map, keyed by map name. See [`crate::analysis::zones`].
+    pub zones: HashMap<String, Vec<Zone>>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct AnalysisConfig {
+    /// Python files to load analyses from.
+    pub scripts: Vec<PathBuf>,
+
+    /// Python modules to load analyses from. These must be importable,
+    /// e.g. installed in the virtual environment.
+    pub modules: Vec<String>,
+
+    /// If set, the tables and metrics from analyses run with `-a` are
+    /// written here as CSV files.
+    pub export_dir: Option<PathBuf>,
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        AnalysisConfig {
+            scripts: vec![PathBuf::from("python/demo_analysis.py")],
+            modules: Vec::new(),
+            export_dir: None,
+        }
+    }
+}
+
+/// Which heatmaps are written for each demo, one per team, class or player.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum HeatmapSplit {
+    #[default]
+    Team,
+    Class,
+    Player,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageFormat {
+    #[default]
+    Png,
+    Svg,
+}
+
+impl ImageFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Png => "png",
+            ImageFormat::Svg => "svg",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct HeatmapConfig {
+    /// If set, the heatmaps of each demo parsed without a window are
+    /// written here.
+    pub dir: Option<PathBuf>,
+
+    pub kind: HeatmapKind,
+
+    pub by: HeatmapSplit,
+
+    pub format: ImageFormat,
+
+    /// Width of each cell, in world units.
+    pub cell_size: f32,
+}
+
+impl Default for HeatmapConfig {
+    fn default() -> Self {
+        HeatmapConfig {
+            dir: None,
+            kind: HeatmapKind::Occupancy,
+            by: HeatmapSplit::Team,
+            format: ImageFormat::Png,
+            cell_size: 64.0,
+        }
+    }
+}
+
+/// Settings for `--season`. See [`crate::analysis::season`].
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SeasonConfig {
+    /// If set, parses are saved here and read back instead of parsing
+    /// demos that haven't changed since.
+    pub cache_dir: Option<PathBuf>,
+
+    /// The name of our team in `teams`, so the other side is the opponent.
+    pub team: Option<String>,
+
+    /// Team name to the SteamIDs of its players, in any format.
+    pub teams: HashMap<String, Vec<String>>,
+}
+
+impl SeasonConfig {
+    /// `teams` with the SteamIDs parsed. Ones that aren't valid are
+    /// logged and left out.
+    pub fn rosters(&self) -> HashMap<String, Vec<SteamId>> {
+        self.teams.iter()
+            .map(|(name, ids)| {
+                let ids = ids.iter()
+                    .filter_map(|id| id.parse::<SteamId>()
+                        .map_err(|err| log::warn!("in team {}: {}", name, err))
+                        .ok())
+                    .collect();
+                (name.clone(), ids)
+            })
+            .collect()
+    }
+}
+
+impl Config {
+    /// Reads the config at `path`. A missing file gives the default config.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err)),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Config::default()),
+            Err(err) => Err(err),
+        }
+    }
+}
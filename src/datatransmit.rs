@@ -1,56 +1,260 @@
+//! Runs the Python analysis scripts.
+//!
+//! Scripts and modules are loaded once, when the [`AnalysisHost`] is made.
+//! Each one registers its analyses by defining `register(registry)` and
+//! calling `registry.add_demo_analysis(name, func)` or
+//! `registry.add_tick_analysis(name, func)`. Scripts without `register` fall
+//! back to their `demo_analysis_main` and `tick_analysis_main` functions.
+//!
+//! What an analysis returns is converted into an [`AnalysisResult`].
+//!
+//! The host remembers which files it loaded from, so the viewer can reload
+//! them with [`AnalysisHost::reload`] when [`AnalysisHost::files_changed`].
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
 use pyo3::prelude::*;
 
+use crate::analysisresult::AnalysisResult;
+use crate::config::AnalysisConfig;
 use crate::types::demo::{DemoData, TickData};
 
-const DEMO_ANALYSIS_PY: &str = "python/demoanalysis.py";
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AnalysisKind {
+    /// Called with a whole `DemoData`.
+    Demo,
+    /// Called with the `TickData` of a single tick.
+    Tick,
+}
 
-#[allow(dead_code)]
-pub fn launch_demo_analysis(demodata: &DemoData) {
-    Python::with_gil(move |py| -> PyResult<()> {
-        let m = PyModule::from_code(py,
-            std::fs::read_to_string(DEMO_ANALYSIS_PY).unwrap().as_str(),
-            "demoanalysis.py",
-            "demoanalysis"
-        )?;
+impl fmt::Display for AnalysisKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnalysisKind::Demo => write!(f, "demo"),
+            AnalysisKind::Tick => write!(f, "tick"),
+        }
+    }
+}
 
-        let args = (demodata.clone(),);
+#[derive(Debug, Clone)]
+pub enum AnalysisError {
+    /// A script could not be read.
+    Io(PathBuf, String),
+    /// Python raised an exception. Holds the formatted traceback.
+    Python(String),
+    /// No analysis of that name and kind is registered.
+    NotFound(String, AnalysisKind),
+}
 
-        m.getattr("demo_analysis_main")?.call1(args)?;
+impl fmt::Display for AnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnalysisError::Io(path, err) => write!(f, "could not read {}: {}", path.display(), err),
+            AnalysisError::Python(err) => write!(f, "{}", err),
+            AnalysisError::NotFound(name, kind) => write!(f, "no {} analysis named \"{}\"", kind, name),
+        }
+    }
+}
 
-        Ok(())
-    }).unwrap();
+impl AnalysisError {
+    fn from_py(py: Python<'_>, err: PyErr) -> Self {
+        let traceback = err.traceback(py)
+            .and_then(|tb| tb.format().ok())
+            .unwrap_or_default();
+        AnalysisError::Python(format!("{}{}", traceback, err))
+    }
 }
 
-#[allow(dead_code)]
-pub fn launch_tick_analysis(tickdata: &TickData) {
-    Python::with_gil(move |py| -> PyResult<()> {
-        let m = PyModule::from_code(py,
-            std::fs::read_to_string(DEMO_ANALYSIS_PY).unwrap().as_str(),
-            "demoanalysis.py",
-            "demoanalysis"
-        )?;
+/// Passed to a script's `register` function.
+#[pyclass]
+#[derive(Default)]
+pub struct AnalysisRegistry {
+    entries: Vec<(String, AnalysisKind, PyObject)>,
+}
 
-        let args = (tickdata.clone(),);
+#[pymethods]
+impl AnalysisRegistry {
+    /// `func` is called with a `DemoData`.
+    pub fn add_demo_analysis(&mut self, name: String, func: PyObject) {
+        self.entries.push((name, AnalysisKind::Demo, func));
+    }
 
-        m.getattr("tick_analysis_main")?.call1(args)?;
+    /// `func` is called with a `TickData`.
+    pub fn add_tick_analysis(&mut self, name: String, func: PyObject) {
+        self.entries.push((name, AnalysisKind::Tick, func));
+    }
+}
 
-        Ok(())
-    }).unwrap();
+pub struct RegisteredAnalysis {
+    pub name: String,
+    pub kind: AnalysisKind,
+    /// The script path or module name the analysis came from.
+    pub source: String,
+    func: PyObject,
 }
 
-#[allow(dead_code)]
-pub fn launch_get_player_groupings(tickdata: &TickData) {
-    Python::with_gil(move |py| -> PyResult<()> {
-        let m = PyModule::from_code(py,
-            std::fs::read_to_string(DEMO_ANALYSIS_PY).unwrap().as_str(),
-            "demoanalysis.py",
-            "demoanalysis"
-        )?;
+/// Every analysis from the configured scripts and modules.
+#[derive(Default)]
+pub struct AnalysisHost {
+    config: AnalysisConfig,
+    analyses: Vec<RegisteredAnalysis>,
+    load_errors: Vec<AnalysisError>,
+
+    /// Files the analyses came from, and when they were last modified as of loading.
+    watched: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl AnalysisHost {
+    /// Loads every script and module in `config`. A source that fails to load
+    /// is recorded in [`AnalysisHost::load_errors`] and skipped.
+    pub fn load(config: &AnalysisConfig) -> Self {
+        Self::load_sources(config, false)
+    }
+
+    /// Loads everything again, picking up any changes to the files.
+    pub fn reload(&mut self) {
+        *self = Self::load_sources(&self.config, true);
+    }
+
+    /// True if any file the analyses came from has changed since loading.
+    pub fn files_changed(&self) -> bool {
+        self.watched.iter().any(|(path, loaded)| modified_time(path) != *loaded)
+    }
+
+    fn load_sources(config: &AnalysisConfig, reload: bool) -> Self {
+        let mut host = AnalysisHost {
+            config: config.clone(),
+            ..Default::default()
+        };
+
+        Python::with_gil(|py| {
+            for path in &config.scripts {
+                // Watched even if it fails to load, so that fixing it reloads it
+                host.watched.push((path.clone(), modified_time(path)));
+
+                let loaded = load_script(py, path)
+                    .and_then(|module| host.register_module(py, module, &path.display().to_string()));
+                if let Err(err) = loaded {
+                    host.load_errors.push(err);
+                }
+            }
+
+            for name in &config.modules {
+                // Found without importing it, so it's watched even if the import fails
+                if let Some(file) = module_origin(py, name) {
+                    let modified = modified_time(&file);
+                    host.watched.push((file, modified));
+                }
 
-        let args = (tickdata.clone(),);
+                let loaded = import_module(py, name, reload)
+                    .map_err(|err| AnalysisError::from_py(py, err))
+                    .and_then(|module| host.register_module(py, module, name));
+                if let Err(err) = loaded {
+                    host.load_errors.push(err);
+                }
+            }
+        });
 
-        m.getattr("get_player_groupings")?.call1(args)?;
+        host
+    }
+
+    fn register_module(&mut self, py: Python<'_>, module: &PyModule, source: &str) -> Result<(), AnalysisError> {
+        let entries = match module.getattr("register") {
+            Ok(register) => {
+                let registry = Py::new(py, AnalysisRegistry::default())
+                    .map_err(|err| AnalysisError::from_py(py, err))?;
+                register.call1((registry.clone_ref(py),))
+                    .map_err(|err| AnalysisError::from_py(py, err))?;
+                let entries = std::mem::take(&mut registry.borrow_mut(py).entries);
+                entries
+            },
+            Err(_) => {
+                let mut entries = Vec::new();
+                if let Ok(func) = module.getattr("demo_analysis_main") {
+                    entries.push(("demo_analysis_main".to_string(), AnalysisKind::Demo, func.into()));
+                }
+                if let Ok(func) = module.getattr("tick_analysis_main") {
+                    entries.push(("tick_analysis_main".to_string(), AnalysisKind::Tick, func.into()));
+                }
+                entries
+            }
+        };
+
+        for (name, kind, func) in entries {
+            self.analyses.push(RegisteredAnalysis { name, kind, source: source.to_string(), func });
+        }
 
         Ok(())
-    }).unwrap();
-}
\ No newline at end of file
+    }
+
+    pub fn analyses(&self) -> &[RegisteredAnalysis] {
+        &self.analyses
+    }
+
+    pub fn analyses_of(&self, kind: AnalysisKind) -> impl Iterator<Item = &RegisteredAnalysis> {
+        self.analyses.iter().filter(move |a| a.kind == kind)
+    }
+
+    pub fn load_errors(&self) -> &[AnalysisError] {
+        &self.load_errors
+    }
+
+    fn find(&self, name: &str, kind: AnalysisKind) -> Result<&RegisteredAnalysis, AnalysisError> {
+        self.analyses.iter()
+            .find(|a| a.kind == kind && a.name == name)
+            .ok_or_else(|| AnalysisError::NotFound(name.to_string(), kind))
+    }
+
+    pub fn run_demo_analysis(&self, name: &str, demodata: &DemoData) -> Result<AnalysisResult, AnalysisError> {
+        let analysis = self.find(name, AnalysisKind::Demo)?;
+        Python::with_gil(|py| {
+            analysis.func.call1(py, (demodata.clone(),))
+                .and_then(|ret| ret.extract(py))
+                .map_err(|err| AnalysisError::from_py(py, err))
+        })
+    }
+
+    pub fn run_tick_analysis(&self, name: &str, tickdata: &TickData) -> Result<AnalysisResult, AnalysisError> {
+        let analysis = self.find(name, AnalysisKind::Tick)?;
+        Python::with_gil(|py| {
+            analysis.func.call1(py, (tickdata.clone(),))
+                .and_then(|ret| ret.extract(py))
+                .map_err(|err| AnalysisError::from_py(py, err))
+        })
+    }
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// The file the module `name` would be imported from, if it is one.
+/// Parent packages of a dotted name are imported to find it.
+fn module_origin(py: Python<'_>, name: &str) -> Option<PathBuf> {
+    let spec = py.import("importlib.util").ok()?.call_method1("find_spec", (name,)).ok()?;
+    let origin = spec.getattr("origin").ok()?.extract::<String>().ok()?;
+    // Built-in and frozen modules have an origin that isn't a file
+    Some(PathBuf::from(origin)).filter(|path| path.is_file())
+}
+
+/// Python caches imports, so reloading has to go through `importlib`.
+fn import_module<'py>(py: Python<'py>, name: &str, reload: bool) -> PyResult<&'py PyModule> {
+    let module = py.import(name)?;
+    if reload {
+        Ok(py.import("importlib")?.call_method1("reload", (module,))?.downcast()?)
+    } else {
+        Ok(module)
+    }
+}
+
+fn load_script<'py>(py: Python<'py>, path: &Path) -> Result<&'py PyModule, AnalysisError> {
+    let code = std::fs::read_to_string(path)
+        .map_err(|err| AnalysisError::Io(path.to_path_buf(), err.to_string()))?;
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("analysis.py");
+    let module_name = path.file_stem().and_then(|n| n.to_str()).unwrap_or("analysis");
+
+    PyModule::from_code(py, &code, file_name, module_name)
+        .map_err(|err| AnalysisError::from_py(py, err))
+}
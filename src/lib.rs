@@ -3,33 +3,114 @@
 //! [`demo_analysis_lib`]` is run during `maturin develop` to add the classes
 //! necessary to `demo_analysis_lib.pyd`.
 
+#[macro_use]
 mod types;
+mod analysis;
 mod parsing;
 mod datatransmit;
+mod analysisresult;
+mod config;
 mod app;
 
+use std::collections::HashMap;
 use std::path::PathBuf;
 use pyo3::prelude::*;
+use analysis::season::Season;
 use types::demo::DemoData;
+use types::parse::ParseHandle;
+use parsing::{ParseOptions, ParseWorkerError, TickFilter, TickHook};
+use types::events::{TickEventKind, TickEvents};
+use types::identity::SteamId;
+use pyo3::exceptions::{PyRuntimeError, PyValueError};
 //use crate::types::{math, demo, game};
 
-/// Adds all of the types to the python library.
+/// Demo Analysis Library
+///
+/// Every class can be pickled (so works with multiprocessing, joblib, etc.),
+/// copied with `copy.copy`/`copy.deepcopy`, and printed. Data classes compare
+/// by value with `==`.
 #[pymodule]
 fn tf2dal(py: Python<'_>, m: &PyModule) -> PyResult<()> {
     pyo3_log::init();
 
+    /// Loads a demo and returns the associated data.
+    ///
+    /// `start_tick` and `end_tick` limit the recorded ticks to an inclusive range,
+    /// and `rounds` limits them to the rounds with those indices (0 is the first
+    /// round). Events outside of those are not recorded either.
+    /// With `stop_early`, parsing stops as soon as nothing else would be recorded.
+    ///
+    /// `on_tick(tick_data, tick_events)` is called while parsing with each
+    /// recorded tick and the events that happened on it. `every` only calls it
+    /// every N recorded ticks, and `events` ("kill", "capture", "ubercharge", "damage", "heal", "spawn")
+    /// only on ticks with one of those events; with both, either will do. An
+    /// exception raised by `on_tick` stops the parse and is re-raised.
+    /// With `store_ticks=False` the result has no `tick_states`, so whole demos
+    /// can be processed without keeping every tick in memory.
+    ///
+    /// The GIL is released while parsing, here and in the other load functions,
+    /// so other Python threads (and Jupyter) keep running. It is only taken
+    /// back to call `on_tick`.
     #[pyfn(m)]
-    fn load_demo(fname: PathBuf) -> PyResult<DemoData> {
-        Ok(crate::app::do_parses(vec![fname], false)?
-            .get(0).ok_or(
+    #[pyo3(signature = (
+        fname, start_tick=None, end_tick=None, rounds=None, stop_early=true,
+        on_tick=None, every=None, events=None, store_ticks=true
+    ))]
+    #[allow(clippy::too_many_arguments)]
+    fn load_demo(
+        py: Python<'_>,
+        fname: PathBuf,
+        start_tick: Option<u32>,
+        end_tick: Option<u32>,
+        rounds: Option<Vec<usize>>,
+        stop_early: bool,
+        on_tick: Option<PyObject>,
+        every: Option<u32>,
+        events: Option<Vec<String>>,
+        store_ticks: bool,
+    ) -> PyResult<DemoData> {
+        let mut options = ParseOptions::new(start_tick, end_tick, rounds, stop_early);
+        options.discard_ticks = !store_ticks;
+
+        if let Some(on_tick) = on_tick {
+            let events = events.unwrap_or_default()
+                .iter()
+                .map(|name| TickEventKind::from_name(name)
+                    .ok_or_else(|| PyValueError::new_err(format!("unknown event type \"{}\"", name))))
+                .collect::<PyResult<Vec<_>>>()?;
+
+            // The callback's exception is kept so it can be raised as-is
+            let mut callback_err = None;
+            let hook = TickHook {
+                filter: TickFilter { every, events },
+                callback: Box::new(|tick: &types::demo::TickData, tick_events: TickEvents| {
+                    Python::with_gil(|py| on_tick.call1(py, (tick.clone(), tick_events)).map(|_| ()))
+                        .map_err(|err| {
+                            callback_err = Some(err);
+                            ParseWorkerError::HookError
+                        })
+                }),
+            };
+
+            let result = py.allow_threads(|| crate::app::parse_with_hook(fname, &options, hook));
+            if let Some(err) = callback_err {
+                return Err(err);
+            }
+            return result.map_err(|err| PyRuntimeError::new_err(format!("parse failed: {:?}", err)));
+        }
+
+        Ok(py.allow_threads(|| crate::app::do_parses(vec![fname], false, &options))?
+            .into_iter().next().ok_or(
                 std::io::Error::new(std::io::ErrorKind::InvalidInput, "could not find demo file")
-            )?.to_owned().1 // Could potentially avoid the .to_owned here?
+            )?.1
         )
     }
-    
+
+    /// Loads a demo and returns data split into rounds.
+    /// Each DemoData in the list should be a separate round.
     #[pyfn(m)]
-    fn load_demo_rounds(fname: PathBuf) -> PyResult<Vec<DemoData>> {
-        let data = load_demo(fname)?;
+    fn load_demo_rounds(py: Python<'_>, fname: PathBuf) -> PyResult<Vec<DemoData>> {
+        let data = load_demo(py, fname, None, None, None, true, None, None, None, true)?;
 
         let mut retvec = Vec::new();
         for round in &data.rounds {
@@ -39,16 +120,66 @@ fn tf2dal(py: Python<'_>, m: &PyModule) -> PyResult<()> {
         Ok(retvec)
     }
 
+    /// Parses many demos at once, `threads` at a time. The returned list is in
+    /// the same order as `fnames`, with None for any demo that failed to parse.
+    #[pyfn(m)]
+    #[pyo3(signature = (fnames, threads=4))]
+    fn load_demos(py: Python<'_>, fnames: Vec<PathBuf>, threads: usize) -> PyResult<Vec<Option<DemoData>>> {
+        let options = ParseOptions::default();
+        Ok(py.allow_threads(|| crate::app::parse_each(fnames, threads, &options))?)
+    }
+
+    /// Parses many demos, `threads` at a time and without tick states, into a
+    /// `Season`. Saved parses (`.tf2dal` files from `DemoData.save`) can be
+    /// given in place of demos. With `cache_dir`, each parse is saved there and
+    /// read back instead of parsing the demo again, until the demo changes.
+    /// Demos that fail to parse are left out.
+    #[pyfn(m)]
+    #[pyo3(signature = (fnames, threads=4, cache_dir=None, teams=HashMap::new(), our_team=None))]
+    fn load_season(
+        py: Python<'_>,
+        fnames: Vec<PathBuf>,
+        threads: usize,
+        cache_dir: Option<PathBuf>,
+        teams: HashMap<String, Vec<SteamId>>,
+        our_team: Option<String>,
+    ) -> PyResult<Season> {
+        Ok(py.allow_threads(|| crate::app::load_season(fnames, threads, cache_dir.as_deref(), teams, our_team))?)
+    }
+
+    /// Starts parsing a demo on another thread and returns immediately.
+    /// Takes the same tick and round options as `load_demo`.
+    #[pyfn(m)]
+    #[pyo3(signature = (fname, start_tick=None, end_tick=None, rounds=None, stop_early=true))]
+    fn parse_async(
+        fname: PathBuf,
+        start_tick: Option<u32>,
+        end_tick: Option<u32>,
+        rounds: Option<Vec<usize>>,
+        stop_early: bool,
+    ) -> PyResult<ParseHandle> {
+        let options = ParseOptions::new(start_tick, end_tick, rounds, stop_early);
+        Ok(ParseHandle::new(fname, options)?)
+    }
+
     let loader_fns = vec![
         types::demo::get_submod,
         types::entities::get_submod,
         types::events::get_submod,
         types::game::get_submod,
         types::math::get_submod,
+        types::parse::get_submod,
+        types::identity::get_submod,
+        analysis::get_submod,
     ];
 
+    // Registering the submodules in sys.modules makes them importable as
+    // `tf2dal.<name>`, which pickle needs to find the classes again.
+    let sys_modules = py.import("sys")?.getattr("modules")?;
     for func in loader_fns {
-        m.add_submodule(func(py)?)?;
+        let submodule = func(py)?;
+        m.add_submodule(submodule)?;
+        sys_modules.set_item(format!("tf2dal.{}", submodule.name()?), submodule)?;
     }
 
     Ok(())
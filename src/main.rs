@@ -2,11 +2,19 @@ use clap::Parser;
 
 //mod drawing;
 mod parsing;
+#[macro_use]
 mod types;
 mod viewing;
 mod analysis;
 mod app;
 mod datatransmit;
+mod analysisresult;
+mod config;
+
+use analysis::heatmap::HeatmapKind;
+use analysis::season::SeasonFilter;
+use config::{Config, HeatmapSplit, ImageFormat};
+use types::game::Class;
 
 #[derive(Parser, Debug)]
 #[command(version, about)]
@@ -21,15 +29,113 @@ struct Args {
     /// Stop the window from showing up. Without analysis, does nothing.
     #[arg(short)]
     no_window: bool,
+
+    /// The config file to read. Defaults to `demo_analysis.toml`.
+    #[arg(short, long)]
+    config: Option<std::path::PathBuf>,
+
+    /// A Python script to load analyses from. Can be given more than once.
+    /// Replaces the scripts listed in the config.
+    #[arg(short, long)]
+    script: Vec<std::path::PathBuf>,
+
+    /// A Python module to load analyses from. Can be given more than once.
+    /// Replaces the modules listed in the config.
+    #[arg(short, long)]
+    module: Vec<String>,
+
+    /// Write the tables and metrics from analyses run with -a to CSV files
+    /// in this directory.
+    #[arg(short, long)]
+    export: Option<std::path::PathBuf>,
+
+    /// Write heatmaps of each demo to this directory. Only used with -n.
+    #[arg(long)]
+    heatmap: Option<std::path::PathBuf>,
+
+    /// What the heatmaps show: occupancy, kills or deaths.
+    #[arg(long)]
+    heatmap_kind: Option<HeatmapKind>,
+
+    /// Write a heatmap per team, class or player.
+    #[arg(long, value_enum)]
+    heatmap_by: Option<HeatmapSplit>,
+
+    #[arg(long, value_enum)]
+    heatmap_format: Option<ImageFormat>,
+
+    /// Add up players and teams over all of the files, which can be demos
+    /// or saved parses, and print the totals. Doesn't open a window.
+    #[arg(long)]
+    season: bool,
+
+    /// Save parses for --season here, and reuse them while the demos are unchanged.
+    #[arg(long)]
+    cache: Option<std::path::PathBuf>,
+
+    /// Only count demos on maps containing this, with --season.
+    #[arg(long)]
+    map: Option<String>,
+
+    /// Only count demos against this team from the config, with --season.
+    #[arg(long)]
+    opponent: Option<String>,
+
+    /// Only count demos from this date (YYYY-MM-DD) on, with --season.
+    #[arg(long)]
+    since: Option<String>,
+
+    /// Only count demos up to this date (YYYY-MM-DD), with --season.
+    #[arg(long)]
+    until: Option<String>,
+
+    /// Only count players' stats while on this class, with --season.
+    #[arg(long)]
+    class: Option<Class>,
 }
 
 fn main() -> eframe::Result<()> {
     let args = Args::parse();
 
+    let config_path = args.config.clone()
+        .unwrap_or_else(|| std::path::PathBuf::from(config::DEFAULT_CONFIG_PATH));
+    let mut config = Config::load(&config_path).unwrap_or_else(|err| {
+        eprintln!("could not read config {:?}: {}", config_path, err);
+        Config::default()
+    });
+    if !args.script.is_empty() {
+        config.analysis.scripts = args.script.clone();
+    }
+    if !args.module.is_empty() {
+        config.analysis.modules = args.module.clone();
+    }
+    if args.export.is_some() {
+        config.analysis.export_dir = args.export.clone();
+    }
+    if args.heatmap.is_some() {
+        config.heatmap.dir = args.heatmap.clone();
+    }
+    config.heatmap.kind = args.heatmap_kind.unwrap_or(config.heatmap.kind);
+    config.heatmap.by = args.heatmap_by.unwrap_or(config.heatmap.by);
+    config.heatmap.format = args.heatmap_format.unwrap_or(config.heatmap.format);
+    if args.cache.is_some() {
+        config.season.cache_dir = args.cache.clone();
+    }
+
     println!("filenames: {:?} ; analysis: {:?} ; no_window: {:?}",
         args.filenames, args.analysis, args.no_window);
 
-    if !args.no_window {
+    if args.season {
+        let filter = SeasonFilter {
+            map: args.map,
+            opponent: args.opponent,
+            since: args.since,
+            until: args.until,
+            player_class: args.class,
+        };
+        app::run_season(args.filenames.unwrap_or_default(), &filter, &config).unwrap();
+    }
+    else if !args.no_window {
         let native_options = eframe::NativeOptions {
             viewport: egui::ViewportBuilder::default()
             .with_inner_size([1000.0, 600.0])
@@ -41,12 +147,12 @@ fn main() -> eframe::Result<()> {
         eframe::run_native(
             "tf2 demo info", 
             native_options,
-            Box::new(|cc| Box::new(viewing::TemplateApp::new(cc))),
+            Box::new(|cc| Box::new(viewing::TemplateApp::new(cc, &config))),
         )?;
     }
     else if let Some(fnames) = args.filenames {
         if fnames.len() > 0 {
-            app::run(fnames, args.analysis).unwrap();
+            app::run(fnames, args.analysis, &config).unwrap();
         }
     }
 
@@ -70,9 +70,10 @@ use tf_demo_parser::{
 use crate::types::DemoTick;
 use crate::types::math::{Vector, VectorXY};
 use crate::types::game::{World, Round, Team, Class};
-use crate::types::events::{Capture, Kill, Ubercharge};
+use crate::types::events::{Capture, Damage, Heal, Kill, Spawn, Ubercharge};
 use crate::types::entities::*;
 use crate::types::demo::TickData;
+use crate::types::identity::IdentityMap;
 
 //use serde::{Serialize, Deserialize};
 
@@ -89,6 +90,7 @@ pub struct TickGameState {
     pub data: TickData,
     pub rounds: Vec<Round>,
     pub world: Option<World>,
+    pub identities: IdentityMap,
     
     pub(crate) _cur_round_index: u32,
 
@@ -98,6 +100,9 @@ pub struct TickGameState {
     pub kills: Vec<Kill>,
     pub captures: Vec<Capture>,
     pub ubercharges: Vec<Ubercharge>,
+    pub damage: Vec<Damage>,
+    pub heals: Vec<Heal>,
+    pub spawns: Vec<Spawn>,
     pub players_hit: Vec<u32>, // by entity_id
 }
 
@@ -327,6 +332,9 @@ impl GameStateAnalyserPlus {
         self.state.kills.clear();
         self.state.captures.clear();
         self.state.ubercharges.clear();
+        self.state.damage.clear();
+        self.state.heals.clear();
+        self.state.spawns.clear();
     }
 
     pub fn handle_temp_entity(&mut self, _events: &Vec<EventInfo>) {
@@ -367,11 +375,14 @@ impl GameStateAnalyserPlus {
 
             // Player related
             GameEvent::PlayerSpawn(event) => {
+                let mut position = Vector::default();
                 if let Some(player) = self.state.data.mut_player_by_userid(event.user_id) {
                     player.time_since_last_hurt = 15.0;
                     player.class = Class::new(event.class);
                     player.team = Team::new(event.team);
+                    position = player.position;
                 }
+                self.state.spawns.push(Spawn::from_event(tick, event, position));
             }
 
             GameEvent::PlayerDeath(event) => {
@@ -379,6 +390,7 @@ impl GameStateAnalyserPlus {
             }
             
             GameEvent::PlayerHurt(event) => {
+                self.state.damage.push(Damage::from_event(tick, event));
                 if let Some(player) = self.state.data.mut_player_by_userid(event.user_id) {
                     player.time_since_last_hurt = 0.0;
                 }
@@ -391,10 +403,8 @@ impl GameStateAnalyserPlus {
                             debug!("NOTE: weird player healed: pat/heal team {:?} {:?}, heal class {:?}",
                                     patient.team, healer.team, healer.class);
                         }
-                        else {
-                            
-                        }
                     }
+                    self.state.heals.push(Heal::from_event(tick, event));
                 }
                 else {
                     debug!("player healed with invalid patient");
@@ -686,6 +696,13 @@ impl GameStateAnalyserPlus {
                 }
             }
         }
+
+        // The spawn event can come before the player has moved to the spawn
+        if let Some(info) = &player.info {
+            for spawn in self.state.spawns.iter_mut().filter(|s| s.user_id == info.user_id) {
+                spawn.position = player.position;
+            }
+        }
     }
 
     pub fn handle_world_entity(&mut self, entity: &PacketEntity, parser_state: &ParserState) {
@@ -928,7 +945,9 @@ impl GameStateAnalyserPlus {
             tf_demo_parser::demo::data::UserInfo::parse_from_string_table(index as u16, text, data)?
         {
             let id = user_info.entity_id;
-            self.state.data.get_or_create_player(id).info = Some(user_info.into());
+            let info = UserInfo::from(user_info);
+            self.state.identities.record(&info);
+            self.state.data.get_or_create_player(id).info = Some(info);
         }
 
         Ok(())
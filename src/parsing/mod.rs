@@ -19,14 +19,16 @@ pub mod datacollection;
 pub mod internals;
 
 // INCLUDES
-use super::types::game::World;
-use super::types::demo::DemoData;
+use super::types::game::{Round, World};
+use super::types::demo::{DemoData, TickData};
+use super::types::events::{TickEventKind, TickEvents};
 
 use self::datacollection::GameStateAnalyserPlus;
 
 use std::path::PathBuf;
 use std::sync::mpsc;
 use std::thread::{self, JoinHandle};
+use std::time::Duration;
 
 use tf_demo_parser::demo::parser::ParseError;
 use tf_demo_parser::DemoParser;
@@ -35,7 +37,10 @@ use tf_demo_parser::DemoParser;
 pub enum ParseWorkerError {
     ParseError(ParseError),
     IoError(std::io::Error),
-    SendError
+    SendError,
+    Cancelled,
+    /// A [`TickHook`] asked for the parse to stop.
+    HookError,
 }
 
 impl From<ParseError> for ParseWorkerError {
@@ -66,10 +71,22 @@ pub enum ParseProgressReport {
     Error(ParseWorkerError)
 }
 
+/// Instructions that can be sent to a running [`ParseWorker`].
+/// Works the same way as [`internals::InternalParseInstruction`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ParseInstruction {
+    Cancel,
+    Pause,
+    Resume,
+}
+
+/// Runs [`parse_demo`] on its own thread. Dropping the worker cancels the
+/// parse and waits for the thread to exit.
 pub struct ParseWorker {
-    #[allow(dead_code)]
-    handle: JoinHandle<()>,
+    handle: Option<JoinHandle<()>>,
     prog_recv: mpsc::Receiver<ParseProgressReport>,
+    instruct_send: mpsc::Sender<ParseInstruction>,
+    paused: bool,
 }
 
 /// Information required to properly render the parse.
@@ -81,8 +98,138 @@ pub struct ParseDrawInfo {
     pub player_at_max: World
 }
 
-pub fn parse_demo(fpath: PathBuf, mut progress_reporter: impl FnMut(ParseProgressReport) -> Result<(), ParseWorkerError>)
-{
+/// Limits what [`parse_demo`] records. The default records everything.
+#[derive(Default, Debug, Clone)]
+pub struct ParseOptions {
+    /// Only record ticks within this inclusive range.
+    pub tick_range: Option<(u32, u32)>,
+
+    /// Only record ticks within these rounds, by index into the demo's rounds.
+    pub rounds: Option<Vec<usize>>,
+
+    /// Stop parsing once nothing else would be recorded.
+    pub stop_early: bool,
+
+    /// Don't keep tick states in the result. Events and rounds are still kept.
+    /// Useful when a [`TickHook`] already looks at every tick it needs.
+    pub discard_ticks: bool,
+}
+
+impl ParseOptions {
+    /// Either bound can be left open. Leaving both open records every tick.
+    pub fn new(start_tick: Option<u32>, end_tick: Option<u32>, rounds: Option<Vec<usize>>, stop_early: bool) -> Self {
+        let tick_range = match (start_tick, end_tick) {
+            (None, None) => None,
+            (start, end) => Some((start.unwrap_or(0), end.unwrap_or(u32::MAX)))
+        };
+
+        ParseOptions {
+            tick_range,
+            rounds,
+            stop_early,
+            ..Default::default()
+        }
+    }
+
+    /// The index of the round that `tick` is in, if any.
+    fn round_at(tick: u32, rounds: &[Round]) -> Option<usize> {
+        rounds
+            .iter()
+            .rposition(|r| r.contains(tick))
+    }
+
+    pub fn records_tick(&self, tick: u32, rounds: &[Round]) -> bool {
+        let in_range = match self.tick_range {
+            Some((start, end)) => tick >= start && tick <= end,
+            None => true
+        };
+
+        let in_rounds = match &self.rounds {
+            Some(selected) => Self::round_at(tick, rounds).is_some_and(|i| selected.contains(&i)),
+            None => true
+        };
+
+        in_range && in_rounds
+    }
+
+    /// True if stopping early is allowed and no tick after `tick` would be recorded.
+    pub fn is_finished(&self, tick: u32, rounds: &[Round]) -> bool {
+        if !self.stop_early {
+            return false;
+        }
+
+        let past_range = self.tick_range.is_some_and(|(_, end)| tick > end);
+
+        // Every selected round has to have happened and ended
+        let past_rounds = self.rounds.as_ref().is_some_and(|selected| {
+            selected.iter().all(|i| match rounds.get(*i) {
+                Some(round) => round.end_tick != 0 && tick > round.end_tick,
+                None => false
+            })
+        });
+
+        past_range || past_rounds
+    }
+}
+
+/// Which recorded ticks a [`TickHook`] is called for. The default is every tick.
+#[derive(Default, Debug, Clone)]
+pub struct TickFilter {
+    /// Call the hook every N recorded ticks.
+    pub every: Option<u32>,
+
+    /// Call the hook on ticks where any of these events happened.
+    pub events: Vec<TickEventKind>,
+}
+
+impl TickFilter {
+    /// `recorded` is how many ticks were recorded before this one. If both
+    /// `every` and `events` are set, matching either is enough.
+    pub fn matches(&self, recorded: u32, events: &TickEvents) -> bool {
+        if self.every.is_none() && self.events.is_empty() {
+            return true;
+        }
+
+        let on_interval = self.every.is_some_and(|n| recorded % n.max(1) == 0);
+        let on_event = self.events.iter().any(|kind| events.has(*kind));
+
+        on_interval || on_event
+    }
+}
+
+/// Called by [`parse_demo_with_hook`] with the live tick state, so that data
+/// can be gathered in a single pass without keeping every tick.
+pub struct TickHook<'a> {
+    pub filter: TickFilter,
+    pub callback: Box<dyn FnMut(&TickData, TickEvents) -> Result<(), ParseWorkerError> + Send + 'a>,
+}
+
+pub fn parse_demo(
+    fpath: PathBuf,
+    options: &ParseOptions,
+    progress_reporter: impl FnMut(ParseProgressReport) -> Result<(), ParseWorkerError>
+) {
+    parse_demo_with_hook(fpath, options, None, progress_reporter)
+}
+
+pub fn parse_demo_with_hook(
+    fpath: PathBuf,
+    options: &ParseOptions,
+    hook: Option<TickHook<'_>>,
+    progress_reporter: impl FnMut(ParseProgressReport) -> Result<(), ParseWorkerError>
+) {
+    parse_demo_with_checkpoint(fpath, options, hook, || Ok(()), progress_reporter)
+}
+
+/// Like [`parse_demo_with_hook`], but calls `checkpoint` before every tick,
+/// whether or not it gets reported, and stops the parse if it errors.
+fn parse_demo_with_checkpoint(
+    fpath: PathBuf,
+    options: &ParseOptions,
+    mut hook: Option<TickHook<'_>>,
+    mut checkpoint: impl FnMut() -> Result<(), ParseWorkerError>,
+    mut progress_reporter: impl FnMut(ParseProgressReport) -> Result<(), ParseWorkerError>
+) {
     use ParseProgressReport::*;
     let mut error_catch = || -> Result<(), ParseWorkerError> {
         let mut seen_zero = false;
@@ -106,8 +253,10 @@ pub fn parse_demo(fpath: PathBuf, mut progress_reporter: impl FnMut(ParseProgres
         progress_reporter(Info(header.ticks))?;
 
         let mut draw_data = ParseDrawInfo::default();
+        let mut recorded = 0;
 
         while ticker.tick()? {
+            checkpoint()?;
             let state = ticker.state();
             if state.data.tick <= 10 {
                 seen_zero = true;
@@ -117,17 +266,49 @@ pub fn parse_demo(fpath: PathBuf, mut progress_reporter: impl FnMut(ParseProgres
                 progress_reporter(Working(u32::from(state.data.tick)))?;
             }
 
+            let tick = u32::from(state.data.tick);
+            if options.is_finished(tick, &state.rounds) {
+                log::debug!("Stopping parse early at tick {}", tick);
+                break;
+            }
+
             // Update the rounds in our results. Copy is just simpler.
             result_data.rounds = state.rounds.clone();
-            result_data.kills.extend(state.kills.clone());
-            result_data.point_captures.extend(state.captures.clone());
-            result_data.tick_states.insert(u32::from(state.data.tick), state.data.clone());
 
             // Update draw data
             // TODO: Max projectiles
             if let Some(world) = &state.world {
                 draw_data.world_max = draw_data.world_max.adjoin_bounds(world);
             }
+
+            if !options.records_tick(tick, &state.rounds) {
+                continue;
+            }
+
+            if let Some(hook) = &mut hook {
+                let events = TickEvents {
+                    kills: state.kills.clone(),
+                    captures: state.captures.clone(),
+                    ubercharges: state.ubercharges.clone(),
+                    damage: state.damage.clone(),
+                    heals: state.heals.clone(),
+                    spawns: state.spawns.clone(),
+                };
+                if hook.filter.matches(recorded, &events) {
+                    (hook.callback)(&state.data, events)?;
+                }
+            }
+            recorded += 1;
+
+            result_data.kills.extend(state.kills.clone());
+            result_data.point_captures.extend(state.captures.clone());
+            result_data.ubercharges.extend(state.ubercharges.clone());
+            result_data.damage.extend(state.damage.clone());
+            result_data.heals.extend(state.heals.clone());
+            result_data.spawns.extend(state.spawns.clone());
+            if !options.discard_ticks {
+                result_data.tick_states.insert(tick, state.data.clone());
+            }
                     
             for player in &state.data.players {
                 draw_data.player_at_max.stretch_to_include(player.position);
@@ -135,6 +316,19 @@ pub fn parse_demo(fpath: PathBuf, mut progress_reporter: impl FnMut(ParseProgres
             }
         }
 
+        // Everyone who connected up to where the parse stopped
+        result_data.identities = ticker.state().identities.clone();
+
+        // Only keep the rounds that were asked for so round_data lines up
+        if let Some(selected) = &options.rounds {
+            result_data.rounds = result_data.rounds
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| selected.contains(i))
+                .map(|(_, round)| *round)
+                .collect();
+        }
+
         // deliberate lack of ?
         // we want the done() to be the last thing we could potentially send
         progress_reporter(Done(result_data, draw_data)).map_err(|e| ParseWorkerError::from(e))
@@ -147,14 +341,21 @@ pub fn parse_demo(fpath: PathBuf, mut progress_reporter: impl FnMut(ParseProgres
 
 impl ParseWorker {
     pub fn new(fpath: PathBuf) -> std::io::Result<Self> {
+        Self::with_options(fpath, ParseOptions::default())
+    }
+
+    pub fn with_options(fpath: PathBuf, options: ParseOptions) -> std::io::Result<Self> {
         log::debug!("Beginning multithreaded parse: {:?}", fpath.clone());
 
         let (prog_send, prog_recv) = mpsc::channel::<ParseProgressReport>();
-
-        let handle = thread::spawn(move || parse_demo(
-            fpath, |report| {
-                prog_send.send(report)?; Ok(())
-            }
+        let (instruct_send, instruct_recv) = mpsc::channel::<ParseInstruction>();
+
+        // Instructions are followed on every tick, as reports only start
+        // once the parse sees tick 0 and some demos never do
+        let handle = thread::spawn(move || parse_demo_with_checkpoint(
+            fpath, &options, None,
+            || follow_instructions(&instruct_recv),
+            |report| {prog_send.send(report)?; Ok(())}
         ));
 
         // let handle = thread::spawn(move || {
@@ -223,11 +424,33 @@ impl ParseWorker {
         // });
 
         Ok(ParseWorker {
-            handle,
-            prog_recv
+            handle: Some(handle),
+            prog_recv,
+            instruct_send,
+            paused: false,
         })
     }
 
+    /// Stops the parse. The last report will be an `Error(Cancelled)`.
+    pub fn cancel(&self) {
+        // If the thread is already gone there's nothing to cancel
+        let _ = self.instruct_send.send(ParseInstruction::Cancel);
+    }
+
+    pub fn pause(&mut self) {
+        let _ = self.instruct_send.send(ParseInstruction::Pause);
+        self.paused = true;
+    }
+
+    pub fn resume(&mut self) {
+        let _ = self.instruct_send.send(ParseInstruction::Resume);
+        self.paused = false;
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
     
     #[allow(dead_code)]
     pub fn get_next(&self) -> Option<ParseProgressReport> {
@@ -239,6 +462,11 @@ impl ParseWorker {
         }
     }
 
+    /// Blocks until the next report arrives, or until `timeout` has passed.
+    pub fn wait_next(&self, timeout: Duration) -> Result<ParseProgressReport, mpsc::RecvTimeoutError> {
+        self.prog_recv.recv_timeout(timeout)
+    }
+
     pub fn get_most_recent(&self) -> Option<ParseProgressReport> {
         let mut last: Option<ParseProgressReport> = None;
         
@@ -255,6 +483,40 @@ impl ParseWorker {
     }
 }
 
+impl Drop for ParseWorker {
+    fn drop(&mut self) {
+        self.cancel();
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+/// Called by the worker thread before each tick. Returns an error if the
+/// parse should stop, and blocks for as long as the parse is paused.
+/// A dropped [`ParseWorker`] counts as a cancel.
+fn follow_instructions(instruct_recv: &mpsc::Receiver<ParseInstruction>) -> Result<(), ParseWorkerError> {
+    use ParseInstruction::*;
+    let mut paused = false;
+    loop {
+        let instruction = if paused {
+            instruct_recv.recv().unwrap_or(Cancel)
+        } else {
+            match instruct_recv.try_recv() {
+                Ok(instruction) => instruction,
+                Err(mpsc::TryRecvError::Empty) => return Ok(()),
+                Err(mpsc::TryRecvError::Disconnected) => Cancel,
+            }
+        };
+
+        match instruction {
+            Cancel => return Err(ParseWorkerError::Cancelled),
+            Pause => paused = true,
+            Resume => paused = false,
+        }
+    }
+}
+
 
 
 
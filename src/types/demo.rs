@@ -1,16 +1,48 @@
+//! Contains the structures for DemoData and TickData.
+//!
+//! DemoData contains one of the following:
+//!
+//! 1) Information on the entire demo file. If this is the case,
+//!    `DemoData.rounds` will contain multiple rounds inside of it and may
+//!    contain ticks that are not a part of an actual round of gameplay.
+//!
+//! OR
+//!
+//! 2) Information on a single round in the demo. In this case, `rounds` will
+//!    only contain one round. The map name, demo file name, duration, and
+//!    player reach bounds will be the same as the whole demo. Note that the
+//!    duration is NOT the duration of the round in time in this case!
 
 use std::collections::HashMap;
 
 use itertools::Itertools;
+use numpy::{IntoPyArray, PyArray1, PyArray2, PyArray3};
+use numpy::ndarray::{Array2, Array3};
+use pyo3::exceptions::PyValueError;
 use pyo3::prelude::*;
+use serde::{Serialize, Deserialize};
 
 use super::{DemoTick, EntityId};
 use super::entities::{
     Player, Sentry, Dispenser, Teleporter, Building, Projectile,
     Medigun, ProjectileType, BuildingClass
 };
-use super::events::{Kill, Capture};
+use super::events::{Kill, Capture, Ubercharge, Damage, Heal, Spawn};
+use super::frames;
 use super::game::{Round, World};
+use super::identity::IdentityMap;
+use crate::analysis::{self, TickAnalysisData};
+use crate::analysis::data::TickAnalysisPy;
+use crate::analysis::grouping::{GroupingConfig, GroupTracker};
+use crate::analysis::fights::{self, Fight, FightConfig};
+use crate::analysis::timeline::{self, RoundTimeline};
+use crate::analysis::critheal::{self, CritHealReport};
+use crate::analysis::healing::{self, MedicReport};
+use crate::analysis::heatmap::{self, Heatmap, HeatmapFilter, HeatmapKind};
+use crate::analysis::zones::{self, Zone, ZoneReport};
+use crate::analysis::respawns::{self, RespawnReport};
+use crate::analysis::medicdeaths::{self, MedicDeathConfig, MedicDeathReport};
+use crate::analysis::rating::{self, RatingModel, RatingReport};
 
 /// This function is used by lib.rs, but the IDE thinks it's unused.
 #[allow(dead_code)]
@@ -25,10 +57,13 @@ pub(crate) fn get_submod<'a>(
     Ok(module)
 }
 
-#[pyclass]
-#[derive(Default, Debug, Clone)]
+/// Contains information about the state of a tick.
+#[pyclass(module = "tf2dal.demo")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct TickData {
     // Formerly gamestate internals
+
+    /// List of all players that have ever been seen up until this tick.
     #[pyo3(get)]
     pub players: Vec<Player>,
     pub projectiles: HashMap<u32, Projectile>,
@@ -36,8 +71,9 @@ pub struct TickData {
     pub mediguns: HashMap<u32, Medigun>,
     pub tick: DemoTick,
 
+    /// Amount of actual seconds this tick lasted.
     #[pyo3(get)]
-    pub tick_delta: f32,    // This is the amount of time (in seconds) that passed after the previous tick.
+    pub tick_delta: f32,
 }
 
 impl TickData {
@@ -120,6 +156,18 @@ impl TickData {
             .find(|p| p.entity == entity_id)
     }
 
+    /// (medic, patient) for every living medic healing a living player.
+    pub fn heal_targets(&self) -> Vec<(&Player, &Player)> {
+        self.mediguns.values()
+            .filter(|gun| gun.is_healing && !gun.is_holstered)
+            .filter_map(|gun| Some((
+                self.get_player_by_entityid(gun.owner)?,
+                self.get_player_by_entityid(gun.heal_target)?,
+            )))
+            .filter(|(medic, patient)| medic.is_alive() && patient.is_alive())
+            .collect()
+    }
+
     pub fn mut_player_by_entityid(&mut self, entity_id: u32) -> Option<&mut Player> {
         self.players
             .iter_mut()
@@ -130,6 +178,11 @@ impl TickData {
 
 #[pymethods]
 impl TickData {
+    fn __repr__(&self) -> String {
+        format!("TickData(tick={}, players={}, projectiles={}, buildings={})",
+            u32::from(self.tick), self.players.len(), self.projectiles.len(), self.buildings.len())
+    }
+
     #[staticmethod]
     fn empty_player() -> Player {
         Player::default()
@@ -171,16 +224,25 @@ impl TickData {
         hash
     }
 
+    /// Index of the tick.
     #[getter]
     fn tick(&self) -> u32 {
         u32::from(self.tick)
     }
 
+    /// List of all mediguns that have ever been seen up until this tick.
     #[getter]
     pub fn mediguns(&self) -> Vec<Medigun> {
         self.mediguns.values().cloned().collect_vec()
     }
 
+    /// Team averages, medic distances and groupings for this tick.
+    #[pyo3(signature = (config=None))]
+    pub fn analyze(&self, config: Option<GroupingConfig>) -> TickAnalysisPy {
+        let config = config.unwrap_or_default();
+        TickAnalysisPy::from(&TickAnalysisData::new(self, &config, &mut GroupTracker::default()))
+    }
+
     #[pyo3(name="get_player_by_entityid")]
     pub fn py_get_player_by_entityid(&self, entity_id: u32) -> Option<Player> {
         self.players.iter().find(|p| p.entity == entity_id).cloned()
@@ -197,8 +259,20 @@ impl TickData {
 
 use std::path::PathBuf;
 
-#[pyclass(get_all)]
-#[derive(Default, Debug, Clone)]
+/// Contains either information for the whole demo,
+/// or information on a piece of the demo that contains multiple
+/// tick states (e.g. a round).
+///
+/// The player arrays are each shaped (ticks, players, ...). Rows are in the
+/// order of `tick_index()` and columns are in the order of `player_index()`.
+/// Players that don't exist in a tick are NaN for float arrays, 0 otherwise.
+/// They are copied out of the tick states on every call, so keep the
+/// result rather than asking again.
+///
+/// In the DataFrames, Class and Team columns are categoricals. Ticks between
+/// rounds have a `round` of -1.
+#[pyclass(get_all, module = "tf2dal.demo")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DemoData {
     // General information about the demo
 
@@ -208,7 +282,10 @@ pub struct DemoData {
     /// The name of the map. Should end in .bsp.
     pub map_name: String,
 
-    /// Duration of the recording, in seconds.
+    /// The duration of the demo file in seconds.
+    /// If this DemoData is a single round of a demo file, then this
+    /// will not change! It will still be the duration of the entire demo,
+    /// not the duration of the round.
     pub duration: f32,
 
     /// Basic information for each round that occurred.
@@ -219,14 +296,30 @@ pub struct DemoData {
 
     pub point_captures: Vec<Capture>,
 
-    /// TODO: Point captures, blocks/defends, ubers
-    /// time spend on each class, etc.
-    /// TODO: world
+    /// Every ubercharge deployed in the game.
+    pub ubercharges: Vec<Ubercharge>,
+
+    /// Every time a player was hurt.
+    pub damage: Vec<Damage>,
+
+    /// Every time a player was healed.
+    pub heals: Vec<Heal>,
+
+    /// Every time a player spawned.
+    pub spawns: Vec<Spawn>,
+
+    /// The SteamID and names behind each user ID, for following players
+    /// across demos.
+    pub identities: IdentityMap,
+
+    // TODO: blocks/defends
+    // time spend on each class, etc.
+    // TODO: world
 
     /// The minimum and maximum X, Y, and Z values players ever had positions.
     pub player_reach_bounds: World,
 
-    /// Tick data. Key: DemoTick as u32, Value: TickGameState
+    /// The state of every recorded tick, by tick.
     pub tick_states: HashMap<u32, TickData>,
 }
 
@@ -237,6 +330,11 @@ pub struct DemoDataSlice<'a> {
     pub rounds: Vec<&'a Round>,
     pub kills: Vec<&'a Kill>,
     pub point_captures: Vec<&'a Capture>,
+    pub ubercharges: Vec<&'a Ubercharge>,
+    pub damage: Vec<&'a Damage>,
+    pub heals: Vec<&'a Heal>,
+    pub spawns: Vec<&'a Spawn>,
+    pub identities: &'a IdentityMap,
     pub player_reach_bounds: World,
     pub tick_states: HashMap<u32, &'a TickData>,
 }
@@ -245,18 +343,38 @@ impl DemoData {
     pub fn round_data<'a>(&'a self, round: &'a Round) -> DemoDataSlice<'a> {
         let tick_states = self.tick_states
             .iter()
-            .filter(|(tick, _)| **tick >= round.start_tick && **tick <= round.end_tick)
+            .filter(|(tick, _)| round.contains(**tick))
             .map(|(tickr, stater)| (*tickr, stater))
             .collect::<HashMap<u32, &TickData>>();
 
         let kills = self.kills
             .iter()
-            .filter(|kill| u32::from(kill.tick) >= round.start_tick && u32::from(kill.tick) <= round.end_tick)
+            .filter(|kill| round.contains(u32::from(kill.tick)))
             .collect();
 
         let point_captures = self.point_captures
             .iter()
-            .filter(|cap| cap.tick >= round.start_tick && cap.tick <= round.end_tick)
+            .filter(|cap| round.contains(cap.tick))
+            .collect();
+
+        let ubercharges = self.ubercharges
+            .iter()
+            .filter(|uber| round.contains(uber.tick))
+            .collect();
+
+        let damage = self.damage
+            .iter()
+            .filter(|hurt| round.contains(hurt.tick))
+            .collect();
+
+        let heals = self.heals
+            .iter()
+            .filter(|heal| round.contains(heal.tick))
+            .collect();
+
+        let spawns = self.spawns
+            .iter()
+            .filter(|spawn| round.contains(spawn.tick))
             .collect();
 
         DemoDataSlice {
@@ -266,10 +384,151 @@ impl DemoData {
             rounds: vec![&round],
             kills,
             point_captures,
+            ubercharges,
+            damage,
+            heals,
+            spawns,
+            identities: &self.identities,
             player_reach_bounds: self.player_reach_bounds.clone(),
             tick_states
         }
     }
+
+    /// Every tick that has a tick state, in ascending order.
+    /// This is the order of the first axis of the player arrays.
+    pub fn sorted_ticks(&self) -> Vec<u32> {
+        self.tick_states.keys().copied().sorted().collect_vec()
+    }
+
+    /// The player with `user_id` at `tick`, if that tick was recorded.
+    pub fn player_at(&self, tick: u32, user_id: u16) -> Option<&Player> {
+        self.tick_states.get(&tick).and_then(|state| state.get_player_by_userid(user_id))
+    }
+
+    /// Ticks per second, from the tick states. Assumes 66.67 without any.
+    pub fn tick_rate(&self) -> f32 {
+        self.tick_states.values()
+            .find(|state| state.tick_delta > 0.0)
+            .map_or(66.67, |state| 1.0 / state.tick_delta)
+    }
+
+    /// Writes the parse to `path` as bincode, so it can be loaded again
+    /// without parsing the demo.
+    pub fn save(&self, path: &std::path::Path) -> std::io::Result<()> {
+        let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+        bincode::serialize_into(file, self)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))
+    }
+
+    /// Reads a parse written by `save`. Parses saved by another version of
+    /// the library may fail with `InvalidData`.
+    pub fn load(path: &std::path::Path) -> std::io::Result<Self> {
+        let file = std::io::BufReader::new(std::fs::File::open(path)?);
+        bincode::deserialize_from(file)
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::InvalidData, err))
+    }
+
+    /// The user ID of every player seen in the demo, in ascending order.
+    /// This is the order of the second axis of the player arrays.
+    pub fn player_user_ids(&self) -> Vec<u16> {
+        self.tick_states
+            .values()
+            .flat_map(|state| state.players.iter())
+            .filter_map(|player| player.info.as_ref().map(|info| info.user_id))
+            .unique()
+            .sorted()
+            .collect_vec()
+    }
+
+    /// The rows and columns of the player arrays.
+    fn player_grid(&self) -> PlayerGrid {
+        let user_ids = self.player_user_ids();
+        PlayerGrid {
+            ticks: self.sorted_ticks(),
+            columns: user_ids.iter().enumerate().map(|(col, user_id)| (*user_id, col)).collect(),
+            players: user_ids.len(),
+        }
+    }
+
+    /// (ticks, players, 3) array of XYZ positions.
+    /// Players that aren't in a tick state are NaN.
+    pub fn player_position_array(&self) -> Array3<f32> {
+        let grid = self.player_grid();
+        let (ticks, players) = grid.shape();
+        let mut positions = Array3::<f32>::from_elem((ticks, players, 3), f32::NAN);
+        grid.for_each_cell(self, |row, col, player| {
+            positions[[row, col, 0]] = player.position.x;
+            positions[[row, col, 1]] = player.position.y;
+            positions[[row, col, 2]] = player.position.z;
+        });
+        positions
+    }
+
+    /// (ticks, players) array of health. Players that aren't in a tick state are 0.
+    pub fn player_health_array(&self) -> Array2<u16> {
+        let grid = self.player_grid();
+        let mut health = Array2::<u16>::zeros(grid.shape());
+        grid.for_each_cell(self, |row, col, player| health[[row, col]] = player.health);
+        health
+    }
+
+    /// (ticks, players) array of time since last hurt, in seconds.
+    /// Players that aren't in a tick state are NaN.
+    pub fn player_time_since_hurt_array(&self) -> Array2<f32> {
+        let grid = self.player_grid();
+        let mut times = Array2::<f32>::from_elem(grid.shape(), f32::NAN);
+        grid.for_each_cell(self, |row, col, player| times[[row, col]] = player.time_since_last_hurt);
+        times
+    }
+
+    /// (ticks, players) array of [`Team`](super::game::Team) values.
+    pub fn player_team_array(&self) -> Array2<u8> {
+        let grid = self.player_grid();
+        let mut teams = Array2::<u8>::zeros(grid.shape());
+        grid.for_each_cell(self, |row, col, player| teams[[row, col]] = player.team.into());
+        teams
+    }
+
+    /// (ticks, players) array of [`Class`](super::game::Class) values.
+    pub fn player_class_array(&self) -> Array2<u8> {
+        let grid = self.player_grid();
+        let mut classes = Array2::<u8>::zeros(grid.shape());
+        grid.for_each_cell(self, |row, col, player| classes[[row, col]] = player.class.into());
+        classes
+    }
+
+    /// (ticks, players) array that is true where the player is alive.
+    pub fn alive_mask_array(&self) -> Array2<bool> {
+        let grid = self.player_grid();
+        let mut alive = Array2::<bool>::from_elem(grid.shape(), false);
+        grid.for_each_cell(self, |row, col, player| alive[[row, col]] = player.is_alive());
+        alive
+    }
+}
+
+/// Which row each tick and which column each user ID is in the player arrays.
+struct PlayerGrid {
+    ticks: Vec<u32>,
+    columns: HashMap<u16, usize>,
+    players: usize,
+}
+
+impl PlayerGrid {
+    fn shape(&self) -> (usize, usize) {
+        (self.ticks.len(), self.players)
+    }
+
+    /// Calls `cell` with the (row, column) of every player in every tick state of `demo`.
+    /// Players without user info are skipped as they have no column.
+    fn for_each_cell(&self, demo: &DemoData, mut cell: impl FnMut(usize, usize, &Player)) {
+        for (row, tick) in self.ticks.iter().enumerate() {
+            for player in &demo.tick_states[tick].players {
+                if let Some(col) = player.info.as_ref().and_then(|info| self.columns.get(&info.user_id)) {
+                    cell(row, *col, player);
+                }
+            }
+        }
+    }
 }
 
 impl From<DemoDataSlice<'_>> for DemoData {
@@ -281,6 +540,11 @@ impl From<DemoDataSlice<'_>> for DemoData {
             rounds: value.rounds.iter().map(|r| (*r).clone()).collect_vec(),
             kills: value.kills.iter().map(|r| (*r).clone()).collect_vec(),
             point_captures: value.point_captures.clone().into_iter().cloned().collect(),
+            ubercharges: value.ubercharges.iter().map(|u| (*u).clone()).collect(),
+            damage: value.damage.iter().map(|d| (*d).clone()).collect(),
+            heals: value.heals.iter().map(|h| (*h).clone()).collect(),
+            spawns: value.spawns.iter().map(|s| (*s).clone()).collect(),
+            identities: value.identities.clone(),
             player_reach_bounds: value.player_reach_bounds,
             tick_states: value.tick_states.iter().map(|(t,s)| (*t, (*s).clone())).collect()
         }
@@ -289,17 +553,202 @@ impl From<DemoDataSlice<'_>> for DemoData {
 
 #[pymethods]
 impl DemoData {
+    fn __repr__(&self) -> String {
+        format!("DemoData(demo_filename={:?}, map_name={:?}, ticks={}, rounds={}, kills={})",
+            self.demo_filename, self.map_name, self.tick_states.len(), self.rounds.len(), self.kills.len())
+    }
+
     /// View data that has been limited to a single round.
     /// This DOES copy the data in the parse, so be wary.
+    /// Don't use this function if `len(rounds) < 2`!
     #[pyo3(name = "round_data")]
     fn py_round_data(&self, round: &Round) -> DemoData {
         self.round_data(round).into()
     }
+
+    /// Writes the parse to `path`, to be read back with `DemoData.load`
+    /// or passed to `load_season` in place of the demo.
+    #[pyo3(name = "save")]
+    fn py_save(&self, py: Python<'_>, path: PathBuf) -> PyResult<()> {
+        Ok(py.allow_threads(|| self.save(&path))?)
+    }
+
+    /// Reads a parse written by `DemoData.save`.
+    #[staticmethod]
+    #[pyo3(name = "load")]
+    fn py_load(py: Python<'_>, path: PathBuf) -> PyResult<DemoData> {
+        Ok(py.allow_threads(|| DemoData::load(&path))?)
+    }
+
+    /// `TickData.analyze` for every tick, or only the ticks of `round`,
+    /// in tick order. Groups are followed from tick to tick within a round.
+    #[pyo3(signature = (round=None, config=None))]
+    fn analyze(&self, py: Python<'_>, round: Option<Round>, config: Option<GroupingConfig>) -> Vec<TickAnalysisPy> {
+        let config = config.unwrap_or_default();
+        py.allow_threads(|| {
+            analysis::analyze_ticks(self, round.as_ref(), &config)
+                .iter()
+                .map(TickAnalysisPy::from)
+                .collect()
+        })
+    }
+
+    /// The fights in `round`, or in every round, in the order they started.
+    /// Needs the tick states, so it is always empty with `store_ticks=False`.
+    #[pyo3(signature = (round=None, config=None))]
+    fn fights(&self, py: Python<'_>, round: Option<Round>, config: Option<FightConfig>) -> Vec<Fight> {
+        let config = config.unwrap_or_default();
+        py.allow_threads(|| fights::detect_fights(self, round.as_ref(), &config))
+    }
+
+    /// Alive counts, man advantages, trades and the first pick of `round`.
+    /// A kill is traded if its attacker dies within `trade_window` seconds.
+    #[pyo3(signature = (round, trade_window=5.0))]
+    fn timeline(&self, py: Python<'_>, round: Round, trade_window: f32) -> RoundTimeline {
+        py.allow_threads(|| timeline::round_timeline(self, &round, trade_window))
+    }
+
+    /// How crit heals were built up, used and wasted, by player and by medic,
+    /// over the demo or only `round`.
+    #[pyo3(signature = (round=None))]
+    fn critheals(&self, py: Python<'_>, round: Option<Round>) -> CritHealReport {
+        py.allow_threads(|| critheal::critheal_report(self, round.as_ref()))
+    }
+
+    /// Each medic's heal targets over the demo or only `round`, with how
+    /// their healing was spread over classes and patients.
+    #[pyo3(signature = (round=None))]
+    fn medic_heals(&self, py: Python<'_>, round: Option<Round>) -> Vec<MedicReport> {
+        py.allow_threads(|| healing::medic_reports(self, round.as_ref()))
+    }
+
+    /// A `kind` heatmap over the player reach bounds, with square cells
+    /// `cell_size` units wide, of only what passes `filter`. Empty with
+    /// `store_ticks=False`.
+    #[pyo3(signature = (kind=HeatmapKind::Occupancy, filter=None, cell_size=64.0))]
+    fn heatmap(&self, py: Python<'_>, kind: HeatmapKind, filter: Option<HeatmapFilter>, cell_size: f32) -> PyResult<Heatmap> {
+        if !cell_size.is_finite() || cell_size <= 0.0 {
+            return Err(PyValueError::new_err("cell_size must be above 0"));
+        }
+        let filter = filter.unwrap_or_default();
+        Ok(py.allow_threads(|| heatmap::heatmap(self, kind, &filter, cell_size)))
+    }
+
+    /// Which of `zones` each player was in, how long each team held them,
+    /// and where each kill happened, over the demo or only `round`.
+    /// `tf2dal.analysis.load_zones()` reads zones from the config.
+    #[pyo3(signature = (zones, round=None))]
+    fn zone_occupancy(&self, py: Python<'_>, zones: Vec<Zone>, round: Option<Round>) -> ZoneReport {
+        py.allow_threads(|| zones::zone_report(self, &zones, round.as_ref()))
+    }
+
+    /// How long each death waited to respawn, the respawn waves of each
+    /// team, and how long teams took to recover from wipes, over every
+    /// round or only `round`. Only needs the events.
+    #[pyo3(signature = (round=None))]
+    fn respawns(&self, py: Python<'_>, round: Option<Round>) -> RespawnReport {
+        py.allow_threads(|| respawns::respawn_report(self, round.as_ref()))
+    }
+
+    /// The charge, nearest teammate and enemies, last damage and last uber of
+    /// every medic that died, over every round or only `round`. Positions and
+    /// charge need the tick states, and are None with `store_ticks=False`.
+    #[pyo3(signature = (round=None, config=None))]
+    fn medic_deaths(&self, py: Python<'_>, round: Option<Round>, config: Option<MedicDeathConfig>) -> MedicDeathReport {
+        let config = config.unwrap_or_default();
+        py.allow_threads(|| medicdeaths::medic_death_report(self, round.as_ref(), &config))
+    }
+
+    /// Each player's impact rating over the demo and each round, with the
+    /// breakdown of every class they played. Uses `RatingModel()` unless
+    /// `model` is given. Only needs the events.
+    #[pyo3(signature = (model=None))]
+    fn ratings(&self, py: Python<'_>, model: Option<RatingModel>) -> RatingReport {
+        let model = model.unwrap_or_default();
+        py.allow_threads(|| rating::rating_report(self, &model))
+    }
+
+    /// The tick of each row of the player arrays, ascending.
+    fn tick_index<'py>(&self, py: Python<'py>) -> &'py PyArray1<u32> {
+        self.sorted_ticks().into_pyarray(py)
+    }
+
+    /// The user ID of each column of the player arrays, ascending.
+    fn player_index<'py>(&self, py: Python<'py>) -> &'py PyArray1<u16> {
+        self.player_user_ids().into_pyarray(py)
+    }
+
+    /// (ticks, players, 3) array of XYZ positions.
+    fn player_positions<'py>(&self, py: Python<'py>) -> &'py PyArray3<f32> {
+        self.player_position_array().into_pyarray(py)
+    }
+
+    /// (ticks, players) array of health.
+    fn player_health<'py>(&self, py: Python<'py>) -> &'py PyArray2<u16> {
+        self.player_health_array().into_pyarray(py)
+    }
+
+    /// (ticks, players) array of `Player.time_since_last_hurt`.
+    fn player_time_since_hurt<'py>(&self, py: Python<'py>) -> &'py PyArray2<f32> {
+        self.player_time_since_hurt_array().into_pyarray(py)
+    }
+
+    /// (ticks, players) array of Team values.
+    fn player_teams<'py>(&self, py: Python<'py>) -> &'py PyArray2<u8> {
+        self.player_team_array().into_pyarray(py)
+    }
+
+    /// (ticks, players) array of Class values.
+    fn player_classes<'py>(&self, py: Python<'py>) -> &'py PyArray2<u8> {
+        self.player_class_array().into_pyarray(py)
+    }
+
+    /// (ticks, players) array that is True where the player is alive.
+    fn alive_mask<'py>(&self, py: Python<'py>) -> &'py PyArray2<bool> {
+        self.alive_mask_array().into_pyarray(py)
+    }
+
+    /// One row per player per tick. Requires pandas.
+    fn to_dataframe<'py>(&self, py: Python<'py>) -> PyResult<&'py PyAny> {
+        frames::demo_dataframe(py, self)
+    }
+
+    /// One row per kill, with the class and team of the victim and attacker.
+    fn kills_to_dataframe<'py>(&self, py: Python<'py>) -> PyResult<&'py PyAny> {
+        frames::kills_dataframe(py, &self.kills, Some(self))
+    }
+
+    /// One row per point capture.
+    fn captures_to_dataframe<'py>(&self, py: Python<'py>) -> PyResult<&'py PyAny> {
+        frames::captures_dataframe(py, &self.point_captures)
+    }
+
+    /// One row per ubercharge, with the team of the medic.
+    fn ubercharges_to_dataframe<'py>(&self, py: Python<'py>) -> PyResult<&'py PyAny> {
+        frames::ubercharges_dataframe(py, &self.ubercharges, Some(self))
+    }
+
+    /// One row per time a player was hurt, with the class and team of the
+    /// victim and attacker.
+    fn damage_to_dataframe<'py>(&self, py: Python<'py>) -> PyResult<&'py PyAny> {
+        frames::damage_dataframe(py, &self.damage, Some(self))
+    }
+
+    /// One row per time a player was healed, with the class and team of the
+    /// patient and healer.
+    fn heals_to_dataframe<'py>(&self, py: Python<'py>) -> PyResult<&'py PyAny> {
+        frames::heals_dataframe(py, &self.heals, Some(self))
+    }
+
+    /// One row per spawn, with where the player spawned.
+    fn spawns_to_dataframe<'py>(&self, py: Python<'py>) -> PyResult<&'py PyAny> {
+        frames::spawns_dataframe(py, &self.spawns, Some(self))
+    }
 }
 
-// Post-game summary of a player
-#[pyclass(get_all)]
-#[derive(Default, Debug, Clone)]
+/// Post-game summary of a player.
+#[pyclass(get_all, module = "tf2dal.demo")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PlayerSummary {
     pub kills: u32,
     pub assists: u32,
@@ -309,4 +758,8 @@ pub struct PlayerSummary {
     pub defenses: u32,
     //pub 
     pub user_id: u16,
-}
\ No newline at end of file
+}
+
+py_pickle!(TickData, DemoData, PlayerSummary);
+py_eq!(TickData, DemoData, PlayerSummary);
+py_debug_repr!(PlayerSummary);
@@ -1,11 +1,14 @@
-use itertools::Itertools;
-/// entities.rs
-/// 
-/// Essentially re-creates the types from tf_demo_parser's analysers
-/// in a way that they can be interfaced through python. Most of this code
-/// is basically copied.
+//! Contains definitions for each entity.
+//! An entity is (pretty much) any game object, including players, weapons, hats,
+//! objectives, and many more.
+
+// Essentially re-creates the types from tf_demo_parser's analysers
+// in a way that they can be interfaced through python. Most of this code
+// is basically copied.
 
+use itertools::Itertools;
 use pyo3::prelude::*;
+use serde::{Serialize, Deserialize};
 
 use num_enum::{TryFromPrimitive, IntoPrimitive};
 
@@ -37,14 +40,15 @@ use super::game::{Class, Team, ClassList};
 use tf_demo_parser::demo::parser::analyser::UserInfo as TFUinf;
 //use tf_demo_parser::demo::parser::gamestateanalyser::{Player as TFPlayer};
 
-#[pyclass(get_all)]
-#[derive(Debug, Clone)]
+#[pyclass(get_all, module = "tf2dal.entities")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UserInfo {
     pub classes: ClassList,
     pub name: String,
     pub user_id: u16,
     pub steam_id: String,
     pub entity_id: u32,
+    /// Same as `Player.team`.
     pub team: Team,
 }
 
@@ -97,8 +101,9 @@ impl PartialEq for UserInfo {
     }
 }
 
-#[pyclass]
-#[derive(Debug, Clone, Copy, PartialEq, TryFromPrimitive, IntoPrimitive, Default)]
+/// Life state of a player. Just use `Player.is_alive`.
+#[pyclass(module = "tf2dal.entities")]
+#[derive(Debug, Clone, Copy, PartialEq, TryFromPrimitive, IntoPrimitive, Default, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum PlayerState {
     #[default]
@@ -121,8 +126,9 @@ impl PlayerState {
     }
 }
 
-#[pyclass]
-#[derive(Debug, Clone, Default)]
+/// All properties of players that are tracked.
+#[pyclass(module = "tf2dal.entities")]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct Player {
     pub(crate) entity: u32,
     #[pyo3(get)]
@@ -131,14 +137,29 @@ pub struct Player {
     pub health: u16,
     #[pyo3(get)]
     pub max_health: u16,
+    /// What class they're currently playing.
     #[pyo3(get, name="player_class")]
     pub class: Class,
+    /// The team the player is on. Can be any of:
+    ///
+    /// - Other (0): Used for non-player entities.
+    /// - Spectator (1)
+    /// - Red (2)
+    /// - Blue (3)
     #[pyo3(get)]
     pub team: Team,
+    /// Part of the direction that the player is looking.
+    /// This specific measure is the angle from the positive x
+    /// axis, moving counter-clockwise, that the player is facing.
+    /// Should range from 0 to 2pi, or maybe -pi to pi.
     #[pyo3(get)]
     pub view_angle: f32,
+    /// The angle the player is looking up and down.
+    /// I believe this is measured with 0 looking neither up or down,
+    /// and ranging from -pi/2 to pi/2. Could also range from 0 to pi.
     #[pyo3(get)]
     pub pitch_angle: f32,
+    /// Just use is_alive unless you really need to know.
     #[pyo3(get)]
     pub state: PlayerState,
 
@@ -274,10 +295,13 @@ impl Player {
         self.position.dist_to(&other.position)
     }
 
+    /// Distance, but only taking into account X and Y axes.
     pub fn distance_from_xy(&self, other: &Player) -> f32 {
         self.position.xy().dist_to(&other.position.xy())
     }
 
+    /// Height difference between two players.
+    /// Negative means self is below other.
     pub fn height_diff(&self, other: &Player) -> f32 {
         self.position.z - other.position.z
     }
@@ -291,14 +315,19 @@ impl Player {
     }
 }
 
-#[pyclass(get_all)]
-#[derive(Default, Debug, Clone, Copy)]
+#[pyclass(get_all, module = "tf2dal.entities")]
+#[derive(Default, Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub struct Medigun {
     pub entity_id: u32,
+    /// The medigun owner's (wielder's) entity ID.
     pub owner: u32,
+    /// Ubercharge %.
     pub charge: f32,
+    /// The EntityID of the person being healed.
     pub heal_target: u32,
+    /// If the medic is actually healing.
     pub is_healing: bool,
+    /// If the medigun is currently put away.
     pub is_holstered: bool,
 }
 
@@ -311,40 +340,40 @@ impl Medigun {
     }
 }
 
-#[pyclass(get_all)]
-#[derive(Default, Debug, Clone)]
+#[pyclass(get_all, module = "tf2dal.entities")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ScoutInfo {}
-#[pyclass(get_all)]
-#[derive(Default, Debug, Clone)]
+#[pyclass(get_all, module = "tf2dal.entities")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SoldierInfo{}
-#[pyclass(get_all)]
-#[derive(Default, Debug, Clone)]
+#[pyclass(get_all, module = "tf2dal.entities")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PyroInfo{}
 
-#[pyclass(get_all)]
-#[derive(Default, Debug, Clone)]
+#[pyclass(get_all, module = "tf2dal.entities")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct DemomanInfo{}
-#[pyclass(get_all)]
-#[derive(Default, Debug, Clone)]
+#[pyclass(get_all, module = "tf2dal.entities")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct HeavyInfo{}
-#[pyclass(get_all)]
-#[derive(Default, Debug, Clone)]
+#[pyclass(get_all, module = "tf2dal.entities")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct EngineerInfo{}
-#[pyclass(get_all)]
-#[derive(Default, Debug, Clone)]
+#[pyclass(get_all, module = "tf2dal.entities")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct MedicInfo {
     pub is_healing: bool,
     pub heal_target: u32,
     pub last_heal_target: u32
 }
-#[pyclass(get_all)]
-#[derive(Default, Debug, Clone)]
+#[pyclass(get_all, module = "tf2dal.entities")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SniperInfo{}
-#[pyclass(get_all)]
-#[derive(Default, Debug, Clone)]
+#[pyclass(get_all, module = "tf2dal.entities")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct SpyInfo{}
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ClassInfo {
     Scout(ScoutInfo),
     Soldier(SoldierInfo),
@@ -379,8 +408,8 @@ impl ClassInfo {
 /// BUILDINGS
 /// /////////////////////////////////////////
 
-#[pyclass(get_all)]
-#[derive(Debug, Clone, PartialEq, Default)]
+#[pyclass(get_all, module = "tf2dal.entities")]
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct Sentry {
     pub entity: u32, // eid
     pub builder: u16, //uid
@@ -399,8 +428,8 @@ pub struct Sentry {
     pub is_mini: bool,
 }
 
-#[pyclass(get_all)]
-#[derive(Debug, Clone, PartialEq, Default)]
+#[pyclass(get_all, module = "tf2dal.entities")]
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct Dispenser {
     pub entity: u32,        // entity id
     pub builder: u16,       // user id
@@ -416,8 +445,8 @@ pub struct Dispenser {
     pub metal: u16,
 }
 
-#[pyclass(get_all)]
-#[derive(Debug, Clone, PartialEq, Default)]
+#[pyclass(get_all, module = "tf2dal.entities")]
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
 pub struct Teleporter {
     pub entity: u32,        // entity id
     pub builder: u16,       // user id
@@ -437,7 +466,7 @@ pub struct Teleporter {
     pub yaw_to_exit: f32,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum Building {
     Sentry(Sentry),
     Dispenser(Dispenser),
@@ -554,8 +583,8 @@ pub enum BuildingClass {
 /// /////////////////////////////////////////
 
 // TODO
-#[pyclass]
-#[derive(Default, Copy, Clone, Debug)]
+#[pyclass(module = "tf2dal.entities")]
+#[derive(Default, Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum ProjectileType {
     #[default]
     Unknown,
@@ -578,8 +607,8 @@ pub enum ProjectileType {
             - m_vecOrigin
 
  */
-#[pyclass]
-#[derive(Default, Copy, Clone, Debug)]
+#[pyclass(module = "tf2dal.entities")]
+#[derive(Default, Copy, Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub struct Projectile {
     #[allow(dead_code)]
     pub(in super::super) entity: u32,
@@ -598,4 +627,6 @@ impl Projectile {
     }
 }
 
-
+py_pickle!(UserInfo, PlayerState, Player, Medigun, Sentry, Dispenser, Teleporter, ProjectileType, Projectile);
+py_eq!(UserInfo, Player, Medigun, Sentry, Dispenser, Teleporter, Projectile);
+py_debug_repr!(UserInfo, Player, Medigun, Sentry, Dispenser, Teleporter, Projectile);
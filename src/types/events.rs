@@ -1,10 +1,18 @@
+//! Constructs that record important game events that occur during the demo.
+
 use pyo3::prelude::*;
+use serde::{Serialize, Deserialize};
 
-use super::{DemoTick, game::Team};
+use super::{DemoTick, game::{Class, Team}};
+use super::frames;
+use super::math::Vector;
 
 use tf_demo_parser::demo::gameevent_gen::{
     PlayerDeathEvent,
     PlayerChargeDeployedEvent,
+    PlayerHealedEvent,
+    PlayerHurtEvent,
+    PlayerSpawnEvent,
     TeamPlayPointCapturedEvent
 };
 
@@ -17,12 +25,18 @@ pub(crate) fn get_submod(
     module.add_class::<Kill>()?;
     module.add_class::<Capture>()?;
     module.add_class::<Ubercharge>()?;
+    module.add_class::<Damage>()?;
+    module.add_class::<Heal>()?;
+    module.add_class::<Spawn>()?;
+    module.add_class::<TickEvents>()?;
     Ok(module)
 }
 
-// Information surrounding a PlayerDeath event
-#[pyclass]
-#[derive(Debug, Clone, Default)]
+/// By God, he's dead!
+///
+/// In all seriousness, this is a record of a kill.
+#[pyclass(module = "tf2dal.events")]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct Kill {
     pub dead_id: u16,        // UserID who died
     pub dead_entity: u32,  // EntityID of the player who died
@@ -37,34 +51,41 @@ pub struct Kill {
 
     pub assister: Option<u16>,       // UserID that assisted in the kill
 
+    /// If the killed / dead player was rocket jumping when they died.
     #[pyo3(get)]
-    pub dead_rocketjumping: bool,// If the player that died was rocket jumping
+    pub dead_rocketjumping: bool,
 
     pub tick: DemoTick,
 }
 
 #[pymethods]
 impl Kill {
+    /// User ID of the killed / dead player.
     #[getter]
     fn dead_id(&self) -> PyResult<u16> {
         Ok(u16::from(self.dead_id))
     }
     
+    /// Entity ID of the killed / dead player.
     #[getter]
     fn dead_entity(&self) -> PyResult<u32> {
         Ok(u32::from(self.dead_entity))
     }
 
+    /// User ID of the player was responsible for the final blow.
+    /// May not be a valid ID if the player died to the environment.
     #[getter]
     fn attacker_id(&self) -> PyResult<u16> {
         Ok(u16::from(self.attacker_id))
     }
     
+    /// Entity ID of the thing responsible for the final blow. May not be a player.
     #[getter]
     fn inflictor_id(&self) -> PyResult<u32> {
         Ok(u32::from(self.inflictor_id))
     }
 
+    /// User ID of the assister, if anyone assisted.
     #[getter]
     fn assister_id(&self) -> PyResult<Option<u16>> {
         Ok(match self.assister {
@@ -77,6 +98,13 @@ impl Kill {
     fn tick(&self) -> PyResult<u32> {
         Ok(u32::from(self.tick))
     }
+
+    /// One row per kill. Use `DemoData.kills_to_dataframe` to also get the
+    /// class and team of everyone involved.
+    #[staticmethod]
+    fn to_dataframe(py: Python<'_>, kills: Vec<Kill>) -> PyResult<&PyAny> {
+        frames::kills_dataframe(py, &kills, None)
+    }
 }
 
 impl Kill {
@@ -102,17 +130,31 @@ impl Kill {
 
 // capture
 
-#[pyclass(get_all)]
-#[derive(Default, Debug, Clone)]
+#[pyclass(get_all, module = "tf2dal.events")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Capture {
+    /// The index of the capture point. 0 is typically blue last or the
+    /// midpoint in KOTH, with 4 being red last, but this is not guaranteed.
+    /// The index of each point will be in order though.
     pub cp_index: u8,
+    /// Name of the control point.
     pub cp_name: String,
+    /// The team that captured the point.
     pub team: Team,
-    // user ids
+    /// User IDs of who was standing on the point when it was captured.
     pub cappers: Vec<u16>,
+    /// When the capture occurred.
     pub tick: u32
 }
 
+#[pymethods]
+impl Capture {
+    #[staticmethod]
+    fn to_dataframe(py: Python<'_>, captures: Vec<Capture>) -> PyResult<&PyAny> {
+        frames::captures_dataframe(py, &captures)
+    }
+}
+
 impl Capture {
     pub fn from_event(tick: DemoTick, capture: &TeamPlayPointCapturedEvent) -> Self {
         let cappers: Vec<u16> = capture.cappers
@@ -132,14 +174,24 @@ impl Capture {
     }
 }
 
-#[pyclass(get_all)]
-#[derive(Default, Debug, Clone)]
+#[pyclass(get_all, module = "tf2dal.events")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct Ubercharge {
+    /// User ID of the medic that deployed.
     pub medic_id: u16,
+    /// User ID of the player the medic was healing when they deployed.
     pub ubered_id: u16,
     pub tick: u32,
 }
 
+#[pymethods]
+impl Ubercharge {
+    #[staticmethod]
+    fn to_dataframe(py: Python<'_>, ubercharges: Vec<Ubercharge>) -> PyResult<&PyAny> {
+        frames::ubercharges_dataframe(py, &ubercharges, None)
+    }
+}
+
 impl Ubercharge {
     pub fn from_event(tick: DemoTick, charge: &PlayerChargeDeployedEvent) -> Self {
         Ubercharge {
@@ -148,4 +200,168 @@ impl Ubercharge {
             tick: u32::from(tick),
         }
     }
-}
\ No newline at end of file
+}
+
+/// A player taking damage.
+#[pyclass(get_all, module = "tf2dal.events")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Damage {
+    /// User ID of the player that was hurt.
+    pub victim_id: u16,
+    /// User ID of the player that did the damage.
+    /// May not be a valid ID if the damage came from the environment.
+    pub attacker_id: u16,
+    pub amount: u16,
+    /// Health the victim had left afterwards.
+    pub health: u16,
+    pub crit: bool,
+    pub mini_crit: bool,
+    pub weapon_id: u16,
+    pub tick: u32,
+}
+
+#[pymethods]
+impl Damage {
+    #[staticmethod]
+    fn to_dataframe(py: Python<'_>, damage: Vec<Damage>) -> PyResult<&PyAny> {
+        frames::damage_dataframe(py, &damage, None)
+    }
+}
+
+impl Damage {
+    pub fn from_event(tick: DemoTick, hurt: &PlayerHurtEvent) -> Self {
+        Damage {
+            victim_id: hurt.user_id,
+            attacker_id: hurt.attacker,
+            amount: hurt.damage_amount,
+            health: hurt.health,
+            crit: hurt.crit,
+            mini_crit: hurt.mini_crit,
+            weapon_id: hurt.weapon_id,
+            tick: u32::from(tick),
+        }
+    }
+}
+
+/// A player being healed by a teammate.
+#[pyclass(get_all, module = "tf2dal.events")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Heal {
+    /// User ID of the player that was healed.
+    pub patient_id: u16,
+    /// User ID of the player that did the healing.
+    pub healer_id: u16,
+    pub amount: u16,
+    pub tick: u32,
+}
+
+#[pymethods]
+impl Heal {
+    #[staticmethod]
+    fn to_dataframe(py: Python<'_>, heals: Vec<Heal>) -> PyResult<&PyAny> {
+        frames::heals_dataframe(py, &heals, None)
+    }
+}
+
+impl Heal {
+    pub fn from_event(tick: DemoTick, healed: &PlayerHealedEvent) -> Self {
+        Heal {
+            patient_id: healed.patient,
+            healer_id: healed.healer,
+            amount: healed.amount,
+            tick: u32::from(tick),
+        }
+    }
+}
+
+/// A player spawning, at the start of a round or after dying.
+#[pyclass(module = "tf2dal.events")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Spawn {
+    #[pyo3(get)]
+    pub user_id: u16,
+    #[pyo3(get)]
+    pub team: Team,
+    #[pyo3(get, name="player_class")]
+    pub class: Class,
+    /// Where the player was at the end of the tick they spawned on.
+    #[pyo3(get)]
+    pub position: Vector,
+    #[pyo3(get)]
+    pub tick: u32,
+}
+
+#[pymethods]
+impl Spawn {
+    #[staticmethod]
+    fn to_dataframe(py: Python<'_>, spawns: Vec<Spawn>) -> PyResult<&PyAny> {
+        frames::spawns_dataframe(py, &spawns, None)
+    }
+}
+
+impl Spawn {
+    pub fn from_event(tick: DemoTick, spawn: &PlayerSpawnEvent, position: Vector) -> Self {
+        Spawn {
+            user_id: spawn.user_id,
+            team: Team::new(spawn.team),
+            class: Class::new(spawn.class),
+            position,
+            tick: u32::from(tick),
+        }
+    }
+}
+
+/// The kinds of event in [`TickEvents`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TickEventKind {
+    Kill,
+    Capture,
+    Ubercharge,
+    Damage,
+    Heal,
+    Spawn,
+}
+
+impl TickEventKind {
+    /// Accepts the singular or plural name, e.g. "kill" or "kills".
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().trim_end_matches('s') {
+            "kill" => Some(TickEventKind::Kill),
+            "capture" => Some(TickEventKind::Capture),
+            "ubercharge" => Some(TickEventKind::Ubercharge),
+            "damage" => Some(TickEventKind::Damage),
+            "heal" => Some(TickEventKind::Heal),
+            "spawn" => Some(TickEventKind::Spawn),
+            _ => None
+        }
+    }
+}
+
+/// Every event that happened during a single tick. Passed to `on_tick`.
+#[pyclass(get_all, module = "tf2dal.events")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TickEvents {
+    pub kills: Vec<Kill>,
+    pub captures: Vec<Capture>,
+    pub ubercharges: Vec<Ubercharge>,
+    pub damage: Vec<Damage>,
+    pub heals: Vec<Heal>,
+    pub spawns: Vec<Spawn>,
+}
+
+impl TickEvents {
+    pub fn has(&self, kind: TickEventKind) -> bool {
+        match kind {
+            TickEventKind::Kill => !self.kills.is_empty(),
+            TickEventKind::Capture => !self.captures.is_empty(),
+            TickEventKind::Ubercharge => !self.ubercharges.is_empty(),
+            TickEventKind::Damage => !self.damage.is_empty(),
+            TickEventKind::Heal => !self.heals.is_empty(),
+            TickEventKind::Spawn => !self.spawns.is_empty(),
+        }
+    }
+}
+
+py_pickle!(Kill, Capture, Ubercharge, Damage, Heal, Spawn, TickEvents);
+py_eq!(Kill, Capture, Ubercharge, Damage, Heal, Spawn, TickEvents);
+py_debug_repr!(Kill, Capture, Ubercharge, Damage, Heal, Spawn, TickEvents);
@@ -0,0 +1,269 @@
+//! Conversion of the data types into pandas DataFrames.
+//!
+//! Columns are built in Rust and handed to pandas all at once, so pandas has
+//! to be installed for any of these to work. Numeric columns keep their Rust
+//! dtypes, and Class/Team columns are categoricals over every possible value
+//! so that frames from different demos can be concatenated.
+
+use numpy::{Element, IntoPyArray};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+use super::demo::DemoData;
+use super::entities::Player;
+use super::events::{Capture, Damage, Heal, Kill, Spawn, Ubercharge};
+use super::game::{Class, Round, Team};
+
+fn pandas(py: Python<'_>) -> PyResult<&PyModule> {
+    py.import("pandas")
+}
+
+fn column<T: Element>(py: Python<'_>, values: Vec<T>) -> PyObject {
+    values.into_pyarray(py).into()
+}
+
+fn nullable_column(py: Python<'_>, values: Vec<Option<u16>>) -> PyResult<PyObject> {
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("dtype", "UInt16")?;
+    Ok(pandas(py)?.getattr("array")?.call((values,), Some(kwargs))?.into())
+}
+
+fn categorical(py: Python<'_>, values: Vec<&str>, categories: Vec<&str>) -> PyResult<PyObject> {
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("categories", categories)?;
+    Ok(pandas(py)?.getattr("Categorical")?.call((values,), Some(kwargs))?.into())
+}
+
+fn class_column(py: Python<'_>, classes: Vec<Class>) -> PyResult<PyObject> {
+    categorical(py,
+        classes.iter().map(Class::name).collect(),
+        Class::ALL.iter().map(Class::name).collect()
+    )
+}
+
+fn team_column(py: Python<'_>, teams: Vec<Team>) -> PyResult<PyObject> {
+    categorical(py,
+        teams.iter().map(Team::name).collect(),
+        Team::ALL.iter().map(Team::name).collect()
+    )
+}
+
+fn dataframe<'py>(py: Python<'py>, columns: Vec<(&str, PyObject)>) -> PyResult<&'py PyAny> {
+    // Dicts keep their insertion order, so this is also the column order.
+    let dict = PyDict::new(py);
+    for (name, values) in columns {
+        dict.set_item(name, values)?;
+    }
+    pandas(py)?.getattr("DataFrame")?.call1((dict,))
+}
+
+/// Index of the round the tick is in, or -1 if it is between rounds.
+fn round_index(rounds: &[Round], tick: u32) -> i32 {
+    rounds
+        .iter()
+        .position(|r| r.contains(tick))
+        .map_or(-1, |i| i as i32)
+}
+
+fn player_at<'a>(demo: &'a DemoData, tick: u32, user_id: u16) -> Option<&'a Player> {
+    demo.tick_states.get(&tick).and_then(|state| state.get_player_by_userid(user_id))
+}
+
+/// One row per player per tick, sorted by tick.
+pub fn demo_dataframe<'py>(py: Python<'py>, demo: &DemoData) -> PyResult<&'py PyAny> {
+    let mut ticks = Vec::new();
+    let mut rounds = Vec::new();
+    let mut user_ids = Vec::new();
+    let mut names = Vec::new();
+    let mut teams = Vec::new();
+    let mut classes = Vec::new();
+    let mut xs = Vec::new();
+    let mut ys = Vec::new();
+    let mut zs = Vec::new();
+    let mut health = Vec::new();
+    let mut max_health = Vec::new();
+    let mut alive = Vec::new();
+    let mut time_since_hurt = Vec::new();
+    let mut charge = Vec::new();
+
+    for tick in demo.sorted_ticks() {
+        let round = round_index(&demo.rounds, tick);
+        for player in &demo.tick_states[&tick].players {
+            if let Some(info) = &player.info {
+                ticks.push(tick);
+                rounds.push(round);
+                user_ids.push(info.user_id);
+                names.push(info.name.clone());
+                teams.push(player.team);
+                classes.push(player.class);
+                xs.push(player.position.x);
+                ys.push(player.position.y);
+                zs.push(player.position.z);
+                health.push(player.health);
+                max_health.push(player.max_health);
+                alive.push(player.is_alive());
+                time_since_hurt.push(player.time_since_last_hurt);
+                charge.push(player.charge);
+            }
+        }
+    }
+
+    dataframe(py, vec![
+        ("tick", column(py, ticks)),
+        ("round", column(py, rounds)),
+        ("user_id", column(py, user_ids)),
+        ("name", names.into_py(py)),
+        ("team", team_column(py, teams)?),
+        ("player_class", class_column(py, classes)?),
+        ("x", column(py, xs)),
+        ("y", column(py, ys)),
+        ("z", column(py, zs)),
+        ("health", column(py, health)),
+        ("max_health", column(py, max_health)),
+        ("alive", column(py, alive)),
+        ("time_since_last_hurt", column(py, time_since_hurt)),
+        ("charge", column(py, charge)),
+    ])
+}
+
+/// One row per kill. If `demo` is given, the class and team of the victim and
+/// attacker at the tick of the kill are added.
+pub fn kills_dataframe<'py>(py: Python<'py>, kills: &[Kill], demo: Option<&DemoData>) -> PyResult<&'py PyAny> {
+    let mut columns = vec![
+        ("tick", column(py, kills.iter().map(|k| u32::from(k.tick)).collect())),
+        ("dead_id", column(py, kills.iter().map(|k| k.dead_id).collect())),
+        ("dead_entity", column(py, kills.iter().map(|k| k.dead_entity).collect())),
+        ("attacker_id", column(py, kills.iter().map(|k| k.attacker_id).collect())),
+        ("assister_id", nullable_column(py, kills.iter().map(|k| k.assister).collect())?),
+        ("inflictor_id", column(py, kills.iter().map(|k| k.inflictor_id).collect())),
+        ("weapon", kills.iter().map(|k| k.weapon.clone()).collect::<Vec<String>>().into_py(py)),
+        ("weapon_id", column(py, kills.iter().map(|k| k.weapon_id).collect())),
+        ("dead_rocketjumping", column(py, kills.iter().map(|k| k.dead_rocketjumping).collect())),
+    ];
+
+    if let Some(demo) = demo {
+        let dead = kills.iter()
+            .map(|k| player_at(demo, u32::from(k.tick), k.dead_id))
+            .collect::<Vec<Option<&Player>>>();
+        let attacker = kills.iter()
+            .map(|k| player_at(demo, u32::from(k.tick), k.attacker_id))
+            .collect::<Vec<Option<&Player>>>();
+
+        columns.push(("round", column(py, kills.iter().map(|k| round_index(&demo.rounds, u32::from(k.tick))).collect())));
+        columns.push(("dead_team", team_column(py, dead.iter().map(|p| p.map_or(Team::Other, |p| p.team)).collect())?));
+        columns.push(("dead_class", class_column(py, dead.iter().map(|p| p.map_or(Class::Other, |p| p.class)).collect())?));
+        columns.push(("attacker_team", team_column(py, attacker.iter().map(|p| p.map_or(Team::Other, |p| p.team)).collect())?));
+        columns.push(("attacker_class", class_column(py, attacker.iter().map(|p| p.map_or(Class::Other, |p| p.class)).collect())?));
+    }
+
+    dataframe(py, columns)
+}
+
+/// One row per point capture. `cappers` is a list of user IDs.
+pub fn captures_dataframe<'py>(py: Python<'py>, captures: &[Capture]) -> PyResult<&'py PyAny> {
+    dataframe(py, vec![
+        ("tick", column(py, captures.iter().map(|c| c.tick).collect())),
+        ("cp_index", column(py, captures.iter().map(|c| c.cp_index).collect())),
+        ("cp_name", captures.iter().map(|c| c.cp_name.clone()).collect::<Vec<String>>().into_py(py)),
+        ("team", team_column(py, captures.iter().map(|c| c.team).collect())?),
+        ("cappers", captures.iter().map(|c| c.cappers.clone()).collect::<Vec<Vec<u16>>>().into_py(py)),
+    ])
+}
+
+/// One row per ubercharge. If `demo` is given, the team of the medic is added.
+pub fn ubercharges_dataframe<'py>(py: Python<'py>, ubercharges: &[Ubercharge], demo: Option<&DemoData>) -> PyResult<&'py PyAny> {
+    let mut columns = vec![
+        ("tick", column(py, ubercharges.iter().map(|u| u.tick).collect())),
+        ("medic_id", column(py, ubercharges.iter().map(|u| u.medic_id).collect())),
+        ("ubered_id", column(py, ubercharges.iter().map(|u| u.ubered_id).collect())),
+    ];
+
+    if let Some(demo) = demo {
+        columns.push(("round", column(py, ubercharges.iter().map(|u| round_index(&demo.rounds, u.tick)).collect())));
+        columns.push(("team", team_column(py, ubercharges.iter()
+            .map(|u| player_at(demo, u.tick, u.medic_id).map_or(Team::Other, |p| p.team))
+            .collect())?));
+    }
+
+    dataframe(py, columns)
+}
+
+/// One row per time a player was hurt. If `demo` is given, the class and team
+/// of the victim and attacker at the tick of the damage are added.
+pub fn damage_dataframe<'py>(py: Python<'py>, damage: &[Damage], demo: Option<&DemoData>) -> PyResult<&'py PyAny> {
+    let mut columns = vec![
+        ("tick", column(py, damage.iter().map(|d| d.tick).collect())),
+        ("victim_id", column(py, damage.iter().map(|d| d.victim_id).collect())),
+        ("attacker_id", column(py, damage.iter().map(|d| d.attacker_id).collect())),
+        ("amount", column(py, damage.iter().map(|d| d.amount).collect())),
+        ("health", column(py, damage.iter().map(|d| d.health).collect())),
+        ("crit", column(py, damage.iter().map(|d| d.crit).collect())),
+        ("mini_crit", column(py, damage.iter().map(|d| d.mini_crit).collect())),
+        ("weapon_id", column(py, damage.iter().map(|d| d.weapon_id).collect())),
+    ];
+
+    if let Some(demo) = demo {
+        let victim = damage.iter()
+            .map(|d| player_at(demo, d.tick, d.victim_id))
+            .collect::<Vec<Option<&Player>>>();
+        let attacker = damage.iter()
+            .map(|d| player_at(demo, d.tick, d.attacker_id))
+            .collect::<Vec<Option<&Player>>>();
+
+        columns.push(("round", column(py, damage.iter().map(|d| round_index(&demo.rounds, d.tick)).collect())));
+        columns.push(("victim_team", team_column(py, victim.iter().map(|p| p.map_or(Team::Other, |p| p.team)).collect())?));
+        columns.push(("victim_class", class_column(py, victim.iter().map(|p| p.map_or(Class::Other, |p| p.class)).collect())?));
+        columns.push(("attacker_team", team_column(py, attacker.iter().map(|p| p.map_or(Team::Other, |p| p.team)).collect())?));
+        columns.push(("attacker_class", class_column(py, attacker.iter().map(|p| p.map_or(Class::Other, |p| p.class)).collect())?));
+    }
+
+    dataframe(py, columns)
+}
+
+/// One row per time a player was healed. If `demo` is given, the class and
+/// team of the patient and healer at the tick of the heal are added.
+pub fn heals_dataframe<'py>(py: Python<'py>, heals: &[Heal], demo: Option<&DemoData>) -> PyResult<&'py PyAny> {
+    let mut columns = vec![
+        ("tick", column(py, heals.iter().map(|h| h.tick).collect())),
+        ("patient_id", column(py, heals.iter().map(|h| h.patient_id).collect())),
+        ("healer_id", column(py, heals.iter().map(|h| h.healer_id).collect())),
+        ("amount", column(py, heals.iter().map(|h| h.amount).collect())),
+    ];
+
+    if let Some(demo) = demo {
+        let patient = heals.iter()
+            .map(|h| player_at(demo, h.tick, h.patient_id))
+            .collect::<Vec<Option<&Player>>>();
+        let healer = heals.iter()
+            .map(|h| player_at(demo, h.tick, h.healer_id))
+            .collect::<Vec<Option<&Player>>>();
+
+        columns.push(("round", column(py, heals.iter().map(|h| round_index(&demo.rounds, h.tick)).collect())));
+        columns.push(("patient_team", team_column(py, patient.iter().map(|p| p.map_or(Team::Other, |p| p.team)).collect())?));
+        columns.push(("patient_class", class_column(py, patient.iter().map(|p| p.map_or(Class::Other, |p| p.class)).collect())?));
+        columns.push(("healer_team", team_column(py, healer.iter().map(|p| p.map_or(Team::Other, |p| p.team)).collect())?));
+        columns.push(("healer_class", class_column(py, healer.iter().map(|p| p.map_or(Class::Other, |p| p.class)).collect())?));
+    }
+
+    dataframe(py, columns)
+}
+
+/// One row per spawn, with where the player spawned. If `demo` is given,
+/// the round of each spawn is added.
+pub fn spawns_dataframe<'py>(py: Python<'py>, spawns: &[Spawn], demo: Option<&DemoData>) -> PyResult<&'py PyAny> {
+    let mut columns = vec![
+        ("tick", column(py, spawns.iter().map(|s| s.tick).collect())),
+        ("user_id", column(py, spawns.iter().map(|s| s.user_id).collect())),
+        ("team", team_column(py, spawns.iter().map(|s| s.team).collect())?),
+        ("class", class_column(py, spawns.iter().map(|s| s.class).collect())?),
+        ("x", column(py, spawns.iter().map(|s| s.position.x).collect())),
+        ("y", column(py, spawns.iter().map(|s| s.position.y).collect())),
+        ("z", column(py, spawns.iter().map(|s| s.position.z).collect())),
+    ];
+
+    if let Some(demo) = demo {
+        columns.push(("round", column(py, spawns.iter().map(|s| round_index(&demo.rounds, s.tick)).collect())));
+    }
+
+    dataframe(py, columns)
+}
@@ -1,10 +1,16 @@
 
+//! Game-related classes that translate to generic information about the game.
+//! Typically not directly related to players.
+
 use itertools::Itertools;
 use pyo3::prelude::*;
+use serde::{Serialize, Deserialize};
 use pyo3::pyclass::CompareOp;
+use pyo3::exceptions::{PyKeyError, PyValueError};
 
 use std::collections::HashMap;
 use std::ops::{Index, IndexMut};
+use std::str::FromStr;
 use num_enum::TryFromPrimitive;
 use num_enum::IntoPrimitive;
 
@@ -19,6 +25,11 @@ pub(crate) fn get_submod(py: Python<'_>) -> PyResult<&PyModule> {
     module.add_class::<ClassListIter>()?;
     module.add_class::<Round>()?;
 
+    // So isinstance(classes, Mapping) works
+    py.import("collections.abc")?
+        .getattr("Mapping")?
+        .call_method1("register", (module.getattr("ClassList")?,))?;
+
     Ok(module)
 }
 
@@ -30,8 +41,8 @@ use super::math::Vector;
 
 /// Defines the boundaries of the world as given by the demofile.
 /// You can expect bound_min.x < bound_max.x, and so on.
-#[pyclass(get_all)]
-#[derive(Default, Debug, Clone, PartialEq)]
+#[pyclass(get_all, module = "tf2dal.game")]
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct World {
     /// The minimum.
     pub bound_min: Vector,
@@ -68,11 +79,14 @@ impl World {
 
 use tf_demo_parser::demo::parser::analyser::{Class as TFClass, ClassList as TFCList};
 
-// missing: serde, fromstr
 /// Representation of each class in the game as an enum.
-use std::hash::Hash;
-#[pyclass]
-#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive, Default)]
+///
+/// Compares equal to and hashes the same as its value, so
+/// `player.player_class == 5` works and classes can be mixed with ints in
+/// dicts and sets. Names don't compare equal, as they hash differently; use
+/// `Class("medic")` to look one up. Ordered by value.
+#[pyclass(module = "tf2dal.game")]
+#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive, Default, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum Class {
     #[default]
@@ -95,48 +109,143 @@ impl From<TFClass> for Class {
 }
 
 impl Class {
+    /// Every class, in order of their values.
+    pub const ALL: [Class; 10] = [
+        Class::Other, Class::Scout, Class::Sniper, Class::Soldier, Class::Demoman,
+        Class::Medic, Class::Heavy, Class::Pyro, Class::Spy, Class::Engineer,
+    ];
+
     pub fn new<U>(number: U) -> Self
     where
         u8: TryFrom<U>,
     {
         Class::try_from(u8::try_from(number).unwrap_or_default()).unwrap_or_default()
     }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Class::Other => "Other",
+            Class::Scout => "Scout",
+            Class::Sniper => "Sniper",
+            Class::Soldier => "Soldier",
+            Class::Demoman => "Demoman",
+            Class::Medic => "Medic",
+            Class::Heavy => "Heavy",
+            Class::Pyro => "Pyro",
+            Class::Spy => "Spy",
+            Class::Engineer => "Engineer",
+        }
+    }
 }
 
-use std::hash::Hasher;
-use std::collections::hash_map::DefaultHasher;
+/// Case insensitive. Also takes the short names people actually use.
+impl FromStr for Class {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_lowercase();
+        let alias = match lower.as_str() {
+            "demo" => Some(Class::Demoman),
+            "heavyweapons" | "hoovy" => Some(Class::Heavy),
+            "engi" | "engie" => Some(Class::Engineer),
+            _ => None
+        };
+
+        alias
+            .or_else(|| Class::ALL.iter().find(|c| c.name().to_lowercase() == lower).copied())
+            .ok_or_else(|| format!("unknown class \"{}\"", s))
+    }
+}
 
 #[pymethods]
 impl Class{
+    /// Takes either the value or the name of the class.
     #[new]
-    fn new_py(n: u8) -> Self {
-        Self::new(n)
+    fn new_py(value: &PyAny) -> PyResult<Self> {
+        match value.extract::<&str>() {
+            Ok(name) => name.parse().map_err(PyValueError::new_err),
+            Err(_) => Ok(Self::new(value.extract::<u8>()?))
+        }
+    }
+
+    /// Case insensitive. Also takes "demo", "heavyweapons" and "engi".
+    /// Raises ValueError for anything else.
+    #[staticmethod]
+    #[pyo3(name = "from_str")]
+    fn py_from_str(name: &str) -> PyResult<Self> {
+        name.parse().map_err(PyValueError::new_err)
+    }
+
+    /// Every class, in order of their values.
+    #[staticmethod]
+    fn all() -> Vec<Class> {
+        Class::ALL.to_vec()
     }
 
+    #[getter(name)]
+    fn py_name(&self) -> &'static str {
+        self.name()
+    }
+
+    #[getter]
+    fn value(&self) -> u8 {
+        *self as u8
+    }
+
+    fn __str__(&self) -> &'static str {
+        self.name()
+    }
+
+    /// Same as the hash of the value, since they compare equal.
     fn __hash__(&self) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        self.hash(&mut hasher);
-        hasher.finish()
-    }
-
-    fn __richcmp__(&self, other: &Self, comp: CompareOp) -> Self {
-        match comp {
-            CompareOp::Eq => *{if self == other {self} else {other}},
-            CompareOp::Ge => *{if self >= other {self} else {other}},
-            CompareOp::Gt => *{if self >  other {self} else {other}},
-            CompareOp::Le => *{if self <= other {self} else {other}},
-            CompareOp::Lt => *{if self <  other {self} else {other}},
-            CompareOp::Ne => *{if self != other {self} else {other}},
-        }
+        *self as u64
     }
+
+    fn __richcmp__(&self, other: &PyAny, op: CompareOp) -> PyObject {
+        enum_richcmp(*self, other, op)
+    }
+}
+
+/// Compares an enum against another of the same type or its value. Anything
+/// else, names included, is left to Python: names hash differently from the
+/// value, so comparing equal to them would break dicts and sets.
+fn enum_richcmp<T>(value: T, other: &PyAny, op: CompareOp) -> PyObject
+where
+    T: for<'a> FromPyObject<'a> + Into<u8> + Copy,
+{
+    let py = other.py();
+    let other_value = if let Ok(other) = other.extract::<T>() {
+        Some(other.into())
+    } else if let Ok(number) = other.extract::<u8>() {
+        Some(number)
+    } else {
+        None
+    };
+
+    let Some(other_value) = other_value else {
+        return py.NotImplemented();
+    };
+
+    let value: u8 = value.into();
+    match op {
+        CompareOp::Eq => value == other_value,
+        CompareOp::Ne => value != other_value,
+        CompareOp::Lt => value < other_value,
+        CompareOp::Le => value <= other_value,
+        CompareOp::Gt => value > other_value,
+        CompareOp::Ge => value >= other_value,
+    }.into_py(py)
 }
 
 /////////////////////////////////////////////
 /// Class List
 /// /////////////////////////////////////////
 
-#[pyclass(sequence)]
-#[derive(Default, Debug, Eq, PartialEq, Clone)]
+/// How many times a player spawned as each class. Behaves like a
+/// `collections.Counter` of classes: unplayed classes have a count of 0 and
+/// are not included in `len`, iteration, `keys`, `values` or `items`.
+#[pyclass(mapping, module = "tf2dal.game")]
+#[derive(Default, Debug, Eq, PartialEq, Clone, Serialize, Deserialize)]
 pub struct ClassList([u8; 10]);
 
 impl Index<Class> for ClassList {
@@ -196,38 +305,86 @@ impl ClassList {
     }
 }
 
+/// Keys can be a Class, its name, or its value.
+fn class_key(key: &PyAny) -> PyResult<Class> {
+    if let Ok(class) = key.extract::<Class>() {
+        return Ok(class);
+    }
+    if let Ok(name) = key.extract::<&str>() {
+        return name.parse().map_err(PyKeyError::new_err);
+    }
+
+    key.extract::<u8>().ok()
+        .and_then(|value| Class::try_from(value).ok())
+        .ok_or_else(|| PyKeyError::new_err(key.to_string()))
+}
+
 #[pymethods]
 impl ClassList
 {
+    /// Number of classes played.
     fn __len__(&self) -> usize {
-        let mut count = 0;
-        for i in 0..10 {
-            if self.0[i] > 0 {
-                count += 1;
-            }
-        }
-        count
+        self.iter().count()
     }
 
-    fn __contains__(&self, item: Class) -> bool {
-        self[item] > 0
+    /// Check to see if a player has played this class.
+    fn __contains__(&self, key: &PyAny) -> bool {
+        class_key(key).is_ok_and(|class| self[class] > 0)
     }
 
-    // TODO: __get__
+    /// Number of spawns as the class. 0 if it wasn't played.
+    fn __getitem__(&self, key: &PyAny) -> PyResult<u8> {
+        Ok(self[class_key(key)?])
+    }
 
+    /// Iterates over the classes played.
     fn __iter__(slf: PyRef<'_, Self>) -> PyResult<Py<ClassListIter>> {
-        let list: Vec<(Class, u8)> = slf.iter().collect_vec();
         let iter = ClassListIter {
-            inlist: list,
+            inlist: slf.keys(),
             cur_index: 0
         };
         Py::new(slf.py(), iter)
     }
+
+    fn __repr__(&self) -> String {
+        let counts = self.iter()
+            .map(|(class, count)| format!("{}: {}", class.name(), count))
+            .join(", ");
+        format!("ClassList({{{}}})", counts)
+    }
+
+    /// The count for `key`, or `default` if the class wasn't played.
+    #[pyo3(signature = (key, default=None))]
+    fn get(&self, py: Python<'_>, key: &PyAny, default: Option<PyObject>) -> PyResult<PyObject> {
+        let count = self[class_key(key)?];
+        if count > 0 {
+            Ok(count.into_py(py))
+        } else {
+            Ok(default.unwrap_or_else(|| py.None()))
+        }
+    }
+
+    fn keys(&self) -> Vec<Class> {
+        self.iter().map(|(class, _)| class).collect()
+    }
+
+    fn values(&self) -> Vec<u8> {
+        self.iter().map(|(_, count)| count).collect()
+    }
+
+    fn items(&self) -> Vec<(Class, u8)> {
+        self.iter().collect()
+    }
+
+    /// Classes played and their counts, most played first.
+    fn most_common(&self) -> Vec<(Class, u8)> {
+        self.sorted().collect()
+    }
 }
 
-#[pyclass]
+#[pyclass(module = "tf2dal.game")]
 pub struct ClassListIter {
-    inlist: Vec<(Class, u8)>,
+    inlist: Vec<Class>,
     cur_index: usize,
 }
 
@@ -237,9 +394,10 @@ impl ClassListIter {
         slf
     }
 
-    fn __next__(mut slf: PyRefMut<'_, Self>) -> Option<(Class, u8)> {
+    fn __next__(mut slf: PyRefMut<'_, Self>) -> Option<Class> {
+        let next = slf.inlist.get(slf.cur_index).copied();
         slf.cur_index += 1;
-        slf.inlist.get(slf.cur_index).copied()
+        next
     }
 }
 
@@ -247,8 +405,10 @@ impl ClassListIter {
 /// Team
 /// /////////////////////////////////////////
 
-#[pyclass]
-#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive, Default)]
+/// Compares equal to and hashes the same as its value, but not its name,
+/// like Class. Use `Team("red")` to look one up by name.
+#[pyclass(module = "tf2dal.game")]
+#[derive(Debug, Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash, TryFromPrimitive, IntoPrimitive, Default, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum Team {
     #[default]
@@ -271,40 +431,94 @@ impl From<TFTeam> for Team {
 }
 
 impl Team {
+    /// Every team, in order of their values.
+    pub const ALL: [Team; 4] = [Team::Other, Team::Spectator, Team::Red, Team::Blue];
+
     pub fn new<U>(number: U) -> Self
     where
         u8: TryFrom<U>,
     {
         Team::try_from(u8::try_from(number).unwrap_or_default()).unwrap_or_default()
     }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Team::Other => "Other",
+            Team::Spectator => "Spectator",
+            Team::Red => "Red",
+            Team::Blue => "Blue",
+        }
+    }
+}
+
+/// Case insensitive. "blu" works as well.
+impl FromStr for Team {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.trim().to_lowercase();
+        if lower == "blu" {
+            return Ok(Team::Blue);
+        }
+
+        Team::ALL.iter()
+            .find(|t| t.name().to_lowercase() == lower)
+            .copied()
+            .ok_or_else(|| format!("unknown team \"{}\"", s))
+    }
 }
 
 #[pymethods]
 impl Team {
+    /// Takes either the value or the name of the team.
     #[new]
-    fn new_py(n: u8) -> Self {
-        Self::new(n)
+    fn new_py(value: &PyAny) -> PyResult<Self> {
+        match value.extract::<&str>() {
+            Ok(name) => name.parse().map_err(PyValueError::new_err),
+            Err(_) => Ok(Self::new(value.extract::<u8>()?))
+        }
+    }
+
+    /// Case insensitive. Also takes "blu". Raises ValueError for anything else.
+    #[staticmethod]
+    #[pyo3(name = "from_str")]
+    fn py_from_str(name: &str) -> PyResult<Self> {
+        name.parse().map_err(PyValueError::new_err)
+    }
+
+    /// Every team, in order of their values.
+    #[staticmethod]
+    fn all() -> Vec<Team> {
+        Team::ALL.to_vec()
+    }
+
+    #[getter(name)]
+    fn py_name(&self) -> &'static str {
+        self.name()
+    }
+
+    #[getter]
+    fn value(&self) -> u8 {
+        *self as u8
     }
 
+    /// If the team actually supports having alive players on it.
+    /// The only teams to do so are Red and Blue.
     pub fn is_player(&self) -> bool {
         *self == Team::Red || *self == Team::Blue
     }
 
+    fn __str__(&self) -> &'static str {
+        self.name()
+    }
+
+    /// Same as the hash of the value, since they compare equal.
     fn __hash__(&self) -> u64 {
-        let mut hasher = DefaultHasher::new();
-        self.hash(&mut hasher);
-        hasher.finish()
-    }
-
-    fn __richcmp__(&self, other: &Self, comp: CompareOp) -> Self {
-        match comp {
-            CompareOp::Eq => *{if self == other {self} else {other}},
-            CompareOp::Ge => *{if self >= other {self} else {other}},
-            CompareOp::Gt => *{if self >  other {self} else {other}},
-            CompareOp::Le => *{if self <= other {self} else {other}},
-            CompareOp::Lt => *{if self <  other {self} else {other}},
-            CompareOp::Ne => *{if self != other {self} else {other}},
-        }
+        *self as u64
+    }
+
+    fn __richcmp__(&self, other: &PyAny, op: CompareOp) -> PyObject {
+        enum_richcmp(*self, other, op)
     }
 }
 
@@ -312,8 +526,10 @@ impl Team {
 /// Round
 /// /////////////////////////////////////////
 
-#[pyclass(get_all)]
-#[derive(Debug, Default, Clone, Copy)]
+/// Defines information on a round, such as the tick it started, ended,
+/// and who won.
+#[pyclass(get_all, module = "tf2dal.game")]
+#[derive(Debug, Default, Clone, Copy, Serialize, Deserialize, PartialEq)]
 pub struct Round {
     pub start_tick: u32,
     pub end_tick: u32,
@@ -322,10 +538,20 @@ pub struct Round {
 
 #[pymethods]
 impl Round {
+    /// If `tick` is in the round. A round that hasn't ended has an end tick
+    /// of 0, and contains every tick from its start.
+    pub fn contains(&self, tick: u32) -> bool {
+        tick >= self.start_tick && (self.end_tick == 0 || tick <= self.end_tick)
+    }
+
     pub fn is_tie(&self) -> bool {
         match self.winner {
             Team::Blue | Team::Red => true,
             _ => false
         }
     }
-}
\ No newline at end of file
+}
+
+py_pickle!(World, Class, ClassList, Team, Round);
+py_eq!(World, ClassList, Round);
+py_debug_repr!(World, Round);
@@ -0,0 +1,330 @@
+//! Who players are across demos.
+//!
+//! User IDs only last for a single connection to a single server, and names
+//! change whenever players feel like it. SteamIDs don't, so players are
+//! followed from demo to demo by theirs.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use serde::{Serialize, Deserialize};
+
+use super::demo::DemoData;
+use super::entities::UserInfo;
+
+/// This function is used by lib.rs, but the IDE thinks it's unused.
+#[allow(dead_code)]
+pub(crate) fn get_submod(py: Python<'_>) -> PyResult<&PyModule> {
+    let module = PyModule::new(py, "identity")?;
+    module.add_class::<SteamId>()?;
+    module.add_class::<PlayerIdentity>()?;
+    module.add_class::<IdentityMap>()?;
+    module.add_class::<PlayerRegistry>()?;
+    Ok(module)
+}
+
+/// SteamID64 of account 0 of an individual account in the public universe.
+const STEAM64_BASE: u64 = 76561197960265728;
+
+/// A Steam account, stored as its SteamID64.
+///
+/// Can be made from a SteamID64 as an int or string, a SteamID3 like
+/// `[U:1:22202]`, or a SteamID2 like `STEAM_0:0:11101`. Bots have no SteamID.
+#[pyclass(module = "tf2dal.identity")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct SteamId {
+    #[pyo3(get)]
+    pub steam64: u64,
+}
+
+impl SteamId {
+    pub fn from_account_id(account_id: u32) -> Self {
+        SteamId { steam64: STEAM64_BASE + account_id as u64 }
+    }
+
+    pub fn account_id(&self) -> u32 {
+        (self.steam64 & 0xFFFF_FFFF) as u32
+    }
+
+    /// `[U:1:<account ID>]`, the format demos use.
+    pub fn steam3(&self) -> String {
+        format!("[U:1:{}]", self.account_id())
+    }
+}
+
+/// Takes SteamID64s, SteamID3s with or without brackets, and SteamID2s.
+impl FromStr for SteamId {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || format!("not a SteamID: \"{}\"", s);
+        let trimmed = s.trim();
+
+        if let Some(rest) = trimmed.strip_prefix("STEAM_") {
+            // STEAM_<universe>:<low bit>:<account ID / 2>
+            let parts = rest.split(':').collect::<Vec<&str>>();
+            let [_, low, high] = parts[..] else {
+                return Err(err());
+            };
+            let low = low.parse::<u64>().ok().filter(|l| *l <= 1).ok_or_else(err)?;
+            let high = high.parse::<u64>().map_err(|_| err())?;
+            let account = high.checked_mul(2).and_then(|a| a.checked_add(low))
+                .and_then(|a| u32::try_from(a).ok())
+                .ok_or_else(err)?;
+            return Ok(SteamId::from_account_id(account));
+        }
+
+        let unbracketed = trimmed.trim_start_matches('[').trim_end_matches(']');
+        if let Some(account) = unbracketed.strip_prefix("U:1:") {
+            return account.parse().map(SteamId::from_account_id).map_err(|_| err());
+        }
+
+        // Only individual accounts in the public universe, which share the
+        // top 32 bits of the base. The base itself is account 0, as above.
+        match trimmed.parse::<u64>() {
+            Ok(steam64) if steam64 >> 32 == STEAM64_BASE >> 32 => Ok(SteamId { steam64 }),
+            _ => Err(err()),
+        }
+    }
+}
+
+impl fmt::Display for SteamId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.steam3())
+    }
+}
+
+#[pymethods]
+impl SteamId {
+    #[new]
+    fn new_py(value: &PyAny) -> PyResult<Self> {
+        match value.extract::<&str>() {
+            Ok(text) => text.parse().map_err(PyValueError::new_err),
+            Err(_) => value.extract::<u64>()?.to_string().parse().map_err(PyValueError::new_err),
+        }
+    }
+
+    /// The account ID, which is what SteamID3s and SteamID2s are made of.
+    #[getter(account_id)]
+    fn py_account_id(&self) -> u32 {
+        self.account_id()
+    }
+
+    /// `[U:1:<account ID>]`.
+    #[getter(steam3)]
+    fn py_steam3(&self) -> String {
+        self.steam3()
+    }
+
+    /// The SteamID3.
+    fn __str__(&self) -> String {
+        self.steam3()
+    }
+
+    fn __int__(&self) -> u64 {
+        self.steam64
+    }
+
+    fn __hash__(&self) -> u64 {
+        self.steam64
+    }
+}
+
+/// A player, and every name they played under.
+#[pyclass(get_all, module = "tf2dal.identity")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlayerIdentity {
+    pub steam_id: SteamId,
+    /// The most recent name.
+    pub name: String,
+    /// Every name, in the order they were first used.
+    pub names: Vec<String>,
+}
+
+impl PlayerIdentity {
+    fn add_name(&mut self, name: &str) {
+        self.name = name.to_string();
+        if !self.names.iter().any(|n| n == name) {
+            self.names.push(name.to_string());
+        }
+    }
+}
+
+/// The players of a single demo, and which user IDs they had in it.
+/// Players that reconnect get a new user ID, so one player can have several.
+#[pyclass(module = "tf2dal.identity")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdentityMap {
+    /// Ordered by SteamID.
+    #[pyo3(get)]
+    pub players: Vec<PlayerIdentity>,
+    /// User ID to SteamID.
+    #[pyo3(get)]
+    pub user_ids: BTreeMap<u16, SteamId>,
+}
+
+impl IdentityMap {
+    /// Records the SteamID and name of `info`. Bots and players without a
+    /// valid SteamID are left out.
+    pub fn record(&mut self, info: &UserInfo) {
+        let Ok(steam_id) = info.steam_id.parse::<SteamId>() else {
+            return;
+        };
+        self.user_ids.insert(info.user_id, steam_id);
+
+        let index = match self.players.binary_search_by_key(&steam_id, |p| p.steam_id) {
+            Ok(index) => index,
+            Err(index) => {
+                self.players.insert(index, PlayerIdentity {steam_id, ..Default::default()});
+                index
+            },
+        };
+        if self.players[index].name != info.name {
+            self.players[index].add_name(&info.name);
+        }
+    }
+
+    pub fn get(&self, user_id: u16) -> Option<&PlayerIdentity> {
+        let steam_id = self.user_ids.get(&user_id)?;
+        self.players.iter().find(|p| p.steam_id == *steam_id)
+    }
+}
+
+#[pymethods]
+impl IdentityMap {
+    /// The SteamID of the player with `user_id`.
+    fn steam_id(&self, user_id: u16) -> Option<SteamId> {
+        self.user_ids.get(&user_id).copied()
+    }
+
+    /// The player with `user_id`.
+    #[pyo3(name = "get")]
+    fn py_get(&self, user_id: u16) -> Option<PlayerIdentity> {
+        self.get(user_id).cloned()
+    }
+
+    /// Every user ID the player with `steam_id` had.
+    fn user_ids_of(&self, steam_id: SteamId) -> Vec<u16> {
+        self.user_ids.iter().filter(|(_, id)| **id == steam_id).map(|(user_id, _)| *user_id).collect()
+    }
+}
+
+/// Players over many demos, keyed by SteamID, with every name they used.
+#[pyclass(module = "tf2dal.identity")]
+#[derive(Default, Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlayerRegistry {
+    pub players: BTreeMap<SteamId, PlayerIdentity>,
+}
+
+impl PlayerRegistry {
+    /// Merges in the players of `demo`, adding any new names.
+    pub fn add(&mut self, demo: &DemoData) {
+        for player in &demo.identities.players {
+            let known = self.players.entry(player.steam_id)
+                .or_insert_with(|| PlayerIdentity {steam_id: player.steam_id, ..Default::default()});
+            for name in &player.names {
+                known.add_name(name);
+            }
+            known.add_name(&player.name);
+        }
+    }
+}
+
+#[pymethods]
+impl PlayerRegistry {
+    #[new]
+    #[pyo3(signature = (demos=Vec::new()))]
+    fn new(demos: Vec<PyRef<'_, DemoData>>) -> Self {
+        let mut registry = PlayerRegistry::default();
+        for demo in demos {
+            registry.add(&demo);
+        }
+        registry
+    }
+
+    /// Adds the players of `demo`. Add demos oldest first, so each player's
+    /// `name` ends up being the most recent one.
+    #[pyo3(name = "add")]
+    fn py_add(&mut self, demo: PyRef<'_, DemoData>) {
+        self.add(&demo)
+    }
+
+    /// Every player, ordered by SteamID.
+    #[getter]
+    fn players(&self) -> Vec<PlayerIdentity> {
+        self.players.values().cloned().collect()
+    }
+
+    fn get(&self, steam_id: SteamId) -> Option<PlayerIdentity> {
+        self.players.get(&steam_id).cloned()
+    }
+
+    /// Who had `user_id` in `demo`, if they were added.
+    fn identify(&self, demo: PyRef<'_, DemoData>, user_id: u16) -> Option<PlayerIdentity> {
+        let steam_id = demo.identities.user_ids.get(&user_id)?;
+        self.players.get(steam_id).cloned()
+    }
+
+    /// Players that ever used a name containing `name`, ignoring case.
+    fn find(&self, name: &str) -> Vec<PlayerIdentity> {
+        let name = name.to_lowercase();
+        self.players.values()
+            .filter(|p| p.names.iter().any(|n| n.to_lowercase().contains(&name)))
+            .cloned()
+            .collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.players.len()
+    }
+}
+
+py_pickle!(SteamId, PlayerIdentity, IdentityMap, PlayerRegistry);
+py_eq!(SteamId, PlayerIdentity, IdentityMap, PlayerRegistry);
+py_debug_repr!(SteamId, PlayerIdentity, IdentityMap, PlayerRegistry);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_format() {
+        let id = SteamId::from_account_id(22202);
+        assert_eq!(id.steam64, 76561197960287930);
+        for text in ["76561197960287930", "[U:1:22202]", "U:1:22202", "STEAM_0:0:11101", "STEAM_1:0:11101", " [U:1:22202] "] {
+            assert_eq!(text.parse::<SteamId>(), Ok(id), "{}", text);
+        }
+        assert_eq!("STEAM_0:1:11101".parse::<SteamId>(), Ok(SteamId::from_account_id(22203)));
+    }
+
+    #[test]
+    fn round_trips() {
+        for account in [0, 1, 22202, 22203, u32::MAX] {
+            let id = SteamId::from_account_id(account);
+            assert_eq!(id.account_id(), account);
+            assert_eq!(id.to_string().parse::<SteamId>(), Ok(id));
+            assert_eq!(id.steam64.to_string().parse::<SteamId>(), Ok(id));
+        }
+    }
+
+    #[test]
+    fn rejects_out_of_range() {
+        // An account ID past u32
+        assert!("STEAM_0:0:3000000000".parse::<SteamId>().is_err());
+        assert!("STEAM_0:2:11101".parse::<SteamId>().is_err());
+        assert!("[U:1:4294967296]".parse::<SteamId>().is_err());
+        // SteamID64s outside the public individual range
+        assert!((STEAM64_BASE - 1).to_string().parse::<SteamId>().is_err());
+        assert!((STEAM64_BASE + (1 << 32)).to_string().parse::<SteamId>().is_err());
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        for text in ["", "STEAM_0:0", "STEAM_0:0:x", "U:2:22202", "gaben"] {
+            assert!(text.parse::<SteamId>().is_err(), "{}", text);
+        }
+    }
+}
@@ -1,10 +1,14 @@
 
+//! The 2D and 3D vectors used by the library, including extra functions
+//! necessary for __math__.
+
 use std::iter::Sum;
 use std::ops::{Add, Div, Mul, Neg, Sub};
 use std::fmt::{Display, Formatter};
 
 use pyo3::prelude::*;
 use bitbuffer::{BitRead, BitWrite};
+use serde::{Serialize, Deserialize};
 
 use tf_demo_parser::demo::vector::{Vector as TFVector, VectorXY as TFVectorXY};
 
@@ -23,9 +27,9 @@ pub(crate) fn get_submod(
     Ok(module)
 }
 
-// todo: serialize, deserialize, bitread, bitwrite ?
-#[pyclass(get_all, set_all)]
-#[derive(BitRead, BitWrite, Debug, Clone, Copy, Default)]
+/// 3D vector / point. Compares equal if `self - other ~= 0`.
+#[pyclass(get_all, set_all, module = "tf2dal.math")]
+#[derive(BitRead, BitWrite, Debug, Clone, Copy, Default, Serialize, Deserialize)]
 pub struct Vector {
     pub x: f32,
     pub y: f32,
@@ -66,9 +70,9 @@ impl From<Vector> for [f32; 3] {
 
 impl PartialEq for Vector {
     fn eq(&self, other: &Self) -> bool {
-        (self.x - other.x < ZERO_EPSILON_F32) 
-        && (self.y - other.y < ZERO_EPSILON_F32) 
-        && (self.z - other.z < ZERO_EPSILON_F32)
+        ((self.x - other.x).abs() < ZERO_EPSILON_F32)
+        && ((self.y - other.y).abs() < ZERO_EPSILON_F32)
+        && ((self.z - other.z).abs() < ZERO_EPSILON_F32)
     }
 }
 
@@ -177,6 +181,7 @@ impl Vector {
         f32::acos(self.dot(other) / (self.len() * other.len()))
     }
 
+    /// |v|^2
     pub fn abs2(&self) -> f32 {
         self.dot(self)
     }
@@ -189,6 +194,8 @@ impl Vector {
         VectorXY{x: self.x, y: self.y}
     }
 
+    /// Doesn't change self; simply returns a vector with the
+    /// same direction but length ~= 1
     pub fn normalized(&self) -> Self {
         // fisqrt never panics for f32
         let i = QSqrt::fast_inverse_sqrt_unchecked(&self.abs2());
@@ -209,9 +216,6 @@ impl Vector {
     fn __neg__(&self) -> Self {
         -*self
     }
-    fn __eq__(&self, other: &Self) -> bool {
-        self == other
-    }
     fn __mul__(&self, other: f32) -> Self {
         *self * other
     }
@@ -229,9 +233,9 @@ impl Vector {
 ////////////////
 /// VectorXY
 
-// todo: serialize, deserialize, bitread, bitwrite ?
-#[pyclass(get_all, set_all)]
-#[derive(BitRead, BitWrite, Debug, Clone, Copy, Default)]
+/// 2D vector / point.
+#[pyclass(get_all, set_all, module = "tf2dal.math")]
+#[derive(BitRead, BitWrite, Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
 pub struct VectorXY {
     pub x: f32,
     pub y: f32
@@ -360,12 +364,13 @@ impl VectorXY {
         self.abs2().sqrt()
     }
 
-    /// {x, y, 0}
+    /// Literally just appends 0 to the Z coordinate.
     pub fn xyz(&self) -> Vector {
         Vector{x: self.x, y: self.y, z: 0.0}
     }
 
-    /// same direction but length 1
+    /// Doesn't change self; simply returns a vector with the
+    /// same direction but length ~= 1
     pub fn normalized(&self) -> Self {
         // fisqrt never panics for f32
         let i = QSqrt::fast_inverse_sqrt_unchecked(&self.abs2());
@@ -397,4 +402,8 @@ impl VectorXY {
     fn __rtruediv__(&self, other: f32) -> Self {
         *self / other
     }
-}
\ No newline at end of file
+}
+
+py_pickle!(Vector, VectorXY);
+py_eq!(Vector, VectorXY);
+py_debug_repr!(Vector, VectorXY);
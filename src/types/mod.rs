@@ -5,8 +5,90 @@ pub use tf_demo_parser::demo::data::DemoTick;
 pub use tf_demo_parser::demo::message::packetentities::EntityId;
 pub use tf_demo_parser::demo::parser::analyser::UserId;
 
+use pyo3::prelude::*;
+use pyo3::exceptions::PyValueError;
+use pyo3::types::PyBytes;
+use serde::{Serialize, de::DeserializeOwned};
+
+// The macros have to come before the modules that use them.
+
+/// Makes pyclasses picklable and copyable. They are pickled as bincode, so
+/// the types have to be `Clone`, `Serialize` and `Deserialize`.
+///
+/// Unpickling goes through the `_from_state` classmethod, so the class has
+/// to be importable from its `module`.
+macro_rules! py_pickle {
+    ($($ty:ty),+ $(,)?) => {$(
+        #[::pyo3::pymethods]
+        impl $ty {
+            #[classmethod]
+            fn _from_state(_cls: &::pyo3::types::PyType, state: &::pyo3::types::PyBytes) -> ::pyo3::PyResult<Self> {
+                crate::types::from_state(state)
+            }
+
+            fn __reduce__(slf: &::pyo3::PyCell<Self>) -> ::pyo3::PyResult<(::pyo3::PyObject, (::pyo3::PyObject,))> {
+                let state = crate::types::to_state(slf.py(), &*slf.borrow())?;
+                Ok((slf.get_type().getattr("_from_state")?.into(), (state,)))
+            }
+
+            fn __copy__(&self) -> Self {
+                self.clone()
+            }
+
+            fn __deepcopy__(&self, _memo: &::pyo3::PyAny) -> Self {
+                self.clone()
+            }
+        }
+    )+};
+}
+
+/// Adds `==` and `!=` to pyclasses using their `PartialEq`.
+/// Comparing against any other type is left to Python.
+macro_rules! py_eq {
+    ($($ty:ty),+ $(,)?) => {$(
+        #[::pyo3::pymethods]
+        impl $ty {
+            fn __richcmp__(&self, other: &::pyo3::PyAny, op: ::pyo3::pyclass::CompareOp) -> ::pyo3::PyObject {
+                use ::pyo3::IntoPy;
+                let py = other.py();
+                match (op, other.extract::<::pyo3::PyRef<'_, Self>>()) {
+                    (::pyo3::pyclass::CompareOp::Eq, Ok(other)) => (*self == *other).into_py(py),
+                    (::pyo3::pyclass::CompareOp::Ne, Ok(other)) => (*self != *other).into_py(py),
+                    _ => py.NotImplemented(),
+                }
+            }
+        }
+    )+};
+}
+
+/// Uses the `Debug` output of pyclasses as their `repr`.
+macro_rules! py_debug_repr {
+    ($($ty:ty),+ $(,)?) => {$(
+        #[::pyo3::pymethods]
+        impl $ty {
+            fn __repr__(&self) -> String {
+                format!("{:?}", self)
+            }
+        }
+    )+};
+}
+
 pub mod math;
 pub mod game;
 pub mod demo;
 pub mod entities;
 pub mod events;
+pub mod frames;
+pub mod parse;
+pub mod identity;
+
+pub(crate) fn to_state<T: Serialize>(py: Python<'_>, value: &T) -> PyResult<PyObject> {
+    let bytes = bincode::serialize(value)
+        .map_err(|err| PyValueError::new_err(format!("could not pickle: {}", err)))?;
+    Ok(PyBytes::new(py, &bytes).into())
+}
+
+pub(crate) fn from_state<T: DeserializeOwned>(state: &PyBytes) -> PyResult<T> {
+    bincode::deserialize(state.as_bytes())
+        .map_err(|err| PyValueError::new_err(format!("could not unpickle: {}", err)))
+}
@@ -0,0 +1,205 @@
+//! Handles to demos being parsed in the background.
+//!
+//! The GIL is not held while a demo is parsing, so other Python threads (and
+//! Jupyter) keep running.
+
+use std::path::PathBuf;
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::{Duration, Instant};
+
+use pyo3::prelude::*;
+use pyo3::exceptions::PyRuntimeError;
+
+use crate::parsing::{ParseOptions, ParseProgressReport, ParseWorker, ParseWorkerError};
+use super::demo::DemoData;
+
+/// This function is used by lib.rs, but the IDE thinks it's unused.
+#[allow(dead_code)]
+pub(crate) fn get_submod(py: Python<'_>) -> PyResult<&PyModule> {
+    let module = PyModule::new(py, "parse")?;
+    module.add_class::<ParseHandle>()?;
+    Ok(module)
+}
+
+/// How long to wait for a report before checking for Python signals
+/// (i.e. Ctrl+C) again.
+const WAIT_SLICE: Duration = Duration::from_millis(100);
+
+/// A demo being parsed on another thread. Returned from `tf2dal.parse_async`.
+#[pyclass(module = "tf2dal.parse")]
+pub struct ParseHandle {
+    worker: Option<ParseWorker>,
+
+    #[pyo3(get)]
+    demo_filename: PathBuf,
+    /// Number of ticks in the demo. 0 until the header has been read.
+    #[pyo3(get)]
+    max_tick: u32,
+    /// The last tick the parser reported.
+    #[pyo3(get)]
+    current_tick: u32,
+    /// Why the parse failed, if it did.
+    #[pyo3(get)]
+    error: Option<String>,
+    #[pyo3(get)]
+    cancelled: bool,
+
+    result: Option<DemoData>,
+    result_taken: bool,
+}
+
+impl ParseHandle {
+    pub fn new(fpath: PathBuf, options: ParseOptions) -> std::io::Result<Self> {
+        Ok(ParseHandle {
+            worker: Some(ParseWorker::with_options(fpath.clone(), options)?),
+            demo_filename: fpath,
+            max_tick: 0,
+            current_tick: 0,
+            error: None,
+            cancelled: false,
+            result: None,
+            result_taken: false,
+        })
+    }
+
+    fn handle_report(&mut self, report: ParseProgressReport) {
+        match report {
+            ParseProgressReport::Info(max_tick) => {
+                self.max_tick = max_tick;
+            },
+            ParseProgressReport::Working(tick) => {
+                self.current_tick = tick;
+            },
+            ParseProgressReport::Done(data, _) => {
+                self.current_tick = self.max_tick;
+                self.result = Some(data);
+                self.worker = None;
+            },
+            ParseProgressReport::Error(ParseWorkerError::Cancelled) => {
+                self.cancelled = true;
+                self.worker = None;
+            },
+            ParseProgressReport::Error(err) => {
+                self.error = Some(format!("{:?}", err));
+                self.worker = None;
+            },
+            ParseProgressReport::Waiting => {}
+        }
+    }
+}
+
+#[pymethods]
+impl ParseHandle {
+    /// Takes in any progress the parse has made. Never blocks.
+    pub fn poll(&mut self) {
+        while let Some(report) = self.worker.as_ref().and_then(|w| w.get_next()) {
+            self.handle_report(report);
+        }
+    }
+
+    /// The fraction of the demo that has been parsed, from 0 to 1.
+    #[getter]
+    pub fn progress(&mut self) -> f32 {
+        self.poll();
+        if self.max_tick == 0 {
+            0.0
+        } else {
+            (self.current_tick as f32 / self.max_tick as f32).min(1.0)
+        }
+    }
+
+    /// True once the parse has finished, failed, or been cancelled.
+    #[getter]
+    pub fn done(&mut self) -> bool {
+        self.poll();
+        self.worker.is_none()
+    }
+
+    /// Stops the parse and waits for the parse thread to exit.
+    pub fn cancel(&mut self, py: Python<'_>) {
+        if let Some(worker) = self.worker.take() {
+            // Dropping the worker cancels and joins it
+            py.allow_threads(move || drop(worker));
+            self.cancelled = true;
+        }
+    }
+
+    /// Pauses the parse until `resume` is called.
+    pub fn pause(&mut self) {
+        if let Some(worker) = &mut self.worker {
+            worker.pause();
+        }
+    }
+
+    pub fn resume(&mut self) {
+        if let Some(worker) = &mut self.worker {
+            worker.resume();
+        }
+    }
+
+    #[getter]
+    pub fn paused(&self) -> bool {
+        self.worker.as_ref().is_some_and(|w| w.is_paused())
+    }
+
+    /// Blocks until the parse is done, or until `timeout` seconds have passed.
+    /// The GIL is released while waiting. Returns `done`.
+    #[pyo3(signature = (timeout=None))]
+    pub fn wait(&mut self, py: Python<'_>, timeout: Option<f64>) -> PyResult<bool> {
+        let deadline = timeout.map(|secs| Instant::now() + Duration::from_secs_f64(secs.max(0.0)));
+
+        while let Some(worker) = self.worker.take() {
+            let slice = match deadline {
+                Some(deadline) => WAIT_SLICE.min(deadline.saturating_duration_since(Instant::now())),
+                None => WAIT_SLICE
+            };
+
+            let (worker, report) = py.allow_threads(move || {
+                let report = worker.wait_next(slice);
+                (worker, report)
+            });
+            self.worker = Some(worker);
+
+            match report {
+                Ok(report) => self.handle_report(report),
+                Err(RecvTimeoutError::Timeout) => {},
+                Err(RecvTimeoutError::Disconnected) => {
+                    self.error = Some("parse thread stopped without finishing".to_string());
+                    self.worker = None;
+                }
+            }
+
+            py.check_signals()?;
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                break;
+            }
+        }
+
+        Ok(self.worker.is_none())
+    }
+
+    /// Waits for the parse to finish and returns the data. The data is moved
+    /// out of the handle, so this can only be called once.
+    ///
+    /// Raises RuntimeError if the parse failed, was cancelled, timed out, or
+    /// the result was already taken.
+    #[pyo3(signature = (timeout=None))]
+    pub fn result(&mut self, py: Python<'_>, timeout: Option<f64>) -> PyResult<DemoData> {
+        if !self.wait(py, timeout)? {
+            return Err(PyRuntimeError::new_err("parse did not finish before the timeout"));
+        }
+
+        if let Some(err) = &self.error {
+            return Err(PyRuntimeError::new_err(format!("parse failed: {}", err)));
+        }
+        if self.cancelled {
+            return Err(PyRuntimeError::new_err("parse was cancelled"));
+        }
+        if self.result_taken {
+            return Err(PyRuntimeError::new_err("result was already taken from this handle"));
+        }
+
+        self.result_taken = true;
+        self.result.take().ok_or_else(|| PyRuntimeError::new_err("parse produced no data"))
+    }
+}
@@ -1,12 +1,13 @@
 use std::env;
 
-use eframe::egui_glow;
+use eframe::{egui, egui_glow};
 use egui_glow::glow;
 
 use super::renderable::{Renderable, PlayerRendering, Loadable};
 use super::shader::Shader;
 
 use crate::types::demo::TickData;
+use crate::types::math::Vector;
 use crate::parsing::ParseDrawInfo;
 
 pub struct Drawing {
@@ -68,16 +69,35 @@ impl Drawing {
         }
     }
 
+    /// min_x, min_y, max_x, max_y, as given to `point.vert`.
+    fn world_bounds(&self) -> [f32; 4] {
+        [
+            self.draw_info.player_at_max.bound_min.x - 50.0,
+            self.draw_info.player_at_max.bound_min.y - 50.0,
+            self.draw_info.player_at_max.bound_max.x + 50.0,
+            self.draw_info.player_at_max.bound_max.y + 50.0,
+        ]
+    }
+
+    /// Where a world position is drawn inside `rect`. Matches `point.vert`.
+    pub fn world_to_screen(&self, rect: egui::Rect, pos: Vector) -> egui::Pos2 {
+        let [min_x, min_y, max_x, max_y] = self.world_bounds();
+        let ndc_x = pos.x / (max_x - min_x);
+        let ndc_y = pos.y / (max_y - min_y);
+        egui::pos2(
+            rect.center().x + ndc_x * rect.width() / 2.0,
+            rect.center().y - ndc_y * rect.height() / 2.0,
+        )
+    }
+
     pub fn paint(&self, gl: &glow::Context) {
         use glow::HasContext as _;
         unsafe {
             self.dot_program.bind(gl);
+            let [min_x, min_y, max_x, max_y] = self.world_bounds();
             gl.uniform_4_f32(
                 gl.get_uniform_location(self.dot_program.program, "world_bounds").as_ref(),
-                self.draw_info.player_at_max.bound_min.x - 50.0,
-                self.draw_info.player_at_max.bound_min.y - 50.0,
-                self.draw_info.player_at_max.bound_max.x + 50.0,
-                self.draw_info.player_at_max.bound_max.y + 50.0,
+                min_x, min_y, max_x, max_y,
             );
             self.player_rendering.paint(gl, None);
 
@@ -1,6 +1,8 @@
 
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use eframe::{egui, egui_glow};
 use egui_glow::glow;
@@ -13,12 +15,30 @@ use tf_demo_parser::demo::header::Header;
 use crate::parsing::{ParseProgressReport, ParseWorker, ParseWorkerError, ParseDrawInfo};
 use crate::parsing::internals::{InternalParse, InternalParseInstruction, InternalParseResult};
 use crate::types::demo::DemoData;
+use crate::datatransmit::{AnalysisError, AnalysisHost, AnalysisKind};
+use crate::analysisresult::AnalysisResult;
+use crate::analysis::{critheal, healing, medicdeaths, respawns};
+use crate::analysis::rating::{self, RatingModel};
+use crate::analysis::heatmap::{self, Heatmap, HeatmapFilter, HeatmapKind};
+use crate::analysis::zones::{self, Zone, ZoneReport};
+use crate::types::game::Team;
+use crate::types::math::Vector;
+use crate::config::Config;
 use self::drawing::Drawing;
 
 pub mod renderable;
 pub mod shader;
 pub mod drawing;
 
+/// Analyses done in Rust, shown next to the ones loaded from Python.
+const BUILTIN_ANALYSES: &[(&str, fn(&DemoData) -> AnalysisResult)] = &[
+    ("Crit Heals", |demo| critheal::critheal_report(demo, None).to_result()),
+    ("Medic Heals", |demo| healing::reports_to_result(&healing::medic_reports(demo, None))),
+    ("Respawns", |demo| respawns::respawn_report(demo, None).to_result()),
+    ("Medic Deaths", |demo| medicdeaths::medic_death_report(demo, None, &Default::default()).to_result()),
+    ("Ratings", |demo| rating::rating_report(demo, &RatingModel::default()).to_result()),
+];
+
 struct InternalParseUI {
     parse: InternalParse,
     header: Option<Header>
@@ -195,6 +215,7 @@ struct DemoViewUI {
     // parsing in progress
     parse_worker: Option<ParseWorker>,
     parse_max_tick: u32,
+    parse_current_tick: u32,
     parse_draw_info: Option<ParseDrawInfo>,
     parse_data: Option<DemoData>,
     parse_file: PathBuf,
@@ -205,15 +226,32 @@ struct DemoViewUI {
 
     encountered_error: Option<ParseWorkerError>,
     closed_err: bool,
-}
 
-use crate::datatransmit::{launch_demo_analysis, launch_tick_analysis};
+    analysis_error: Option<AnalysisError>,
+    /// The latest result of each analysis that has been run, by name.
+    analysis_results: Vec<(String, AnalysisResult)>,
+    /// Re-run when the analysis scripts are reloaded.
+    last_analysis: Option<(String, AnalysisKind)>,
+
+    /// The heatmap drawn under the players, if any.
+    heatmap_kind: Option<HeatmapKind>,
+    /// None for both teams.
+    heatmap_team: Option<Team>,
+    heatmap: Option<Heatmap>,
+
+    /// The zones of every map, from the config.
+    zone_table: HashMap<String, Vec<Zone>>,
+    /// The zones of the demo's map, outlined in the colour of the team holding them.
+    zones: Vec<Zone>,
+    zone_report: Option<ZoneReport>,
+}
 
 impl DemoViewUI {
     pub fn new(fpath: PathBuf) -> Self {
-        match ParseWorker::new(fpath) {
+        match ParseWorker::new(fpath.clone()) {
             Ok(pw) => DemoViewUI {
                 parse_worker: Some(pw),
+                parse_file: fpath,
                 //data_transmitter: Some(DataTransmitter::new().unwrap()),
                 ..Default::default()
             },
@@ -229,7 +267,12 @@ impl DemoViewUI {
         self.draw_mutex = draw_mutex;
     }
 
-    pub fn draw_ui(&mut self, ctx: &egui::Context, _frame: &eframe::Frame) {
+    /// Zones for each map. Those of the demo's map are drawn once it is parsed.
+    pub fn set_zones(&mut self, zone_table: HashMap<String, Vec<Zone>>) {
+        self.zone_table = zone_table;
+    }
+
+    pub fn draw_ui(&mut self, ctx: &egui::Context, _frame: &eframe::Frame, analysis: &AnalysisHost) {
         // Check if it's done parsing
         if let Some(inprog) = &mut self.parse_worker {
             if self.parse_max_tick == 0 {
@@ -250,30 +293,20 @@ impl DemoViewUI {
                                 self.parse_max_tick = tickcount;
                             },
                             ParseProgressReport::Working(tick) => {
-                                egui::Window::new(format!("Parsing {:?}", self.parse_file.file_name().unwrap_or_default()))
-                                    .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
-                                    .collapsible(false)
-                                    .resizable(false)
-                                    .show(ctx, |ui| {
-                                        let max_tick = self.parse_max_tick;
-                                        let cur_tick = tick;
-                                    
-                                        ui.vertical_centered(|ui| {
-                                            if max_tick > 0 {
-                                                ui.add(egui::ProgressBar::new(cur_tick as f32 / max_tick as f32));
-                                            }
-                                            ui.label(format!("{} / {}", cur_tick, max_tick));
-                                        });
-                                    
-                                        // request repaint to repaint the progress bar
-                                        ctx.request_repaint();
-                                    });
+                                self.parse_current_tick = tick;
+                            },
+                            ParseProgressReport::Error(ParseWorkerError::Cancelled) => {
+                                self.parse_worker.take();
                             },
                             ParseProgressReport::Error(err) => {
                                 self.encountered_error = Some(err);
                                 self.parse_worker.take();
                             },
                             ParseProgressReport::Done(data, drawdata) => {
+                                self.zones = self.zone_table.get(&data.map_name).cloned().unwrap_or_default();
+                                if !self.zones.is_empty() {
+                                    self.zone_report = Some(zones::zone_report(&data, &self.zones, None));
+                                }
                                 self.parse_data = Some(data);
                                 self.parse_draw_info = Some(drawdata);
                                 self.parse_worker.take();
@@ -285,6 +318,46 @@ impl DemoViewUI {
                 }
             }
         }
+
+        if let Some(inprog) = &mut self.parse_worker {
+            let mut cancel = false;
+            egui::Window::new(format!("Parsing {:?}", self.parse_file.file_name().unwrap_or_default()))
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .collapsible(false)
+                .resizable(false)
+                .show(ctx, |ui| {
+                    let max_tick = self.parse_max_tick;
+                    let cur_tick = self.parse_current_tick;
+                
+                    ui.vertical_centered(|ui| {
+                        if max_tick > 0 {
+                            ui.add(egui::ProgressBar::new(cur_tick as f32 / max_tick as f32));
+                        }
+                        ui.label(format!("{} / {}", cur_tick, max_tick));
+
+                        ui.horizontal(|ui| {
+                            if inprog.is_paused() {
+                                if ui.button("Resume").clicked() {
+                                    inprog.resume();
+                                }
+                            } else if ui.button("Pause").clicked() {
+                                inprog.pause();
+                            }
+                            if ui.button("Cancel").clicked() {
+                                cancel = true;
+                            }
+                        });
+                    });
+                
+                    // request repaint to repaint the progress bar
+                    ctx.request_repaint();
+                });
+
+            if cancel {
+                // Dropping the worker stops the parse thread
+                self.parse_worker.take();
+            }
+        }
     
         else {
             // Display error
@@ -306,6 +379,36 @@ impl DemoViewUI {
                 }
             }
 
+            if let Some(err) = self.analysis_error.as_ref() {
+                let mut closed = false;
+                egui::TopBottomPanel::bottom("analysis_error_panel")
+                    .resizable(true)
+                    .show(ctx, |ui| {
+                        ui.horizontal(|ui| {
+                            ui.strong("Analysis Error");
+                            if ui.button("Dismiss").clicked() {
+                                closed = true;
+                            }
+                        });
+                        egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                            ui.label(egui::RichText::new(err.to_string()).monospace());
+                        });
+                    });
+
+                if closed {
+                    self.analysis_error.take();
+                }
+            }
+
+            let mut requested = None;
+            let mut requested_builtin = None;
+
+            if !self.analysis_results.is_empty() {
+                egui::SidePanel::right("analysis_panel").show(ctx, |ui| {
+                    self.analysis_panel(ui);
+                });
+            }
+
             egui::CentralPanel::default().show(ctx, |ui| {
                 if let Some(result) = &self.parse_data {
                     ui.heading(format!("Viewing Demo: {:?}", result.demo_filename.file_name()));
@@ -331,6 +434,31 @@ impl DemoViewUI {
                         }
                     });
 
+                    ui.horizontal(|ui| {
+                        let before = (self.heatmap_kind, self.heatmap_team);
+                        egui::ComboBox::from_label("Heatmap")
+                            .selected_text(self.heatmap_kind.map_or("None", |kind| kind.name()))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.heatmap_kind, None, "None");
+                                for kind in HeatmapKind::ALL {
+                                    ui.selectable_value(&mut self.heatmap_kind, Some(kind), kind.name());
+                                }
+                            });
+                        egui::ComboBox::from_label("Heatmap Team")
+                            .selected_text(self.heatmap_team.map_or("Both", |team| team.name()))
+                            .show_ui(ui, |ui| {
+                                ui.selectable_value(&mut self.heatmap_team, None, "Both");
+                                for team in [Team::Red, Team::Blue] {
+                                    ui.selectable_value(&mut self.heatmap_team, Some(team), team.name());
+                                }
+                            });
+
+                        if (self.heatmap_kind, self.heatmap_team) != before {
+                            let filter = HeatmapFilter {team: self.heatmap_team, ..Default::default()};
+                            self.heatmap = self.heatmap_kind.map(|kind| heatmap::heatmap(result, kind, &filter, 64.0));
+                        }
+                    });
+
                     if ui.button("Recompile Shaders").clicked() {
                         if let Some(dmutex) = &self.draw_mutex {
                             if dmutex.lock().attempt_recompile(_frame.gl().unwrap()) {
@@ -339,18 +467,20 @@ impl DemoViewUI {
                         }
                     }
         
-                    ui.horizontal(|ui| {
-                        if ui.button("Analyze Demo").clicked() {
-                            launch_demo_analysis(result);
+                    ui.horizontal_wrapped(|ui| {
+                        for (name, analyze) in BUILTIN_ANALYSES {
+                            if ui.button(format!("Analyze Demo: {}", name)).clicked() {
+                                requested_builtin = Some((*name, *analyze));
+                            }
                         }
-                        if ui.button("Analyze Tick").clicked() {
-                            if let Some(tickdata) = result.tick_states.get(&self.current_tick_view) {
-                                launch_tick_analysis(tickdata);
+                        for demo_analysis in analysis.analyses_of(AnalysisKind::Demo) {
+                            if ui.button(format!("Analyze Demo: {}", demo_analysis.name)).clicked() {
+                                requested = Some((demo_analysis.name.clone(), AnalysisKind::Demo));
                             }
-                            else {
-                                self.encountered_error = Some(ParseWorkerError::IoError(
-                                    std::io::Error::new(std::io::ErrorKind::InvalidInput, "Tick has no tick state")
-                                ));
+                        }
+                        for tick_analysis in analysis.analyses_of(AnalysisKind::Tick) {
+                            if ui.button(format!("Analyze Tick: {}", tick_analysis.name)).clicked() {
+                                requested = Some((tick_analysis.name.clone(), AnalysisKind::Tick));
                             }
                         }
                     });
@@ -365,6 +495,50 @@ impl DemoViewUI {
                     egui::warn_if_debug_build(ui);
                 });
             });
+
+            if let Some((name, kind)) = requested {
+                self.run_analysis(analysis, name, kind);
+            }
+            if let (Some((name, analyze)), Some(data)) = (requested_builtin, &self.parse_data) {
+                store_result(&mut self.analysis_results, name, analyze(data));
+            }
+        }
+    }
+
+    /// Runs an analysis on the demo, or on the tick being viewed, and keeps
+    /// the result or error to show.
+    fn run_analysis(&mut self, analysis: &AnalysisHost, name: String, kind: AnalysisKind) {
+        let Some(data) = &self.parse_data else {
+            return;
+        };
+
+        let result = match kind {
+            AnalysisKind::Demo => analysis.run_demo_analysis(&name, data),
+            AnalysisKind::Tick => match data.tick_states.get(&self.current_tick_view) {
+                Some(tickdata) => analysis.run_tick_analysis(&name, tickdata),
+                None => {
+                    self.encountered_error = Some(ParseWorkerError::IoError(
+                        std::io::Error::new(std::io::ErrorKind::InvalidInput, "Tick has no tick state")
+                    ));
+                    return;
+                }
+            }
+        };
+
+        match result {
+            Ok(res) => {
+                store_result(&mut self.analysis_results, &name, res);
+                self.analysis_error = None;
+            },
+            Err(err) => self.analysis_error = Some(err),
+        }
+        self.last_analysis = Some((name, kind));
+    }
+
+    /// Runs the last analysis again, e.g. after its script was reloaded.
+    pub fn rerun_last_analysis(&mut self, analysis: &AnalysisHost) {
+        if let Some((name, kind)) = self.last_analysis.clone() {
+            self.run_analysis(analysis, name, kind);
         }
     }
 
@@ -374,6 +548,25 @@ impl DemoViewUI {
                 let (rect, _) = ui.allocate_exact_size(egui::Vec2::splat(400.0), egui::Sense::drag());
                 let drawing = dmutex.clone();
                 let tick_data = result.tick_states.get(&self.current_tick_view).cloned();
+                let painter = ui.painter_at(rect);
+
+                // Shapes are painted in the order they're added, so the
+                // heatmap goes in before the players
+                if let Some(map) = &self.heatmap {
+                    let drawing = dmutex.lock();
+                    let size = Vector::new(map.cell_size, map.cell_size, 0.0);
+                    for (i, color) in map.colors().into_iter().enumerate() {
+                        let Some([r, g, b]) = color else {
+                            continue;
+                        };
+                        let corner = map.cell_min(i % map.width, i / map.width);
+                        let cell = egui::Rect::from_two_pos(
+                            drawing.world_to_screen(rect, corner),
+                            drawing.world_to_screen(rect, corner + size),
+                        );
+                        painter.rect_filled(cell, 0.0, egui::Color32::from_rgba_unmultiplied(r, g, b, 140));
+                    }
+                }
         
                 let cb = egui_glow::CallbackFn::new(move |_info, painter| {
                     drawing.lock().buffer_data(painter.gl(), &tick_data);
@@ -386,9 +579,111 @@ impl DemoViewUI {
                 };
         
                 ui.painter().add(callback);
+
+                let drawing = dmutex.lock();
+                let control = self.zone_report.as_ref()
+                    .map(|report| report.control_at(self.current_tick_view))
+                    .unwrap_or_default();
+                for (i, zone) in self.zones.iter().enumerate() {
+                    let color = match control.get(i) {
+                        Some(Team::Red) => egui::Color32::from_rgb(220, 70, 60),
+                        Some(Team::Blue) => egui::Color32::from_rgb(80, 130, 220),
+                        _ => egui::Color32::GRAY,
+                    };
+                    let points = zone.points.iter()
+                        .map(|(x, y)| drawing.world_to_screen(rect, Vector::new(*x, *y, 0.0)))
+                        .collect::<Vec<_>>();
+                    painter.add(egui::Shape::closed_line(points, egui::Stroke::new(2.0, color)));
+                    painter.text(drawing.world_to_screen(rect, zone.center()), egui::Align2::CENTER_CENTER,
+                        &zone.name, egui::FontId::proportional(12.0), color);
+                }
+
+                for (_, analysis_result) in &self.analysis_results {
+                    for marker in analysis_result.markers_at(self.current_tick_view) {
+                        let pos = drawing.world_to_screen(rect, marker.position);
+                        let [r, g, b] = marker.color;
+                        let color = egui::Color32::from_rgb(r, g, b);
+                        painter.circle_stroke(pos, 6.0, egui::Stroke::new(2.0, color));
+                        if !marker.label.is_empty() {
+                            painter.text(pos + egui::vec2(8.0, 0.0), egui::Align2::LEFT_CENTER,
+                                &marker.label, egui::FontId::proportional(12.0), color);
+                        }
+                    }
+                }
             }
         }        
     }
+
+    fn analysis_panel(&mut self, ui: &mut egui::Ui) {
+        ui.heading("Analysis");
+        let mut remove = None;
+        let mut jump_to = None;
+
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (i, (name, result)) in self.analysis_results.iter().enumerate() {
+                egui::CollapsingHeader::new(name.as_str()).default_open(true).show(ui, |ui| {
+                    if result.is_empty() {
+                        ui.label("No results.");
+                    }
+
+                    if !result.metrics.is_empty() {
+                        egui::Grid::new((name, "metrics")).striped(true).show(ui, |ui| {
+                            for (metric, value) in &result.metrics {
+                                ui.label(metric);
+                                ui.label(format!("{:.3}", value));
+                                ui.end_row();
+                            }
+                        });
+                    }
+
+                    for table in &result.tables {
+                        egui::CollapsingHeader::new(format!("{} ({} rows)", table.name, table.rows.len()))
+                            .id_source((name, &table.name))
+                            .show(ui, |ui| {
+                                egui::ScrollArea::both().id_source((name, &table.name)).max_height(300.0).show(ui, |ui| {
+                                    egui::Grid::new((name, &table.name, "grid")).striped(true).show(ui, |ui| {
+                                        for column in &table.columns {
+                                            ui.strong(column);
+                                        }
+                                        ui.end_row();
+                                        for row in &table.rows {
+                                            for cell in row {
+                                                ui.label(cell);
+                                            }
+                                            ui.end_row();
+                                        }
+                                    });
+                                });
+                            });
+                    }
+
+                    for annotation in &result.annotations {
+                        if ui.link(format!("[{}] {}", annotation.tick, annotation.text)).clicked() {
+                            jump_to = Some(annotation.tick);
+                        }
+                    }
+
+                    if ui.button("Clear").clicked() {
+                        remove = Some(i);
+                    }
+                });
+            }
+        });
+
+        if let Some(i) = remove {
+            self.analysis_results.remove(i);
+        }
+        if let Some(tick) = jump_to {
+            self.current_tick_view = tick;
+        }
+    }
+}
+
+fn store_result(results: &mut Vec<(String, AnalysisResult)>, name: &str, result: AnalysisResult) {
+    match results.iter_mut().find(|(n, _)| n == name) {
+        Some((_, existing)) => *existing = result,
+        None => results.push((name.to_string(), result)),
+    }
 }
 
 //use crate::datatransmit::{DataTransmitter, INVALID_INPUT, BIT_ERROR_RET};
@@ -402,19 +697,43 @@ pub struct TemplateApp {
     internal_parse: Option<InternalParseUI>,
 
     draw_mutex: Option<Arc<Mutex<Drawing>>>,
+
+    /// Scripts are loaded when the app starts, and again when they change.
+    analysis_host: AnalysisHost,
+    /// Zones for each map, from the config.
+    zones: HashMap<String, Vec<Zone>>,
+    closed_load_errors: bool,
+    auto_reload: bool,
+    last_reload_check: Option<Instant>,
 }
 
+/// How often the analysis scripts are checked for changes.
+const RELOAD_CHECK_INTERVAL: Duration = Duration::from_millis(500);
+
 impl TemplateApp {
     /// Called once before the first frame.
-    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+    pub fn new(cc: &eframe::CreationContext<'_>, config: &Config) -> Self {
         // This is also where you can customize the look and feel of egui using
         // `cc.egui_ctx.set_visuals` and `cc.egui_ctx.set_fonts`.
         let gl = cc.gl.as_ref().unwrap();
         TemplateApp {
             draw_mutex: Some(Arc::new(Mutex::new(Drawing::new(gl).unwrap()))),
+            analysis_host: AnalysisHost::load(&config.analysis),
+            zones: config.zones.clone(),
+            auto_reload: true,
             ..Default::default()
         }
     }
+
+    fn reload_analysis(&mut self) {
+        self.analysis_host.reload();
+        self.closed_load_errors = false;
+        log::info!("Reloaded analysis scripts");
+
+        if let Some(demoview) = &mut self.demo_view_ui {
+            demoview.rerun_last_analysis(&self.analysis_host);
+        }
+    }
 }
 
 impl eframe::App for TemplateApp {
@@ -439,6 +758,7 @@ impl eframe::App for TemplateApp {
                 OpenDemoResult::FullParse(p) => {
                     self.demo_view_ui = Some(p);
                     self.demo_view_ui.as_mut().unwrap().set_draw_mutex(self.draw_mutex.clone());
+                    self.demo_view_ui.as_mut().unwrap().set_zones(self.zones.clone());
                     self.demo_open_window = None;
                 },
                 OpenDemoResult::Internals(p) => {
@@ -448,6 +768,7 @@ impl eframe::App for TemplateApp {
             }
         }
 
+        let mut reload = false;
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             // The top panel is often a good place for a menu bar:
 
@@ -466,6 +787,12 @@ impl eframe::App for TemplateApp {
                         self.demo_open_window = Some(OpenDemoToParseUI::default());
                         ui.close_menu()
                     }
+
+                    if self.demo_view_ui.is_some() && ui.button("Close").clicked() {
+                        // Dropping the view also stops any parse still running
+                        self.demo_view_ui = None;
+                        ui.close_menu()
+                    }
                     
                     if !is_web {
                         if ui.button("Quit").clicked() {
@@ -473,6 +800,14 @@ impl eframe::App for TemplateApp {
                         }
                     }
                 });
+
+                ui.menu_button("Analysis", |ui| {
+                    if ui.button("Reload Scripts").clicked() {
+                        reload = true;
+                        ui.close_menu()
+                    }
+                    ui.checkbox(&mut self.auto_reload, "Reload On Change");
+                });
                 ui.add_space(16.0);
                 
                 egui::widgets::global_dark_light_mode_buttons(ui);
@@ -494,8 +829,41 @@ impl eframe::App for TemplateApp {
         //     });
         // });
 
+        if self.auto_reload {
+            let now = Instant::now();
+            if self.last_reload_check.map_or(true, |last| now - last >= RELOAD_CHECK_INTERVAL) {
+                self.last_reload_check = Some(now);
+                reload |= self.analysis_host.files_changed();
+            }
+            // Keep checking even when nothing else is happening
+            ctx.request_repaint_after(RELOAD_CHECK_INTERVAL);
+        }
+
+        if reload {
+            self.reload_analysis();
+        }
+
+        if !self.closed_load_errors && !self.analysis_host.load_errors().is_empty() {
+            egui::TopBottomPanel::bottom("analysis_load_error_panel")
+                .resizable(true)
+                .show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.strong("Could Not Load Analysis");
+                        if ui.button("Dismiss").clicked() {
+                            self.closed_load_errors = true;
+                        }
+                    });
+                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        for err in self.analysis_host.load_errors() {
+                            ui.label(egui::RichText::new(err.to_string()).monospace());
+                            ui.separator();
+                        }
+                    });
+                });
+        }
+
         if let Some(demoview) = &mut self.demo_view_ui {
-            demoview.draw_ui(ctx, frame);
+            demoview.draw_ui(ctx, frame, &self.analysis_host);
         }
     }
 
@@ -0,0 +1,919 @@
+//! Generates the Python stubs in `python/tf2dal/` from the Rust source, so
+//! they can't drift from the pyclasses. Run it with `cargo run -p stubgen`
+//! after changing a pyclass.
+//!
+//! Every submodule `lib.rs` registers (through a `get_submod`) gets a stub
+//! with the `#[pyclass]`es and `#[pyfunction]`s of its Rust module, their
+//! `#[pymethods]`, and whatever `py_pickle!`, `py_eq!` and `py_debug_repr!`
+//! add. The `#[pyfn]`s in `lib.rs` make up `tf2dal.pyi`. Doc comments become
+//! the docstrings.
+//!
+//! With `--check`, or `TF2DAL_CHECK_STUBS` set, the stubs are compared
+//! instead of written, and it fails if any of them are stale.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::error::Error;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use proc_macro2::{TokenStream, TokenTree};
+use quote::ToTokens;
+use syn::{Attribute, Expr, ExprLit, FnArg, GenericArgument, ImplItem, Item, Lit, Meta, Pat, PathArguments, ReturnType, Signature, Type};
+
+const STUB_DIR: &str = "python/tf2dal";
+const CHECK_VAR: &str = "TF2DAL_CHECK_STUBS";
+const HEADER: &str = "\
+# Generated by stubgen from the Rust source. Don't edit this file, change
+# the pyclasses or their doc comments and run `cargo run -p stubgen` instead.
+";
+
+/// Lines longer than this get one parameter per line.
+const MAX_LINE: usize = 88;
+
+fn main() -> ExitCode {
+    let check = std::env::args().any(|arg| arg == "--check") || std::env::var_os(CHECK_VAR).is_some();
+    match run(check) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Writes the stubs, or with `check`, fails if any of them are stale.
+fn run(check: bool) -> Result<(), Box<dyn Error>> {
+    // This crate lives in the root of the main one
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().ok_or("stubgen has no parent directory")?;
+    let stubs = generate(root).map_err(|err| format!("could not generate the Python stubs: {}", err))?;
+    let stub_dir = root.join(STUB_DIR);
+
+    let mut stale = Vec::new();
+    for (file, text) in &stubs {
+        let path = stub_dir.join(file);
+        if fs::read_to_string(&path).is_ok_and(|old| old == *text) {
+            continue;
+        }
+        if check {
+            stale.push(file.clone());
+        } else {
+            fs::write(&path, text).map_err(|err| format!("could not write {}: {}", path.display(), err))?;
+            println!("wrote {}/{}", STUB_DIR, file);
+        }
+    }
+
+    // Stubs for modules that aren't registered (anymore)
+    for entry in fs::read_dir(&stub_dir).into_iter().flatten().flatten() {
+        let file = entry.file_name().to_string_lossy().to_string();
+        if file.ends_with(".pyi") && !stubs.contains_key(&file) {
+            if check {
+                stale.push(file);
+            } else {
+                eprintln!("warning: {}/{} is not generated from any module", STUB_DIR, file);
+            }
+        }
+    }
+
+    if !stale.is_empty() {
+        return Err(format!(
+            "the Python stubs are out of date: {}. Run `cargo run -p stubgen` to regenerate them.",
+            stale.join(", ")
+        ).into());
+    }
+    Ok(())
+}
+
+/// File name to contents, for every stub.
+fn generate(root: &Path) -> Result<BTreeMap<String, String>, Box<dyn Error>> {
+    let lib = syn::parse_file(&fs::read_to_string(root.join("src/lib.rs"))?)?;
+    let pymodule = lib.items.iter()
+        .find_map(|item| match item {
+            Item::Fn(f) if has_attr(&f.attrs, "pymodule") => Some(f),
+            _ => None
+        })
+        .ok_or("lib.rs has no #[pymodule]")?;
+
+    // Every class has to be known before any stub is made, for the imports
+    let mut sources = Vec::new();
+    let mut classes = BTreeMap::new();
+    for path in submodule_paths(&pymodule.block.to_token_stream()) {
+        let files = source_files(root, &path)?;
+        let asts = files.iter()
+            .map(|file| Ok(syn::parse_file(&fs::read_to_string(file)?)?))
+            .collect::<Result<Vec<_>, Box<dyn Error>>>()?;
+        let name = submodule_name(&asts[0])
+            .ok_or_else(|| format!("{} has no get_submod", files[0].display()))?;
+        for ast in &asts {
+            for (rust_name, py_name) in pyclass_names(&ast.items) {
+                classes.insert(rust_name, (name.clone(), py_name));
+            }
+        }
+        sources.push((name, asts));
+    }
+
+    let mut stubs = BTreeMap::new();
+    for (name, asts) in &sources {
+        let mut module = Module::new(name, &classes);
+        module.doc = doc(&asts[0].attrs);
+        // Classes first, since their methods can be in any of the files,
+        // and the macro methods last, after the ones written out
+        for ast in asts {
+            module.add_classes(&ast.items);
+        }
+        for ast in asts {
+            module.add_members(&ast.items);
+        }
+        for ast in asts {
+            module.add_macros(&ast.items);
+        }
+        stubs.insert(format!("{}.pyi", name), module.render());
+    }
+
+    let mut top = Module::new("tf2dal", &classes);
+    top.doc = doc(&pymodule.attrs);
+    for stmt in &pymodule.block.stmts {
+        if let syn::Stmt::Item(Item::Fn(f)) = stmt {
+            if has_attr(&f.attrs, "pyfn") {
+                let function = top.function(&f.sig, &f.attrs, None);
+                top.functions.push(function);
+            }
+        }
+    }
+    stubs.insert("tf2dal.pyi".to_string(), top.render());
+
+    Ok(stubs)
+}
+
+/// The paths of the `get_submod`s used in the pymodule, e.g. `types::demo`.
+fn submodule_paths(body: &TokenStream) -> Vec<Vec<String>> {
+    let mut idents = Vec::new();
+    flatten_idents(body.clone(), &mut idents);
+
+    let mut paths = Vec::new();
+    for (i, ident) in idents.iter().enumerate() {
+        if ident != "get_submod" {
+            continue;
+        }
+        // Walk back over `a::b::` to the start of the path
+        let mut start = i;
+        while start >= 3 && idents[start - 1] == ":" && idents[start - 2] == ":" {
+            start -= 3;
+        }
+        let path = (start..i).step_by(3)
+            .map(|j| idents[j].clone())
+            .filter(|segment| segment != "crate")
+            .collect::<Vec<_>>();
+        if !path.is_empty() && !paths.contains(&path) {
+            paths.push(path);
+        }
+    }
+    paths
+}
+
+/// Every ident and punctuation character, in order, ignoring groups.
+fn flatten_idents(tokens: TokenStream, out: &mut Vec<String>) {
+    for tt in tokens {
+        match tt {
+            TokenTree::Group(group) => flatten_idents(group.stream(), out),
+            TokenTree::Ident(ident) => out.push(ident.to_string()),
+            TokenTree::Punct(punct) => out.push(punct.as_char().to_string()),
+            TokenTree::Literal(lit) => out.push(lit.to_string()),
+        }
+    }
+}
+
+/// The file of a Rust module, or for a `mod.rs`, it and every file next to or
+/// below it. The file with the `get_submod` comes first.
+fn source_files(root: &Path, path: &[String]) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    let base = root.join("src").join(path.join("/"));
+    let file = base.with_extension("rs");
+    if file.exists() {
+        return Ok(vec![file]);
+    }
+    let file = base.join("mod.rs");
+    if !file.exists() {
+        return Err(format!("no source file for {}", path.join("::")).into());
+    }
+
+    let mut files = vec![file.clone()];
+    let mut dirs = vec![base];
+    while let Some(dir) = dirs.pop() {
+        let mut entries = fs::read_dir(&dir)?.map(|e| e.map(|e| e.path())).collect::<Result<Vec<_>, _>>()?;
+        entries.sort();
+        for entry in entries {
+            if entry.is_dir() {
+                dirs.push(entry);
+            } else if entry.extension().is_some_and(|ext| ext == "rs") && entry != file {
+                files.push(entry);
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// The name given to `PyModule::new` in the file's `get_submod`.
+fn submodule_name(ast: &syn::File) -> Option<String> {
+    let get_submod = ast.items.iter().find_map(|item| match item {
+        Item::Fn(f) if f.sig.ident == "get_submod" => Some(f),
+        _ => None
+    })?;
+    first_str_literal(get_submod.block.to_token_stream())
+}
+
+fn first_str_literal(tokens: TokenStream) -> Option<String> {
+    tokens.into_iter().find_map(|tt| match tt {
+        TokenTree::Group(group) => first_str_literal(group.stream()),
+        TokenTree::Literal(lit) => syn::parse2::<syn::LitStr>(lit.into_token_stream()).ok().map(|s| s.value()),
+        _ => None
+    })
+}
+
+/// Rust name and Python name of every pyclass.
+fn pyclass_names(items: &[Item]) -> Vec<(String, String)> {
+    items.iter()
+        .filter_map(|item| match item {
+            Item::Struct(s) => Some((&s.ident, &s.attrs)),
+            Item::Enum(e) => Some((&e.ident, &e.attrs)),
+            _ => None
+        })
+        .filter_map(|(ident, attrs)| {
+            let pyclass = find_attr(attrs, "pyclass")?;
+            let py_name = arg_str(&attr_args(pyclass), "name").unwrap_or_else(|| ident.to_string());
+            Some((ident.to_string(), py_name))
+        })
+        .collect()
+}
+
+/////////////////////////////////////////////
+// Attributes
+/////////////////////////////////////////////
+
+fn find_attr<'a>(attrs: &'a [Attribute], name: &str) -> Option<&'a Attribute> {
+    attrs.iter().find(|attr| attr.path().segments.last().is_some_and(|s| s.ident == name))
+}
+
+fn has_attr(attrs: &[Attribute], name: &str) -> bool {
+    find_attr(attrs, name).is_some()
+}
+
+type AttrArgs = Vec<(String, Vec<TokenTree>)>;
+
+fn split_commas(tokens: TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut parts = vec![Vec::new()];
+    for tt in tokens {
+        match &tt {
+            TokenTree::Punct(p) if p.as_char() == ',' => parts.push(Vec::new()),
+            _ => parts.last_mut().unwrap().push(tt)
+        }
+    }
+    parts.retain(|part| !part.is_empty());
+    parts
+}
+
+/// `#[attr(flag, key = value)]` as `[("flag", []), ("key", [value])]`.
+fn attr_args(attr: &Attribute) -> AttrArgs {
+    let Meta::List(list) = &attr.meta else {
+        return Vec::new();
+    };
+    split_commas(list.tokens.clone())
+        .into_iter()
+        .map(|part| {
+            let key = part[0].to_string();
+            let value = part.into_iter()
+                .skip(1)
+                .skip_while(|tt| matches!(tt, TokenTree::Punct(p) if p.as_char() == '='))
+                .collect();
+            (key, value)
+        })
+        .collect()
+}
+
+/// The args of every attribute with this name, e.g. all the `#[pyo3(...)]`s.
+fn all_attr_args(attrs: &[Attribute], name: &str) -> AttrArgs {
+    attrs.iter()
+        .filter(|attr| attr.path().is_ident(name))
+        .flat_map(attr_args)
+        .collect()
+}
+
+fn has_arg(args: &AttrArgs, key: &str) -> bool {
+    args.iter().any(|(k, _)| k == key)
+}
+
+fn arg_value<'a>(args: &'a AttrArgs, key: &str) -> Option<&'a [TokenTree]> {
+    args.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_slice())
+}
+
+fn arg_str(args: &AttrArgs, key: &str) -> Option<String> {
+    let value = arg_value(args, key)?;
+    syn::parse2::<syn::LitStr>(value.iter().cloned().collect()).ok().map(|s| s.value())
+}
+
+/// The defaults in `#[pyo3(signature = (a, b=1))]`, as Python.
+fn signature_defaults(args: &AttrArgs) -> BTreeMap<String, String> {
+    let mut defaults = BTreeMap::new();
+    let Some([TokenTree::Group(group)]) = arg_value(args, "signature") else {
+        return defaults;
+    };
+
+    for part in split_commas(group.stream()) {
+        if part.len() > 2 {
+            let rust = part[2..].iter().map(|tt| tt.to_string()).collect::<String>();
+            defaults.insert(part[0].to_string(), py_default(&rust));
+        }
+    }
+    defaults
+}
+
+fn py_default(rust: &str) -> String {
+    match rust {
+        "None" => "None".to_string(),
+        "true" => "True".to_string(),
+        "false" => "False".to_string(),
+        _ if rust.parse::<f64>().is_ok() || rust.starts_with('"') => rust.to_string(),
+        // Enum variants, e.g. `Team::Red`
+        _ if rust.split("::").count() == 2 && rust.split("::").all(is_ident) => rust.replace("::", "."),
+        _ => "...".to_string()
+    }
+}
+
+fn is_ident(s: &str) -> bool {
+    s.chars().next().is_some_and(|c| c.is_alphabetic() || c == '_')
+        && s.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Doc comments, without the leading space rustdoc leaves on each line.
+/// Section banners (`/// NAME` over `/// /////`) are left out.
+fn doc(attrs: &[Attribute]) -> Option<String> {
+    let lines = attrs.iter()
+        .filter(|attr| attr.path().is_ident("doc"))
+        .filter_map(|attr| match &attr.meta {
+            Meta::NameValue(nv) => match &nv.value {
+                Expr::Lit(ExprLit { lit: Lit::Str(s), .. }) => Some(s.value()),
+                _ => None
+            },
+            _ => None
+        })
+        .collect::<Vec<_>>();
+
+    let lines = lines.iter()
+        .map(|line| line.strip_prefix(' ').unwrap_or(line).trim_end())
+        .collect::<Vec<_>>();
+    let banner_end = lines.iter()
+        .rposition(|line| !line.is_empty() && line.trim().chars().all(|c| c == '/'))
+        .map_or(0, |end| end + 1);
+    let text = lines[banner_end..].join("\n");
+    let text = text.trim();
+    (!text.is_empty()).then(|| text.to_string())
+}
+
+/////////////////////////////////////////////
+// Stub contents
+/////////////////////////////////////////////
+
+struct Module<'a> {
+    name: String,
+    doc: Option<String>,
+    classes: Vec<Class>,
+    functions: Vec<Function>,
+
+    /// Rust name to (module, Python name) of every pyclass.
+    known: &'a BTreeMap<String, (String, String)>,
+    /// Module to the names imported from it.
+    imports: BTreeMap<String, BTreeSet<String>>,
+}
+
+struct Class {
+    rust_name: String,
+    name: String,
+    doc: Option<String>,
+    variants: Vec<(String, Option<String>)>,
+    properties: Vec<Property>,
+    methods: Vec<Function>,
+}
+
+struct Property {
+    name: String,
+    ty: String,
+    doc: Option<String>,
+    settable: bool,
+}
+
+#[derive(PartialEq)]
+enum FnKind {
+    Function,
+    Method,
+    Static,
+    Class,
+}
+
+struct Function {
+    name: String,
+    doc: Option<String>,
+    kind: FnKind,
+    params: Vec<(String, String, Option<String>)>,
+    ret: String,
+}
+
+impl Function {
+    fn new(name: &str, params: &[(&str, &str)], ret: &str) -> Self {
+        Function {
+            name: name.to_string(),
+            doc: None,
+            kind: FnKind::Method,
+            params: params.iter().map(|(n, t)| (n.to_string(), t.to_string(), None)).collect(),
+            ret: ret.to_string(),
+        }
+    }
+}
+
+impl<'a> Module<'a> {
+    fn new(name: &str, known: &'a BTreeMap<String, (String, String)>) -> Self {
+        Module {
+            name: name.to_string(),
+            doc: None,
+            classes: Vec::new(),
+            functions: Vec::new(),
+            known,
+            imports: BTreeMap::new(),
+        }
+    }
+
+    fn import(&mut self, module: &str, name: &str) {
+        self.imports.entry(module.to_string()).or_default().insert(name.to_string());
+    }
+
+    fn class_mut(&mut self, rust_name: &str) -> Option<&mut Class> {
+        self.classes.iter_mut().find(|c| c.rust_name == rust_name)
+    }
+
+    fn add_classes(&mut self, items: &[Item]) {
+        for item in items {
+            match item {
+                Item::Struct(s) if has_attr(&s.attrs, "pyclass") => {
+                    let class = self.struct_class(s);
+                    self.classes.push(class);
+                },
+                Item::Enum(e) if has_attr(&e.attrs, "pyclass") => {
+                    let class = self.enum_class(e);
+                    self.classes.push(class);
+                },
+                _ => {}
+            }
+        }
+    }
+
+    fn add_members(&mut self, items: &[Item]) {
+        for item in items {
+            match item {
+                Item::Impl(imp) if has_attr(&imp.attrs, "pymethods") => {
+                    self.add_pymethods(imp);
+                },
+                Item::Fn(f) if has_attr(&f.attrs, "pyfunction") => {
+                    let function = self.function(&f.sig, &f.attrs, None);
+                    self.functions.push(function);
+                },
+                _ => {}
+            }
+        }
+    }
+
+    fn add_macros(&mut self, items: &[Item]) {
+        for item in items {
+            if let Item::Macro(mac) = item {
+                self.add_macro(mac);
+            }
+        }
+    }
+
+    fn new_class(&self, ident: &syn::Ident, attrs: &[Attribute]) -> Class {
+        let rust_name = ident.to_string();
+        Class {
+            name: self.known.get(&rust_name).map_or(rust_name.clone(), |(_, name)| name.clone()),
+            rust_name,
+            doc: doc(attrs),
+            variants: Vec::new(),
+            properties: Vec::new(),
+            methods: Vec::new(),
+        }
+    }
+
+    fn struct_class(&mut self, s: &syn::ItemStruct) -> Class {
+        let mut class = self.new_class(&s.ident, &s.attrs);
+        let pyclass = attr_args(find_attr(&s.attrs, "pyclass").unwrap());
+        let get_all = has_arg(&pyclass, "get_all");
+        let set_all = has_arg(&pyclass, "set_all");
+
+        for field in &s.fields {
+            let Some(ident) = &field.ident else {
+                continue;
+            };
+            let args = all_attr_args(&field.attrs, "pyo3");
+            if !get_all && !has_arg(&args, "get") {
+                continue;
+            }
+            let ty = self.py_type(&field.ty, &class.name);
+            class.properties.push(Property {
+                name: arg_str(&args, "name").unwrap_or_else(|| ident.to_string()),
+                ty,
+                doc: doc(&field.attrs),
+                settable: set_all || has_arg(&args, "set"),
+            });
+        }
+        class
+    }
+
+    fn enum_class(&mut self, e: &syn::ItemEnum) -> Class {
+        let mut class = self.new_class(&e.ident, &e.attrs);
+        class.variants = e.variants.iter()
+            .map(|v| (v.ident.to_string(), doc(&v.attrs)))
+            .collect();
+        class
+    }
+
+    fn add_pymethods(&mut self, imp: &syn::ItemImpl) {
+        let Type::Path(self_ty) = &*imp.self_ty else {
+            return;
+        };
+        let rust_name = self_ty.path.segments.last().unwrap().ident.to_string();
+        let Some(class_name) = self.known.get(&rust_name).map(|(_, name)| name.clone()) else {
+            return;
+        };
+
+        for item in &imp.items {
+            let ImplItem::Fn(f) = item else {
+                continue;
+            };
+
+            if let Some(getter) = find_attr(&f.attrs, "getter") {
+                let name = accessor_name(getter, &f.sig.ident.to_string(), "get_");
+                let ty = self.return_type(&f.sig, &name, &class_name);
+                let doc = doc(&f.attrs);
+                let class = self.class_mut(&rust_name).unwrap();
+                class.properties.retain(|p| p.name != name);
+                class.properties.push(Property { name, ty, doc, settable: false });
+            } else if let Some(setter) = find_attr(&f.attrs, "setter") {
+                let name = accessor_name(setter, &f.sig.ident.to_string(), "set_");
+                let class = self.class_mut(&rust_name).unwrap();
+                if let Some(property) = class.properties.iter_mut().find(|p| p.name == name) {
+                    property.settable = true;
+                }
+            } else if f.sig.ident == "__richcmp__" {
+                let class = self.class_mut(&rust_name).unwrap();
+                for op in ["__eq__", "__ne__", "__lt__", "__le__", "__gt__", "__ge__"] {
+                    class.methods.push(Function::new(op, &[("other", "object")], "bool"));
+                }
+            } else {
+                let function = self.function(&f.sig, &f.attrs, Some(&class_name));
+                self.class_mut(&rust_name).unwrap().methods.push(function);
+            }
+        }
+    }
+
+    /// What the macros in types/mod.rs add.
+    fn add_macro(&mut self, mac: &syn::ItemMacro) {
+        let Some(name) = mac.mac.path.segments.last().map(|s| s.ident.to_string()) else {
+            return;
+        };
+        let targets = split_commas(mac.mac.tokens.clone())
+            .into_iter()
+            .map(|part| part.iter().map(|tt| tt.to_string()).collect::<String>())
+            .collect::<Vec<_>>();
+
+        for rust_name in targets {
+            let Some(class) = self.class_mut(&rust_name) else {
+                continue;
+            };
+            let this = class.name.clone();
+            match name.as_str() {
+                "py_pickle" => {
+                    class.methods.push(Function::new("__reduce__", &[], "Tuple[Any, Tuple[bytes]]"));
+                    class.methods.push(Function::new("__copy__", &[], &this));
+                    class.methods.push(Function::new("__deepcopy__", &[("memo", "Any")], &this));
+                    self.import("typing", "Any");
+                    self.import("typing", "Tuple");
+                },
+                "py_eq" => {
+                    class.methods.push(Function::new("__eq__", &[("other", "object")], "bool"));
+                    class.methods.push(Function::new("__ne__", &[("other", "object")], "bool"));
+                },
+                "py_debug_repr" => {
+                    class.methods.push(Function::new("__repr__", &[], "str"));
+                },
+                _ => {}
+            }
+        }
+    }
+
+    fn function(&mut self, sig: &Signature, attrs: &[Attribute], class: Option<&str>) -> Function {
+        let pyo3 = all_attr_args(attrs, "pyo3");
+        let defaults = signature_defaults(&pyo3);
+        let this = class.unwrap_or_default();
+
+        let (name, kind) = if class.is_none() {
+            (sig.ident.to_string(), FnKind::Function)
+        } else if has_attr(attrs, "new") {
+            ("__init__".to_string(), FnKind::Method)
+        } else if has_attr(attrs, "staticmethod") {
+            (sig.ident.to_string(), FnKind::Static)
+        } else if has_attr(attrs, "classmethod") {
+            (sig.ident.to_string(), FnKind::Class)
+        } else {
+            (sig.ident.to_string(), FnKind::Method)
+        };
+        let name = arg_str(&pyo3, "name").unwrap_or(name);
+
+        let mut params = Vec::new();
+        // `slf: PyRef<Self>` and `cls: &PyType` aren't passed from Python
+        let has_receiver = sig.inputs.iter().any(|arg| matches!(arg, FnArg::Receiver(_)));
+        let mut skip_first = kind == FnKind::Class
+            || (kind == FnKind::Method && name != "__init__" && !has_receiver);
+        for arg in &sig.inputs {
+            let FnArg::Typed(arg) = arg else {
+                continue;
+            };
+            if is_python_token(&arg.ty) {
+                continue;
+            }
+            if std::mem::take(&mut skip_first) {
+                continue;
+            }
+            let param = match &*arg.pat {
+                Pat::Ident(ident) => ident.ident.to_string(),
+                other => other.to_token_stream().to_string(),
+            };
+            let ty = self.py_type(&arg.ty, this);
+            let default = defaults.get(&param).cloned();
+            params.push((param, ty, default));
+        }
+
+        let ret = match name.as_str() {
+            "__init__" => "None".to_string(),
+            // Returning None is how pyo3 stops iteration
+            "__next__" => {
+                let ret = self.return_type(sig, &name, this);
+                ret.strip_suffix(" | None").map_or(ret.clone(), str::to_string)
+            },
+            _ => self.return_type(sig, &name, this)
+        };
+
+        Function { name, doc: doc(attrs), kind, params, ret }
+    }
+
+    fn return_type(&mut self, sig: &Signature, name: &str, this: &str) -> String {
+        match &sig.output {
+            ReturnType::Default => "None".to_string(),
+            // Only pandas knows what a `&PyAny` from these is
+            ReturnType::Type(_, ty) => match self.py_type(ty, this) {
+                ty if ty == "Any" && name.ends_with("dataframe") => {
+                    self.import("pandas", "DataFrame");
+                    "DataFrame".to_string()
+                },
+                ty => ty
+            }
+        }
+    }
+
+    fn py_type(&mut self, ty: &Type, this: &str) -> String {
+        match ty {
+            Type::Reference(r) => self.py_type(&r.elem, this),
+            Type::Paren(p) => self.py_type(&p.elem, this),
+            Type::Group(g) => self.py_type(&g.elem, this),
+            Type::Tuple(t) if t.elems.is_empty() => "None".to_string(),
+            Type::Tuple(t) => {
+                let elems = t.elems.iter().map(|e| self.py_type(e, this)).collect::<Vec<_>>();
+                self.import("typing", "Tuple");
+                format!("Tuple[{}]", elems.join(", "))
+            },
+            Type::Array(a) => self.generic("List", &[&a.elem], this),
+            Type::Slice(s) => self.generic("List", &[&s.elem], this),
+            Type::Path(p) => {
+                let segment = p.path.segments.last().unwrap();
+                let args = match &segment.arguments {
+                    PathArguments::AngleBracketed(args) => args.args.iter()
+                        .filter_map(|arg| match arg {
+                            GenericArgument::Type(ty) => Some(ty),
+                            _ => None
+                        })
+                        .collect(),
+                    _ => Vec::new()
+                };
+                self.path_type(&segment.ident.to_string(), &args, this)
+            },
+            _ => self.any()
+        }
+    }
+
+    fn path_type(&mut self, ident: &str, args: &[&Type], this: &str) -> String {
+        match ident {
+            "bool" => "bool".to_string(),
+            "u8" | "u16" | "u32" | "u64" | "u128" | "usize"
+            | "i8" | "i16" | "i32" | "i64" | "i128" | "isize" => "int".to_string(),
+            "f32" | "f64" => "float".to_string(),
+            "String" | "str" | "char" | "PathBuf" | "Path" | "PyString" => "str".to_string(),
+            "DemoTick" | "EntityId" | "UserId" => "int".to_string(),
+            "PyBytes" => "bytes".to_string(),
+            "PyType" => "type".to_string(),
+            "PyObject" | "PyAny" => self.any(),
+            "Self" => this.to_string(),
+            "Option" if args.len() == 1 => format!("{} | None", self.py_type(args[0], this)),
+            "Vec" | "VecDeque" => self.generic("List", args, this),
+            "HashSet" | "BTreeSet" => self.generic("Set", args, this),
+            "HashMap" | "BTreeMap" => self.generic("Dict", args, this),
+            "PyResult" | "Result" | "Py" | "PyRef" | "PyRefMut" | "PyCell" | "Box" if !args.is_empty() => {
+                self.py_type(args[0], this)
+            },
+            "PyArray1" | "PyArray2" | "PyArray3" | "PyArrayDyn" if args.len() == 1 => {
+                let dtype = match args[0].to_token_stream().to_string().as_str() {
+                    "bool" => "bool_".to_string(),
+                    "f32" => "float32".to_string(),
+                    "f64" => "float64".to_string(),
+                    int @ ("u8" | "u16" | "u32" | "u64" | "i8" | "i16" | "i32" | "i64") => {
+                        format!("{}int{}", if int.starts_with('u') { "u" } else { "" }, &int[1..])
+                    },
+                    _ => "Any".to_string(),
+                };
+                self.import("numpy.typing", "NDArray");
+                if dtype == "Any" {
+                    self.import("typing", "Any");
+                } else {
+                    self.import("numpy", &dtype);
+                }
+                format!("NDArray[{}]", dtype)
+            },
+            class => match self.known.get(class).cloned() {
+                Some((module, name)) => {
+                    if module != self.name {
+                        self.import(&format!(".{}", module), &name);
+                    }
+                    name
+                },
+                None => self.any()
+            }
+        }
+    }
+
+    fn generic(&mut self, name: &str, args: &[&Type], this: &str) -> String {
+        self.import("typing", name);
+        let args = args.iter().map(|ty| self.py_type(ty, this)).collect::<Vec<_>>();
+        format!("{}[{}]", name, args.join(", "))
+    }
+
+    fn any(&mut self) -> String {
+        self.import("typing", "Any");
+        "Any".to_string()
+    }
+
+    fn render(&mut self) -> String {
+        if self.classes.iter().any(|c| !c.variants.is_empty()) {
+            self.import("typing", "ClassVar");
+        }
+
+        let mut out = String::from(HEADER);
+        out.push('\n');
+        if let Some(doc) = &self.doc {
+            out.push_str(&docstring(doc, ""));
+            out.push_str("\n\n");
+        }
+
+        // Packages first, then the other submodules
+        let (relative, packages): (Vec<_>, Vec<_>) = self.imports.iter().partition(|(m, _)| m.starts_with('.'));
+        for (module, names) in packages.iter().chain(&relative) {
+            let names = names.iter().cloned().collect::<Vec<_>>().join(", ");
+            let _ = writeln!(out, "from {} import {}", module, names);
+        }
+
+        for function in &self.functions {
+            out.push('\n');
+            render_function(&mut out, function, "");
+        }
+
+        for class in &self.classes {
+            out.push_str("\n\n");
+            render_class(&mut out, class);
+        }
+
+        out
+    }
+}
+
+fn accessor_name(attr: &Attribute, fn_name: &str, prefix: &str) -> String {
+    match &attr.meta {
+        Meta::List(list) => list.tokens.to_string(),
+        _ => fn_name.strip_prefix(prefix).unwrap_or(fn_name).to_string()
+    }
+}
+
+fn is_python_token(ty: &Type) -> bool {
+    match ty {
+        Type::Path(p) => p.path.segments.last().is_some_and(|s| s.ident == "Python"),
+        _ => false
+    }
+}
+
+fn docstring(doc: &str, indent: &str) -> String {
+    let doc = doc.replace('\\', "\\\\").replace("\"\"\"", "\\\"\\\"\\\"");
+    let lines = doc.lines()
+        .map(|line| if line.is_empty() { String::new() } else { format!("{}{}", indent, line) })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("{}\"\"\"{}\"\"\"", indent, &lines[indent.len()..])
+}
+
+fn render_class(out: &mut String, class: &Class) {
+    let mut members = Vec::new();
+
+    for (variant, doc) in &class.variants {
+        let mut member = format!("    {}: ClassVar[{}]", variant, class.name);
+        if let Some(doc) = doc {
+            member.push('\n');
+            member.push_str(&docstring(doc, "    "));
+        }
+        members.push(member);
+    }
+
+    for property in &class.properties {
+        let mut member = String::from("    @property\n");
+        render_function(&mut member, &Function {
+            name: property.name.clone(),
+            doc: property.doc.clone(),
+            kind: FnKind::Method,
+            params: Vec::new(),
+            ret: property.ty.clone(),
+        }, "    ");
+        if property.settable {
+            let _ = writeln!(member, "    @{}.setter", property.name);
+            render_function(&mut member, &Function::new(&property.name, &[("value", &property.ty)], "None"), "    ");
+        }
+        members.push(member.trim_end().to_string());
+    }
+
+    for method in &class.methods {
+        let mut member = String::new();
+        render_function(&mut member, method, "    ");
+        members.push(member.trim_end().to_string());
+    }
+
+    let _ = writeln!(out, "class {}:", class.name);
+    match &class.doc {
+        Some(doc) => {
+            let _ = writeln!(out, "{}", docstring(doc, "    "));
+            if !members.is_empty() {
+                out.push('\n');
+            }
+        },
+        None if members.is_empty() => out.push_str("    ...\n"),
+        None => {}
+    }
+
+    // One-liners are kept together, anything longer gets a blank line around it
+    for (i, member) in members.iter().enumerate() {
+        if i > 0 {
+            let one_liners = !member.contains('\n') && !members[i - 1].contains('\n');
+            out.push_str(if one_liners { "\n" } else { "\n\n" });
+        }
+        out.push_str(member);
+    }
+    if !members.is_empty() {
+        out.push('\n');
+    }
+}
+
+fn render_function(out: &mut String, function: &Function, indent: &str) {
+    let mut params = match function.kind {
+        FnKind::Method => vec!["self".to_string()],
+        FnKind::Class => vec!["cls".to_string()],
+        FnKind::Static | FnKind::Function => Vec::new(),
+    };
+    for (name, ty, default) in &function.params {
+        params.push(match default {
+            Some(default) => format!("{}: {} = {}", name, ty, default),
+            None => format!("{}: {}", name, ty),
+        });
+    }
+
+    match function.kind {
+        FnKind::Static => { let _ = writeln!(out, "{}@staticmethod", indent); },
+        FnKind::Class => { let _ = writeln!(out, "{}@classmethod", indent); },
+        _ => {}
+    }
+
+    let mut line = format!("{}def {}({}) -> {}:", indent, function.name, params.join(", "), function.ret);
+    if line.len() > MAX_LINE {
+        let inner = format!("{}    ", indent);
+        let params = params.iter().map(|p| format!("{}{},\n", inner, p)).collect::<String>();
+        line = format!("{}def {}(\n{}{}) -> {}:", indent, function.name, params, indent, function.ret);
+    }
+
+    match &function.doc {
+        Some(doc) => {
+            let inner = format!("{}    ", indent);
+            let _ = writeln!(out, "{}\n{}\n{}...", line, docstring(doc, &inner), inner);
+        },
+        None => {
+            let _ = writeln!(out, "{} ...", line);
+        }
+    }
+}